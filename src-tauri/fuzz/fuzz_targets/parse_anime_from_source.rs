@@ -0,0 +1,13 @@
+#![no_main]
+
+use kioku_lib::services::player_detection::fuzzing::parse_anime_from_source;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &str| {
+    if let Some(release) = parse_anime_from_source(data) {
+        assert!(!release.anime_title.trim().is_empty());
+        if let (Some(start), Some(end)) = (release.episode, release.episode_end) {
+            assert!(start <= end);
+        }
+    }
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use kioku_lib::services::player_detection::fuzzing::split_command_line;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &str| {
+    for arg in split_command_line(data) {
+        assert!(data.len() >= arg.len());
+    }
+});
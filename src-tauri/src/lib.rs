@@ -27,7 +27,11 @@ use crate::autostart::{is_auto_start_enabled, set_auto_start_enabled, sync_auto_
 use crate::services::anilist::{
     fetch_anilist_user_info, search_anilist_media, synchronize_anilist,
 };
-use crate::services::anime_list_updates::{enqueue_anime_list_update, AnimeListUpdateQueue};
+use crate::services::anime_list_updates::{
+    configure_anime_list_updates, discard_dead_letter_anime_list_update, enqueue_anime_list_update,
+    get_anime_list_update_queue, list_dead_letter_anime_list_updates,
+    resubmit_dead_letter_anime_list_update, AnimeListUpdateQueue, ListUpdatesConfig,
+};
 use crate::services::discord_rpc::{
     clear_discord_presence, configure_discord_rpc, set_discord_presence, DiscordRpcState,
 };
@@ -35,8 +39,15 @@ use crate::services::myanimelist::{
     fetch_myanimelist_user_info, search_myanimelist_media, synchronize_myanimelist,
 };
 use crate::services::player_detection::{
-    configure_playback_observer, detect_playing_anime, get_playback_observer_state,
-    start_playback_observer, PlaybackObserverState, SupportedPlayer,
+    add_title_alias, configure_playback_observer, delete_title_alias, detect_playing_anime,
+    export_title_aliases, get_playback_observer_state, import_title_aliases, list_title_aliases,
+    parse_release_name, start_playback_observer, AnimeRelationsState, CustomPlayerDefinition,
+    DetectionSelectionConfig, DetectionSourcesConfig, DetectionStabilityConfig,
+    PlaybackObserverState, SupportedPlayer, TitleAliasState,
+};
+use crate::services::scrobbler::{
+    configure_scrobbler, confirm_scrobble, dismiss_scrobble, get_scrobbler_state, ScrobblerConfig,
+    ScrobblerState,
 };
 
 #[derive(Debug, Deserialize, Default)]
@@ -46,6 +57,8 @@ struct StoredConfigurationState {
     detection: StoredDetectionConfig,
     #[serde(default)]
     application: StoredApplicationConfig,
+    #[serde(default)]
+    list_updates: ListUpdatesConfig,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -55,6 +68,16 @@ struct StoredDetectionConfig {
     player_detection_enabled: bool,
     #[serde(default)]
     enabled_players: Vec<SupportedPlayer>,
+    #[serde(default)]
+    sources: DetectionSourcesConfig,
+    #[serde(default)]
+    custom_players: Vec<CustomPlayerDefinition>,
+    #[serde(default)]
+    selection: DetectionSelectionConfig,
+    #[serde(default)]
+    stability: DetectionStabilityConfig,
+    #[serde(default)]
+    scrobbler: ScrobblerConfig,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -281,12 +304,24 @@ pub fn run() {
                 return Err(std::io::Error::new(std::io::ErrorKind::Other, err).into());
             }
 
-            app.manage(AnimeListUpdateQueue::new(app.handle().clone()));
-            let observer_config = bootstrap_config.detection;
-            app.manage(PlaybackObserverState::new(
-                observer_config.player_detection_enabled,
-                observer_config.enabled_players,
+            app.manage(AnimeListUpdateQueue::new(
+                app.handle().clone(),
+                bootstrap_config.list_updates,
             ));
+            let observer_config = bootstrap_config.detection;
+            app.manage(ScrobblerState::new(observer_config.scrobbler));
+            app.manage(TitleAliasState::load(app.handle()));
+            app.manage(AnimeRelationsState::load(app.handle()));
+            app.manage(
+                PlaybackObserverState::new(
+                    observer_config.player_detection_enabled,
+                    observer_config.enabled_players,
+                    observer_config.sources,
+                    observer_config.custom_players,
+                    observer_config.selection,
+                )
+                .with_stability(observer_config.stability),
+            );
 
             if observer_config.player_detection_enabled {
                 start_playback_observer(app.handle().clone());
@@ -309,9 +344,24 @@ pub fn run() {
             synchronize_myanimelist,
             synchronize_anilist,
             enqueue_anime_list_update,
+            configure_anime_list_updates,
+            get_anime_list_update_queue,
+            list_dead_letter_anime_list_updates,
+            resubmit_dead_letter_anime_list_update,
+            discard_dead_letter_anime_list_update,
             detect_playing_anime,
             get_playback_observer_state,
             configure_playback_observer,
+            parse_release_name,
+            list_title_aliases,
+            add_title_alias,
+            delete_title_alias,
+            import_title_aliases,
+            export_title_aliases,
+            get_scrobbler_state,
+            configure_scrobbler,
+            confirm_scrobble,
+            dismiss_scrobble,
             configure_discord_rpc,
             set_discord_presence,
             clear_discord_presence
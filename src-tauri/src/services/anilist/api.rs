@@ -6,20 +6,26 @@ use tauri_plugin_zustand::ManagerExt;
 
 use crate::auth::anilist::PROVIDER_ID as ANILIST_PROVIDER_ID;
 use crate::auth::token_manager::get_access_token;
-use crate::services::anime_list_updates::{AnimeListUpdateRequest, ListType};
+use crate::services::anime_list_updates::{
+    retry_after_header, AnimeListUpdateRequest, ListType, ListUpdateError,
+};
 
 use super::mapping::{
-    map_anilist_statistics, map_anime_to_domain, map_manga_to_domain, map_user_status_to_anilist,
-    parse_fuzzy_date_input,
+    map_anilist_statistics, map_anime_to_domain, map_manga_to_domain, map_media_relations,
+    map_user_status_to_anilist, parse_fuzzy_date_input,
 };
 use super::{
-    AniListCollection, AniListSearchPage, AniListSearchResult, AniListUserInfo, GraphQlError,
-    GraphQlRequest, GraphQlResponse, GraphQlVariables, SaveMediaListEntryMutationResponse,
-    SaveMediaListEntryRequest, SaveMediaListEntryVariables, SearchMediaRequest,
-    SearchMediaResponse, SearchMediaVariables, SynchronizedAnimeList, SynchronizedListResult,
-    SynchronizedMangaList, UserStatusKey, ViewerRequest, ViewerResponse, GRAPHQL_URL,
-    MEDIA_LIST_COLLECTION_QUERY, MEDIA_TYPE_ANIME, MEDIA_TYPE_MANGA, REQUEST_TIMEOUT_SECS,
-    SAVE_MEDIA_LIST_ENTRY_MUTATION, SEARCH_LIMIT_MAX, SEARCH_MEDIA_QUERY, VIEWER_QUERY,
+    AniListCollection, AniListMediaLookup, AniListMediaMapping, AniListMediaRelations,
+    AniListRelationMedia, AniListSearchPage, AniListSearchResult, AniListUserInfo, GraphQlError,
+    GraphQlRequest, GraphQlResponse, GraphQlVariables, MediaIdMappingRequest,
+    MediaIdMappingResponse, MediaIdMappingVariables, MediaRelationsRequest, MediaRelationsResponse,
+    MediaRelationsVariables, SaveMediaListEntryMutationResponse, SaveMediaListEntryRequest,
+    SaveMediaListEntryVariables, SearchMediaRequest, SearchMediaResponse, SearchMediaVariables,
+    SynchronizedAnimeList, SynchronizedListResult, SynchronizedMangaList, UserStatusKey,
+    ViewerRequest, ViewerResponse, GRAPHQL_URL, MEDIA_ID_MAPPING_QUERY,
+    MEDIA_LIST_COLLECTION_QUERY, MEDIA_RELATIONS_QUERY, MEDIA_TYPE_ANIME, MEDIA_TYPE_MANGA,
+    REQUEST_TIMEOUT_SECS, SAVE_MEDIA_LIST_ENTRY_MUTATION, SEARCH_LIMIT_MAX, SEARCH_MEDIA_QUERY,
+    VIEWER_QUERY,
 };
 
 fn map_graphql_errors(errors: Option<Vec<GraphQlError>>) -> Result<(), String> {
@@ -218,10 +224,63 @@ fn parse_search_response(
         .ok_or_else(|| "AniList response missing Page".to_string())
 }
 
+fn parse_media_relations_response(
+    status: reqwest::StatusCode,
+    body: &str,
+) -> Result<AniListRelationMedia, String> {
+    if !status.is_success() {
+        return Err(format!("AniList request failed: {} - {}", status, body));
+    }
+
+    let parsed: MediaRelationsResponse =
+        serde_json::from_str(body).map_err(|e| format!("Failed to parse AniList response: {e}"))?;
+
+    map_graphql_errors(parsed.errors)?;
+
+    parsed
+        .data
+        .and_then(|data| data.media)
+        .ok_or_else(|| "AniList response missing Media".to_string())
+}
+
+fn parse_media_id_mapping_response(
+    status: reqwest::StatusCode,
+    body: &str,
+) -> Result<AniListMediaMapping, String> {
+    if !status.is_success() {
+        return Err(format!("AniList request failed: {} - {}", status, body));
+    }
+
+    let parsed: MediaIdMappingResponse =
+        serde_json::from_str(body).map_err(|e| format!("Failed to parse AniList response: {e}"))?;
+
+    map_graphql_errors(parsed.errors)?;
+
+    let Some(data) = parsed.data else {
+        return Err("AniList response missing data".to_string());
+    };
+    let media = data
+        .media
+        .ok_or_else(|| "AniList response missing Media".to_string())?;
+    let score_format = data
+        .viewer
+        .and_then(|viewer| viewer.media_list_options)
+        .and_then(|options| options.score_format)
+        .unwrap_or_default();
+
+    Ok(AniListMediaMapping {
+        media_id: media.id,
+        mal_id: media.id_mal,
+        score_format,
+    })
+}
+
+// Returns the media id of the saved entry, which requests that only carry an
+// entry id do not know up front.
 fn parse_save_media_list_entry_response(
     status: reqwest::StatusCode,
     body: &str,
-) -> Result<(), String> {
+) -> Result<Option<u64>, String> {
     if !status.is_success() {
         return Err(format!("AniList update failed: {} - {}", status, body));
     }
@@ -243,7 +302,7 @@ fn parse_save_media_list_entry_response(
         return Err("AniList update returned an invalid entry id".to_string());
     }
 
-    Ok(())
+    Ok(saved_entry.media_id)
 }
 
 async fn fetch_collection(
@@ -339,6 +398,33 @@ async fn fetch_search_media(
     parse_search_response(status, &body)
 }
 
+async fn fetch_media_relations(
+    client: &reqwest::Client,
+    token: &str,
+    media_id: u64,
+) -> Result<AniListRelationMedia, String> {
+    let request = MediaRelationsRequest {
+        query: MEDIA_RELATIONS_QUERY,
+        variables: MediaRelationsVariables { id: media_id },
+    };
+
+    let response = client
+        .post(GRAPHQL_URL)
+        .bearer_auth(token)
+        .json(&request)
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .send()
+        .await
+        .map_err(|e| format_transport_error("AniList relations request failed", &e))?;
+    let status = response.status();
+
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format_transport_error("AniList relations response read failed", &e))?;
+    parse_media_relations_response(status, &body)
+}
+
 #[tauri::command]
 pub async fn fetch_anilist_user_info(app: tauri::AppHandle) -> Result<AniListUserInfo, String> {
     let token = get_access_token(&app, ANILIST_PROVIDER_ID).await?;
@@ -468,12 +554,79 @@ pub async fn synchronize_anilist(
     }
 }
 
+pub async fn fetch_anilist_media_relations(
+    app: &tauri::AppHandle,
+    media_id: u64,
+) -> Result<AniListMediaRelations, String> {
+    let token = get_access_token(app, ANILIST_PROVIDER_ID).await?;
+    let client = reqwest::Client::new();
+    let media = fetch_media_relations(&client, &token, media_id).await?;
+
+    Ok(map_media_relations(media))
+}
+
+// Looks a media up by its AniList or MyAnimeList id and returns both ids along
+// with the viewer's score format.
+pub async fn fetch_anilist_media_mapping(
+    app: &tauri::AppHandle,
+    client: &reqwest::Client,
+    list_type: ListType,
+    lookup: AniListMediaLookup,
+) -> Result<AniListMediaMapping, ListUpdateError> {
+    let token = get_access_token(app, ANILIST_PROVIDER_ID)
+        .await
+        .map_err(ListUpdateError::offline)?;
+    let (id, id_mal) = match lookup {
+        AniListMediaLookup::Id(id) => (Some(id), None),
+        AniListMediaLookup::MalId(id_mal) => (None, Some(id_mal)),
+    };
+    let request = MediaIdMappingRequest {
+        query: MEDIA_ID_MAPPING_QUERY,
+        variables: MediaIdMappingVariables {
+            id,
+            id_mal,
+            r#type: match list_type {
+                ListType::Anime => MEDIA_TYPE_ANIME,
+                ListType::Manga => MEDIA_TYPE_MANGA,
+            },
+        },
+    };
+
+    let response = client
+        .post(GRAPHQL_URL)
+        .bearer_auth(token)
+        .json(&request)
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .send()
+        .await
+        .map_err(|e| {
+            ListUpdateError::from_transport(
+                &e,
+                format_transport_error("AniList id mapping request failed", &e),
+            )
+        })?;
+    let status_code = response.status();
+    let retry_after = retry_after_header(response.headers());
+    let body = response.text().await.map_err(|e| {
+        ListUpdateError::from_transport(
+            &e,
+            format_transport_error("AniList id mapping response read failed", &e),
+        )
+    })?;
+    parse_media_id_mapping_response(status_code, &body)
+        .map_err(|message| ListUpdateError::from_status(status_code, retry_after, message))
+}
+
 pub async fn update_anilist_list_entry(
     app: &tauri::AppHandle,
     client: &reqwest::Client,
     update: &AnimeListUpdateRequest,
-) -> Result<(), String> {
-    let token = get_access_token(app, ANILIST_PROVIDER_ID).await?;
+) -> Result<Option<u64>, ListUpdateError> {
+    // A missing token may come back after a refresh or a new login, so the
+    // queue keeps retrying rather than giving up on the update.
+    let token = get_access_token(app, ANILIST_PROVIDER_ID)
+        .await
+        .map_err(ListUpdateError::offline)?;
     let variables = build_save_media_list_entry_variables(update)?;
 
     let payload = SaveMediaListEntryRequest {
@@ -488,18 +641,28 @@ pub async fn update_anilist_list_entry(
         .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
         .send()
         .await
-        .map_err(|e| format_transport_error("AniList update request failed", &e))?;
+        .map_err(|e| {
+            ListUpdateError::from_transport(
+                &e,
+                format_transport_error("AniList update request failed", &e),
+            )
+        })?;
     let status_code = response.status();
-    let body = response
-        .text()
-        .await
-        .map_err(|e| format_transport_error("AniList update response read failed", &e))?;
+    let retry_after = retry_after_header(response.headers());
+    let body = response.text().await.map_err(|e| {
+        ListUpdateError::from_transport(
+            &e,
+            format_transport_error("AniList update response read failed", &e),
+        )
+    })?;
     parse_save_media_list_entry_response(status_code, &body)
+        .map_err(|message| ListUpdateError::from_status(status_code, retry_after, message))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::anilist::AniListScoreFormat;
 
     fn base_update() -> AnimeListUpdateRequest {
         AnimeListUpdateRequest {
@@ -519,6 +682,7 @@ mod tests {
             user_num_times_reread: None,
             user_start_date: None,
             user_finish_date: None,
+            mirror_from: None,
         }
     }
 
@@ -695,6 +859,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_media_relations_response_reads_media_and_relation_edges() {
+        let media = parse_media_relations_response(
+            reqwest::StatusCode::OK,
+            r#"{
+                "data": {
+                    "Media": {
+                        "id": 21,
+                        "title": { "romaji": "One Piece" },
+                        "episodes": null,
+                        "relations": {
+                            "edges": [
+                                {
+                                    "relationType": "SIDE_STORY",
+                                    "node": { "id": 459, "type": "ANIME", "format": "MOVIE" }
+                                }
+                            ]
+                        }
+                    }
+                }
+            }"#,
+        )
+        .expect("media should parse");
+
+        assert_eq!(media.id, 21);
+        assert_eq!(media.episodes, None);
+        assert_eq!(
+            media.relations.map(|relations| relations.edges.len()),
+            Some(1)
+        );
+        assert_eq!(
+            parse_media_relations_response(reqwest::StatusCode::OK, r#"{"data":{"Media":null}}"#)
+                .err()
+                .as_deref(),
+            Some("AniList response missing Media")
+        );
+    }
+
     #[test]
     fn parse_viewer_response_maps_user_info_and_statistics() {
         let user = parse_viewer_response(
@@ -781,11 +983,14 @@ mod tests {
 
     #[test]
     fn parse_save_media_list_entry_response_validates_success_and_error_paths() {
-        parse_save_media_list_entry_response(
-            reqwest::StatusCode::OK,
-            r#"{"data":{"SaveMediaListEntry":{"id":123}}}"#,
-        )
-        .expect("successful mutation should be accepted");
+        assert_eq!(
+            parse_save_media_list_entry_response(
+                reqwest::StatusCode::OK,
+                r#"{"data":{"SaveMediaListEntry":{"id":123,"mediaId":21}}}"#,
+            )
+            .expect("successful mutation should be accepted"),
+            Some(21)
+        );
 
         assert_eq!(
             parse_save_media_list_entry_response(reqwest::StatusCode::BAD_REQUEST, "invalid")
@@ -834,4 +1039,40 @@ mod tests {
             Some("AniList update returned an invalid entry id")
         );
     }
+
+    #[test]
+    fn parse_media_id_mapping_response_maps_ids_and_defaults_score_format() {
+        assert_eq!(
+            parse_media_id_mapping_response(
+                reqwest::StatusCode::OK,
+                r#"{"data":{"Media":{"id":21,"idMal":21},"Viewer":{"mediaListOptions":{"scoreFormat":"POINT_100"}}}}"#,
+            )
+            .expect("mapping should parse"),
+            AniListMediaMapping {
+                media_id: 21,
+                mal_id: Some(21),
+                score_format: AniListScoreFormat::Point100,
+            }
+        );
+
+        assert_eq!(
+            parse_media_id_mapping_response(
+                reqwest::StatusCode::OK,
+                r#"{"data":{"Media":{"id":153518,"idMal":null},"Viewer":null}}"#,
+            )
+            .expect("mapping should parse"),
+            AniListMediaMapping {
+                media_id: 153518,
+                mal_id: None,
+                score_format: AniListScoreFormat::Point10,
+            }
+        );
+
+        assert_eq!(
+            parse_media_id_mapping_response(reqwest::StatusCode::OK, r#"{"data":{"Media":null}}"#)
+                .err()
+                .as_deref(),
+            Some("AniList response missing Media")
+        );
+    }
 }
@@ -1,11 +1,15 @@
 use crate::services::anime_list_updates::ListType;
 
 use super::{
-    AniListAnimeStatistics, AniListFuzzyDate, AniListMedia, AniListMediaListEntry, AniListStaff,
-    AniListStudios, AniListTitle, AnimeListBroadcast, AnimeListItem, FuzzyDateInput, MangaListItem,
-    UserStatistics, UserStatusKey,
+    AniListAnimeStatistics, AniListFuzzyDate, AniListMedia, AniListMediaListEntry,
+    AniListMediaRelations, AniListRelationMedia, AniListStaff, AniListStudios, AniListTitle,
+    AnimeListBroadcast, AnimeListItem, FuzzyDateInput, MangaListItem, UserStatistics,
+    UserStatusKey,
 };
 
+// Formats whose episodes continue the numbering of a TV series.
+const SERIES_FORMATS: [&str; 3] = ["TV", "TV_SHORT", "ONA"];
+
 fn normalize_text(value: Option<&str>) -> Option<String> {
     let value = value?.trim();
     if value.is_empty() {
@@ -48,14 +52,21 @@ fn pick_title(title: Option<&AniListTitle>) -> String {
         .unwrap_or_else(|| "Unknown".to_string())
 }
 
-fn build_alternative_titles(title: Option<&AniListTitle>, primary: &str) -> String {
+fn build_alternative_titles(
+    title: Option<&AniListTitle>,
+    synonyms: &[String],
+    primary: &str,
+) -> String {
     let Some(title) = title else {
         return "Unknown".to_string();
     };
 
     let mut parts: Vec<String> = Vec::new();
 
-    for value in [title.english.as_deref(), title.native_title.as_deref()] {
+    for value in [title.english.as_deref(), title.native_title.as_deref()]
+        .into_iter()
+        .chain(synonyms.iter().map(|synonym| Some(synonym.as_str())))
+    {
         let Some(candidate) = normalize_text(value) else {
             continue;
         };
@@ -384,13 +395,42 @@ pub(super) fn map_anilist_statistics(statistics: AniListAnimeStatistics) -> User
     }
 }
 
+fn find_series_relation(media: &AniListRelationMedia, relation_type: &str) -> Option<u64> {
+    media
+        .relations
+        .as_ref()?
+        .edges
+        .iter()
+        .filter(|edge| edge.relation_type.as_deref() == Some(relation_type))
+        .filter_map(|edge| edge.node.as_ref())
+        .find(|node| {
+            node.r#type.as_deref() == Some("ANIME")
+                && node
+                    .format
+                    .as_deref()
+                    .is_some_and(|format| SERIES_FORMATS.contains(&format))
+        })
+        .map(|node| node.id)
+}
+
+pub(super) fn map_media_relations(media: AniListRelationMedia) -> AniListMediaRelations {
+    AniListMediaRelations {
+        id: media.id,
+        title: pick_title(media.title.as_ref()),
+        episodes: media.episodes.filter(|episodes| *episodes > 0),
+        prequel_id: find_series_relation(&media, "PREQUEL"),
+        sequel_id: find_series_relation(&media, "SEQUEL"),
+    }
+}
+
 pub(super) fn map_anime_to_domain(
     media: AniListMedia,
     media_list_entry: AniListMediaListEntry,
     status_key: UserStatusKey,
 ) -> AnimeListItem {
     let title = pick_title(media.title.as_ref());
-    let alternative_titles = build_alternative_titles(media.title.as_ref(), &title);
+    let alternative_titles =
+        build_alternative_titles(media.title.as_ref(), &media.synonyms, &title);
     let available_episodes = media
         .next_airing_episode
         .as_ref()
@@ -443,7 +483,8 @@ pub(super) fn map_manga_to_domain(
     status_key: UserStatusKey,
 ) -> MangaListItem {
     let title = pick_title(media.title.as_ref());
-    let alternative_titles = build_alternative_titles(media.title.as_ref(), &title);
+    let alternative_titles =
+        build_alternative_titles(media.title.as_ref(), &media.synonyms, &title);
     let image_url = media
         .cover_image
         .and_then(|cover| cover.extra_large.or(cover.large))
@@ -486,7 +527,8 @@ pub(super) fn map_manga_to_domain(
 mod tests {
     use crate::services::anilist::{
         AniListAnimeStatisticsStatus, AniListCoverImage, AniListNextAiringEpisode,
-        AniListStaffEdge, AniListStaffName, AniListStaffNode, AniListStudio,
+        AniListRelationEdge, AniListRelationNode, AniListRelations, AniListStaffEdge,
+        AniListStaffName, AniListStaffNode, AniListStudio,
     };
 
     use super::*;
@@ -525,6 +567,7 @@ mod tests {
         AniListMedia {
             id: 1,
             title: Some(sample_title()),
+            synonyms: vec!["Frieren at the Funeral".to_string()],
             cover_image: Some(AniListCoverImage {
                 large: Some("https://img.example/large.jpg".to_string()),
                 extra_large: Some("https://img.example/extra-large.jpg".to_string()),
@@ -574,6 +617,7 @@ mod tests {
     fn sample_manga_media() -> AniListMedia {
         AniListMedia {
             id: 2,
+            synonyms: Vec::new(),
             title: Some(AniListTitle {
                 romaji: None,
                 native_title: Some(" バガボンド ".to_string()),
@@ -644,11 +688,22 @@ mod tests {
         let title = sample_title();
         assert_eq!(pick_title(Some(&title)), "Sousou no Frieren");
         assert_eq!(
-            build_alternative_titles(Some(&title), "Sousou no Frieren"),
+            build_alternative_titles(Some(&title), &[], "Sousou no Frieren"),
             "Frieren: Beyond Journey's End, 葬送のフリーレン"
         );
         assert_eq!(
-            build_alternative_titles(None, "Sousou no Frieren"),
+            build_alternative_titles(
+                Some(&title),
+                &[
+                    " Frieren at the Funeral ".to_string(),
+                    "葬送のフリーレン".to_string(),
+                ],
+                "Sousou no Frieren"
+            ),
+            "Frieren: Beyond Journey's End, 葬送のフリーレン, Frieren at the Funeral"
+        );
+        assert_eq!(
+            build_alternative_titles(None, &[], "Sousou no Frieren"),
             "Unknown"
         );
     }
@@ -863,7 +918,7 @@ mod tests {
         assert_eq!(mapped.image_url, "https://img.example/extra-large.jpg");
         assert_eq!(
             mapped.alternative_titles,
-            "Frieren: Beyond Journey's End, 葬送のフリーレン"
+            "Frieren: Beyond Journey's End, 葬送のフリーレン, Frieren at the Funeral"
         );
         assert_eq!(mapped.score, 91.0);
         assert_eq!(mapped.source, "Light Novel");
@@ -917,4 +972,37 @@ mod tests {
         assert!(!mapped.is_rereading);
         assert_eq!(mapped.user_num_times_reread, 0);
     }
+
+    #[test]
+    fn map_media_relations_keeps_only_series_sequels_and_prequels() {
+        let edge =
+            |relation_type: &str, id: u64, media_type: &str, format: &str| AniListRelationEdge {
+                relation_type: Some(relation_type.to_string()),
+                node: Some(AniListRelationNode {
+                    id,
+                    r#type: Some(media_type.to_string()),
+                    format: Some(format.to_string()),
+                }),
+            };
+
+        let relations = map_media_relations(AniListRelationMedia {
+            id: 2,
+            title: Some(sample_title()),
+            episodes: Some(0),
+            relations: Some(AniListRelations {
+                edges: vec![
+                    edge("SEQUEL", 10, "ANIME", "MOVIE"),
+                    edge("PREQUEL", 11, "MANGA", "MANGA"),
+                    edge("PREQUEL", 1, "ANIME", "TV"),
+                    edge("SIDE_STORY", 12, "ANIME", "TV"),
+                    edge("SEQUEL", 3, "ANIME", "ONA"),
+                ],
+            }),
+        });
+
+        assert_eq!(relations.title, "Sousou no Frieren");
+        assert_eq!(relations.episodes, None);
+        assert_eq!(relations.prequel_id, Some(1));
+        assert_eq!(relations.sequel_id, Some(3));
+    }
 }
@@ -6,7 +6,8 @@ mod api;
 mod mapping;
 
 pub use api::{
-    fetch_anilist_user_info, search_anilist_media, synchronize_anilist, update_anilist_list_entry,
+    fetch_anilist_media_mapping, fetch_anilist_media_relations, fetch_anilist_user_info,
+    search_anilist_media, synchronize_anilist, update_anilist_list_entry,
 };
 
 const GRAPHQL_URL: &str = "https://graphql.anilist.co";
@@ -28,6 +29,7 @@ query ($type: MediaType!, $userName: String) {
             native
             english
           }
+          synonyms
           coverImage {
             large
             extraLarge
@@ -131,6 +133,7 @@ query ($search: String!, $type: MediaType!, $perPage: Int!) {
         native
         english
       }
+      synonyms
       coverImage {
         large
         extraLarge
@@ -198,6 +201,42 @@ query ($search: String!, $type: MediaType!, $perPage: Int!) {
   }
 }
 "#;
+const MEDIA_RELATIONS_QUERY: &str = r#"
+query ($id: Int!) {
+  Media(id: $id, type: ANIME) {
+    id
+    title {
+      romaji
+      native
+      english
+    }
+    episodes
+    relations {
+      edges {
+        relationType
+        node {
+          id
+          type
+          format
+        }
+      }
+    }
+  }
+}
+"#;
+const MEDIA_ID_MAPPING_QUERY: &str = r#"
+query ($id: Int, $idMal: Int, $type: MediaType) {
+  Media(id: $id, idMal: $idMal, type: $type) {
+    id
+    idMal
+  }
+  Viewer {
+    mediaListOptions {
+      scoreFormat
+    }
+  }
+}
+"#;
 const SAVE_MEDIA_LIST_ENTRY_MUTATION: &str = r#"
 mutation Mutation(
   $saveMediaListEntryId: Int
@@ -224,6 +263,7 @@ mutation Mutation(
     completedAt: $completedAt
   ) {
     id
+    mediaId
   }
 }
 "#;
@@ -260,6 +300,33 @@ struct SearchMediaVariables<'a> {
     per_page: u32,
 }
 
+#[derive(Serialize)]
+struct MediaRelationsRequest<'a> {
+    query: &'a str,
+    variables: MediaRelationsVariables,
+}
+
+#[derive(Serialize)]
+struct MediaRelationsVariables {
+    id: u64,
+}
+
+#[derive(Serialize)]
+struct MediaIdMappingRequest<'a> {
+    query: &'a str,
+    variables: MediaIdMappingVariables<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MediaIdMappingVariables<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id_mal: Option<u64>,
+    r#type: &'a str,
+}
+
 #[derive(Serialize)]
 struct SaveMediaListEntryRequest<'a> {
     query: &'a str,
@@ -341,6 +408,118 @@ struct AniListSearchPage {
     media: Vec<Option<AniListMedia>>,
 }
 
+#[derive(Deserialize)]
+struct MediaRelationsResponse {
+    data: Option<MediaRelationsData>,
+    errors: Option<Vec<GraphQlError>>,
+}
+
+#[derive(Deserialize)]
+struct MediaRelationsData {
+    #[serde(rename = "Media")]
+    media: Option<AniListRelationMedia>,
+}
+
+#[derive(Deserialize, Default)]
+struct AniListRelationMedia {
+    id: u64,
+    title: Option<AniListTitle>,
+    episodes: Option<u32>,
+    relations: Option<AniListRelations>,
+}
+
+#[derive(Deserialize, Default)]
+struct AniListRelations {
+    #[serde(default)]
+    edges: Vec<AniListRelationEdge>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct AniListRelationEdge {
+    relation_type: Option<String>,
+    node: Option<AniListRelationNode>,
+}
+
+#[derive(Deserialize, Default)]
+struct AniListRelationNode {
+    id: u64,
+    r#type: Option<String>,
+    format: Option<String>,
+}
+
+// A media with the sequel and prequel that continue its episode numbering.
+// Movies, specials and other side stories are not part of that chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AniListMediaRelations {
+    pub id: u64,
+    pub title: String,
+    pub episodes: Option<u32>,
+    pub prequel_id: Option<u64>,
+    pub sequel_id: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct MediaIdMappingResponse {
+    data: Option<MediaIdMappingData>,
+    errors: Option<Vec<GraphQlError>>,
+}
+
+#[derive(Deserialize)]
+struct MediaIdMappingData {
+    #[serde(rename = "Media")]
+    media: Option<AniListMediaIds>,
+    #[serde(rename = "Viewer")]
+    viewer: Option<AniListMappingViewer>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AniListMediaIds {
+    id: u64,
+    id_mal: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AniListMappingViewer {
+    media_list_options: Option<AniListMediaListOptions>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AniListMediaListOptions {
+    score_format: Option<AniListScoreFormat>,
+}
+
+// Scale the viewer picked for scores; list updates are sent in this scale.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Default)]
+pub enum AniListScoreFormat {
+    #[serde(rename = "POINT_100")]
+    Point100,
+    #[serde(rename = "POINT_10_DECIMAL")]
+    Point10Decimal,
+    #[default]
+    #[serde(rename = "POINT_10")]
+    Point10,
+    #[serde(rename = "POINT_5")]
+    Point5,
+    #[serde(rename = "POINT_3")]
+    Point3,
+}
+
+pub enum AniListMediaLookup {
+    Id(u64),
+    MalId(u64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AniListMediaMapping {
+    pub media_id: u64,
+    pub mal_id: Option<u64>,
+    pub score_format: AniListScoreFormat,
+}
+
 #[derive(Deserialize)]
 struct SaveMediaListEntryMutationResponse {
     data: Option<SaveMediaListEntryMutationData>,
@@ -354,8 +533,10 @@ struct SaveMediaListEntryMutationData {
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct SaveMediaListEntryMutationPayload {
     id: u64,
+    media_id: Option<u64>,
 }
 
 #[derive(Deserialize)]
@@ -457,6 +638,8 @@ struct AniListEntry {
 struct AniListMedia {
     id: u64,
     title: Option<AniListTitle>,
+    #[serde(default)]
+    synonyms: Vec<String>,
     cover_image: Option<AniListCoverImage>,
     mean_score: Option<u32>,
     media_list_entry: Option<AniListMediaListEntry>,
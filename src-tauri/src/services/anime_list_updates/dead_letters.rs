@@ -0,0 +1,187 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use super::AnimeListUpdateRequest;
+
+pub(super) const DEAD_LETTERS_FILE_NAME: &str = "anime-list-dead-letters.json";
+const DEAD_LETTERS_FILE_VERSION: u32 = 1;
+// Oldest failures are dropped first once the list is full.
+const DEAD_LETTER_CAPACITY: usize = 200;
+
+// An update the queue gave up on, kept so it can be inspected and resubmitted.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetteredUpdate {
+    pub id: u64,
+    pub request: AnimeListUpdateRequest,
+    pub error: String,
+    pub attempts: u32,
+    pub failed_at: u64,
+}
+
+#[derive(Deserialize, Serialize)]
+struct DeadLetterFile {
+    version: u32,
+    updates: Vec<DeadLetteredUpdate>,
+}
+
+fn read_dead_letters_file(path: &Path) -> Result<Vec<DeadLetteredUpdate>, String> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(format!(
+                "Failed to read dead-lettered list updates: {error}"
+            ))
+        }
+    };
+
+    let file: DeadLetterFile = serde_json::from_str(&contents)
+        .map_err(|error| format!("Failed to parse dead-lettered list updates: {error}"))?;
+    if file.version != DEAD_LETTERS_FILE_VERSION {
+        return Err(format!(
+            "Unsupported dead-lettered list updates version: {}",
+            file.version
+        ));
+    }
+
+    Ok(file.updates)
+}
+
+fn write_dead_letters_file(path: &Path, updates: &[DeadLetteredUpdate]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed to save dead-lettered list updates: {error}"))?;
+    }
+
+    let contents = serde_json::to_string_pretty(&DeadLetterFile {
+        version: DEAD_LETTERS_FILE_VERSION,
+        updates: updates.to_vec(),
+    })
+    .map_err(|error| format!("Failed to serialize dead-lettered list updates: {error}"))?;
+
+    let temporary_path = path.with_extension("json.tmp");
+    std::fs::write(&temporary_path, contents)
+        .and_then(|_| std::fs::rename(&temporary_path, path))
+        .map_err(|error| format!("Failed to save dead-lettered list updates: {error}"))
+}
+
+pub(super) struct DeadLetterStore {
+    path: Option<PathBuf>,
+    updates: Mutex<Vec<DeadLetteredUpdate>>,
+}
+
+impl DeadLetterStore {
+    pub(super) fn load(path: Option<PathBuf>) -> Self {
+        let updates = match path.as_deref().map(read_dead_letters_file) {
+            Some(Ok(updates)) => updates,
+            Some(Err(error)) => {
+                eprintln!("{error}");
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+
+        Self {
+            path,
+            updates: Mutex::new(updates),
+        }
+    }
+
+    fn persist(&self, updates: &[DeadLetteredUpdate]) -> Result<(), String> {
+        match self.path.as_deref() {
+            Some(path) => write_dead_letters_file(path, updates),
+            None => Ok(()),
+        }
+    }
+
+    pub(super) async fn list(&self) -> Vec<DeadLetteredUpdate> {
+        self.updates.lock().await.clone()
+    }
+
+    pub(super) async fn recent(&self, limit: usize) -> Vec<DeadLetteredUpdate> {
+        let updates = self.updates.lock().await;
+        updates.iter().rev().take(limit).cloned().collect()
+    }
+
+    pub(super) fn max_id(&mut self) -> Option<u64> {
+        self.updates.get_mut().iter().map(|update| update.id).max()
+    }
+
+    pub(super) async fn push(&self, update: DeadLetteredUpdate) -> Result<(), String> {
+        let mut updates = self.updates.lock().await;
+        updates.push(update);
+        let overflow = updates.len().saturating_sub(DEAD_LETTER_CAPACITY);
+        updates.drain(..overflow);
+        self.persist(&updates)
+    }
+
+    pub(super) async fn take(&self, id: u64) -> Result<DeadLetteredUpdate, String> {
+        let mut updates = self.updates.lock().await;
+        let index = updates
+            .iter()
+            .position(|update| update.id == id)
+            .ok_or_else(|| format!("Dead-lettered list update not found: {id}"))?;
+        let update = updates.remove(index);
+        self.persist(&updates)?;
+        Ok(update)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::runtime::Runtime;
+
+    use super::*;
+
+    fn dead_letter(id: u64) -> DeadLetteredUpdate {
+        DeadLetteredUpdate {
+            id,
+            request: AnimeListUpdateRequest {
+                provider_id: "anilist".to_string(),
+                entry_id: Some(id * 10),
+                ..Default::default()
+            },
+            error: "AniList update failed: 404 Not Found - missing".to_string(),
+            attempts: 1,
+            failed_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn dead_letters_persist_and_can_be_taken_back() {
+        let runtime = Runtime::new().expect("runtime should build");
+        let path = std::env::temp_dir()
+            .join(format!("kioku-dead-letters-{}", std::process::id()))
+            .join(DEAD_LETTERS_FILE_NAME);
+        let _ = std::fs::remove_file(&path);
+
+        let store = DeadLetterStore::load(Some(path.clone()));
+        runtime.block_on(async {
+            store.push(dead_letter(3)).await.expect("push should save");
+            store.push(dead_letter(5)).await.expect("push should save");
+        });
+
+        let mut reloaded = DeadLetterStore::load(Some(path.clone()));
+        assert_eq!(reloaded.max_id(), Some(5));
+        assert_eq!(runtime.block_on(reloaded.recent(1)), vec![dead_letter(5)]);
+        assert_eq!(
+            runtime
+                .block_on(reloaded.take(3))
+                .expect("update should exist"),
+            dead_letter(3)
+        );
+        assert_eq!(
+            runtime.block_on(reloaded.take(3)).unwrap_err(),
+            "Dead-lettered list update not found: 3"
+        );
+        assert_eq!(
+            runtime.block_on(DeadLetterStore::load(Some(path.clone())).list()),
+            vec![dead_letter(5)]
+        );
+
+        let _ = std::fs::remove_dir_all(path.parent().expect("store should have a parent"));
+    }
+}
@@ -0,0 +1,54 @@
+use serde::Serialize;
+use tauri::Emitter;
+
+use super::AnimeListUpdateRequest;
+
+pub const LIST_UPDATE_QUEUED_EVENT: &str = "list-update:queued";
+pub const LIST_UPDATE_STARTED_EVENT: &str = "list-update:started";
+pub const LIST_UPDATE_SUCCEEDED_EVENT: &str = "list-update:succeeded";
+pub const LIST_UPDATE_FAILED_EVENT: &str = "list-update:failed";
+pub const LIST_UPDATE_DROPPED_EVENT: &str = "list-update:dropped";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(super) enum ListUpdateEventKind {
+    Queued,
+    Started,
+    Succeeded,
+    // Emitted for every failed attempt, including ones that will be retried.
+    Failed,
+    // The queue gave up on the update; it was dead-lettered or discarded.
+    Dropped,
+}
+
+impl ListUpdateEventKind {
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Self::Queued => LIST_UPDATE_QUEUED_EVENT,
+            Self::Started => LIST_UPDATE_STARTED_EVENT,
+            Self::Succeeded => LIST_UPDATE_SUCCEEDED_EVENT,
+            Self::Failed => LIST_UPDATE_FAILED_EVENT,
+            Self::Dropped => LIST_UPDATE_DROPPED_EVENT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListUpdateEvent {
+    pub id: u64,
+    pub request: AnimeListUpdateRequest,
+    // 1-based attempt the event refers to.
+    pub attempt: u32,
+    pub error: Option<String>,
+    pub retry_in_ms: Option<u64>,
+}
+
+pub(super) fn emit_list_update_event(
+    app: &tauri::AppHandle,
+    kind: ListUpdateEventKind,
+    event: &ListUpdateEvent,
+) {
+    if let Err(error) = app.emit(kind.name(), event) {
+        eprintln!("failed to emit {} event: {error}", kind.name());
+    }
+}
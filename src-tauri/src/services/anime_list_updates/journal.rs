@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::AnimeListUpdateRequest;
+
+pub(super) const JOURNAL_FILE_NAME: &str = "anime-list-updates.jsonl";
+// Finished records allowed to pile up before the journal is rewritten.
+const COMPACTION_THRESHOLD: usize = 64;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+enum JournalRecord {
+    Enqueued {
+        id: u64,
+        request: Box<AnimeListUpdateRequest>,
+    },
+    Finished {
+        id: u64,
+    },
+}
+
+// Returns the updates that were enqueued but never finished, oldest first. A
+// crash can cut the last line short, so unreadable lines are skipped instead
+// of failing the whole replay.
+fn replay_journal(contents: &str) -> Vec<(u64, AnimeListUpdateRequest)> {
+    let mut pending = BTreeMap::new();
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str::<JournalRecord>(line) {
+            Ok(JournalRecord::Enqueued { id, request }) => {
+                pending.insert(id, *request);
+            }
+            Ok(JournalRecord::Finished { id }) => {
+                pending.remove(&id);
+            }
+            Err(error) => eprintln!("skipping unreadable list update journal record: {error}"),
+        }
+    }
+
+    pending.into_iter().collect()
+}
+
+fn serialize_record(record: &JournalRecord) -> Result<String, String> {
+    serde_json::to_string(record)
+        .map(|mut line| {
+            line.push('\n');
+            line
+        })
+        .map_err(|error| format!("Failed to serialize list update journal record: {error}"))
+}
+
+fn write_journal_file(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed to compact list update journal: {error}"))?;
+    }
+
+    let temporary_path = path.with_extension("jsonl.tmp");
+    std::fs::write(&temporary_path, contents)
+        .and_then(|_| std::fs::rename(&temporary_path, path))
+        .map_err(|error| format!("Failed to compact list update journal: {error}"))
+}
+
+// Append-only log of queued list updates, so updates made offline survive a
+// quit or crash until they reach the provider.
+pub(super) struct UpdateJournal {
+    path: Option<PathBuf>,
+    // Records in the file, including finished ones.
+    records: usize,
+}
+
+impl UpdateJournal {
+    // Replays the journal at `path` and rewrites it with only the updates that
+    // never finished.
+    pub(super) fn open(path: Option<PathBuf>) -> (Self, Vec<(u64, AnimeListUpdateRequest)>) {
+        let pending = match path.as_deref().map(std::fs::read_to_string) {
+            Some(Ok(contents)) => replay_journal(&contents),
+            Some(Err(error)) if error.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Some(Err(error)) => {
+                eprintln!("Failed to read list update journal: {error}");
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+
+        let mut journal = Self { path, records: 0 };
+        if let Err(error) = journal.compact(pending.iter().map(|(id, request)| (*id, request))) {
+            eprintln!("{error}");
+        }
+
+        (journal, pending)
+    }
+
+    pub(super) fn record_enqueued(
+        &mut self,
+        id: u64,
+        request: &AnimeListUpdateRequest,
+    ) -> Result<(), String> {
+        self.append(&JournalRecord::Enqueued {
+            id,
+            request: Box::new(request.clone()),
+        })
+    }
+
+    // `live` lists every update still waiting, so the journal can be rewritten
+    // without the finished ones once enough have accumulated.
+    pub(super) fn record_finished<'a>(
+        &mut self,
+        id: u64,
+        live: impl ExactSizeIterator<Item = (u64, &'a AnimeListUpdateRequest)>,
+    ) -> Result<(), String> {
+        self.append(&JournalRecord::Finished { id })?;
+
+        if self.records >= live.len() + COMPACTION_THRESHOLD {
+            self.compact(live)?;
+        }
+
+        Ok(())
+    }
+
+    fn append(&mut self, record: &JournalRecord) -> Result<(), String> {
+        let Some(path) = self.path.as_deref() else {
+            return Ok(());
+        };
+
+        let line = serialize_record(record)?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| {
+                file.write_all(line.as_bytes())?;
+                file.sync_data()
+            })
+            .map_err(|error| format!("Failed to write list update journal: {error}"))?;
+        self.records += 1;
+
+        Ok(())
+    }
+
+    fn compact<'a>(
+        &mut self,
+        live: impl Iterator<Item = (u64, &'a AnimeListUpdateRequest)>,
+    ) -> Result<(), String> {
+        let Some(path) = self.path.as_deref() else {
+            return Ok(());
+        };
+
+        let mut contents = String::new();
+        let mut records = 0;
+        for (id, request) in live {
+            contents.push_str(&serialize_record(&JournalRecord::Enqueued {
+                id,
+                request: Box::new(request.clone()),
+            })?);
+            records += 1;
+        }
+
+        write_journal_file(path, &contents)?;
+        self.records = records;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(entry_id: u64) -> AnimeListUpdateRequest {
+        AnimeListUpdateRequest {
+            provider_id: "anilist".to_string(),
+            entry_id: Some(entry_id),
+            user_episodes_watched: Some(3),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn journal_replays_unfinished_updates_and_compacts_on_open() {
+        let dir =
+            std::env::temp_dir().join(format!("kioku-list-update-journal-{}", std::process::id()));
+        let path = dir.join(JOURNAL_FILE_NAME);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let (mut journal, pending) = UpdateJournal::open(Some(path.clone()));
+        assert!(pending.is_empty());
+        for id in 1..=3 {
+            journal
+                .record_enqueued(id, &update(id * 10))
+                .expect("enqueue should be journaled");
+        }
+        let (first, third) = (update(10), update(30));
+        journal
+            .record_finished(2, [(1, &first), (3, &third)].into_iter())
+            .expect("finish should be journaled");
+
+        // A crash in the middle of an append leaves a partial line behind.
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("journal should exist");
+        file.write_all(b"{\"op\":\"enqueued\",\"id\":4,\"req")
+            .expect("partial record should write");
+
+        let (_, pending) = UpdateJournal::open(Some(path.clone()));
+        assert_eq!(
+            pending
+                .iter()
+                .map(|(id, request)| (*id, request.entry_id))
+                .collect::<Vec<_>>(),
+            vec![(1, Some(10)), (3, Some(30))]
+        );
+        let contents = std::fs::read_to_string(&path).expect("journal should exist");
+        assert_eq!(contents.lines().count(), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
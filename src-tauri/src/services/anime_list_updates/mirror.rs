@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+use tauri_plugin_http::reqwest;
+
+use crate::auth::anilist::PROVIDER_ID as ANILIST_PROVIDER_ID;
+use crate::auth::mal::PROVIDER_ID as MAL_PROVIDER_ID;
+use crate::services::anilist::{
+    fetch_anilist_media_mapping, AniListMediaLookup, AniListScoreFormat,
+};
+
+use super::{AnimeListUpdateRequest, ListUpdateError};
+
+pub(super) const MIRROR_PROVIDER_IDS: [&str; 2] = [ANILIST_PROVIDER_ID, MAL_PROVIDER_ID];
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListUpdatesConfig {
+    // Repeat every update made on one provider on the other linked ones.
+    #[serde(default)]
+    pub mirror_to_linked_providers: bool,
+}
+
+// The mirrored request keeps the source provider's media id and score; both
+// are translated when the worker picks it up, so a failed id lookup is
+// retried and reported like any other update.
+pub(super) fn mirror_request(
+    update: &AnimeListUpdateRequest,
+    media_id: u64,
+    target_provider_id: &str,
+) -> AnimeListUpdateRequest {
+    AnimeListUpdateRequest {
+        provider_id: target_provider_id.to_string(),
+        entry_id: None,
+        media_id: Some(media_id),
+        mirror_from: Some(update.provider_id.clone()),
+        ..update.clone()
+    }
+}
+
+pub(super) async fn resolve_mirror_update(
+    app: &tauri::AppHandle,
+    client: &reqwest::Client,
+    update: &AnimeListUpdateRequest,
+    source_provider_id: &str,
+) -> Result<AnimeListUpdateRequest, ListUpdateError> {
+    let media_id = update.media_id.ok_or_else(|| {
+        ListUpdateError::permanent("Mirrored list update is missing its media id".to_string())
+    })?;
+    let lookup = match source_provider_id {
+        ANILIST_PROVIDER_ID => AniListMediaLookup::Id(media_id),
+        MAL_PROVIDER_ID => AniListMediaLookup::MalId(media_id),
+        _ => {
+            return Err(ListUpdateError::permanent(format!(
+                "Provider not supported: {source_provider_id}"
+            )))
+        }
+    };
+
+    let mapping =
+        fetch_anilist_media_mapping(app, client, update.list_type.unwrap_or_default(), lookup)
+            .await?;
+    let target_media_id = match update.provider_id.as_str() {
+        ANILIST_PROVIDER_ID => mapping.media_id,
+        _ => mapping.mal_id.ok_or_else(|| {
+            ListUpdateError::permanent(format!(
+                "AniList media {} has no MyAnimeList id",
+                mapping.media_id
+            ))
+        })?,
+    };
+
+    Ok(translate_mirror_update(
+        update,
+        source_provider_id,
+        target_media_id,
+        mapping.score_format,
+    ))
+}
+
+fn translate_mirror_update(
+    update: &AnimeListUpdateRequest,
+    source_provider_id: &str,
+    target_media_id: u64,
+    anilist_score_format: AniListScoreFormat,
+) -> AnimeListUpdateRequest {
+    let to_anilist = update.provider_id == ANILIST_PROVIDER_ID;
+    let user_score = update.user_score.map(|score| {
+        if to_anilist {
+            score_from_percent(score.min(10) * 10, anilist_score_format)
+        } else if source_provider_id == ANILIST_PROVIDER_ID {
+            (score_to_percent(score, anilist_score_format) + 5) / 10
+        } else {
+            score
+        }
+    });
+
+    AnimeListUpdateRequest {
+        // AniList creates the entry from the media id; MyAnimeList keys list
+        // entries by it.
+        entry_id: (!to_anilist).then_some(target_media_id),
+        media_id: Some(target_media_id),
+        user_status: update.user_status.as_deref().map(normalize_status),
+        user_score,
+        user_start_date: translate_date(update.user_start_date.as_deref(), to_anilist),
+        user_finish_date: translate_date(update.user_finish_date.as_deref(), to_anilist),
+        mirror_from: None,
+        ..update.clone()
+    }
+}
+
+// Both providers accept the camelCase names; MyAnimeList's own snake_case
+// spellings are normalized so the request reads the same on either side.
+fn normalize_status(status: &str) -> String {
+    match status {
+        "on_hold" => "onHold",
+        "plan_to_watch" => "planToWatch",
+        "plan_to_read" => "planToRead",
+        other => other,
+    }
+    .to_string()
+}
+
+// MyAnimeList takes partial dates such as "2024-03", which AniList rejects,
+// so those are left out of updates mirrored to AniList.
+fn translate_date(date: Option<&str>, to_anilist: bool) -> Option<String> {
+    let date = date?.trim();
+    if date.is_empty() || (to_anilist && date.split('-').count() != 3) {
+        return None;
+    }
+
+    Some(date.to_string())
+}
+
+fn score_to_percent(score: u32, format: AniListScoreFormat) -> u32 {
+    match format {
+        AniListScoreFormat::Point100 => score.min(100),
+        AniListScoreFormat::Point10Decimal | AniListScoreFormat::Point10 => score.min(10) * 10,
+        AniListScoreFormat::Point5 => score.min(5) * 20,
+        AniListScoreFormat::Point3 => (score.min(3) * 100 + 1) / 3,
+    }
+}
+
+// Zero means unscored on every scale and stays zero.
+fn score_from_percent(percent: u32, format: AniListScoreFormat) -> u32 {
+    if percent == 0 {
+        return 0;
+    }
+
+    match format {
+        AniListScoreFormat::Point100 => percent,
+        AniListScoreFormat::Point10Decimal | AniListScoreFormat::Point10 => {
+            ((percent + 5) / 10).max(1)
+        }
+        AniListScoreFormat::Point5 => ((percent + 10) / 20).max(1),
+        AniListScoreFormat::Point3 => ((percent * 3 + 50) / 100).clamp(1, 3),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::anime_list_updates::ListType;
+
+    fn anilist_update() -> AnimeListUpdateRequest {
+        AnimeListUpdateRequest {
+            provider_id: ANILIST_PROVIDER_ID.to_string(),
+            list_type: Some(ListType::Anime),
+            entry_id: Some(900),
+            user_status: Some("watching".to_string()),
+            user_score: Some(85),
+            user_episodes_watched: Some(4),
+            user_start_date: Some("2026-10-01".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn translate_mirror_update_maps_ids_and_score_to_myanimelist() {
+        let mirror = mirror_request(&anilist_update(), 154587, MAL_PROVIDER_ID);
+        assert_eq!(mirror.mirror_from.as_deref(), Some(ANILIST_PROVIDER_ID));
+        assert_eq!((mirror.entry_id, mirror.media_id), (None, Some(154587)));
+
+        let translated = translate_mirror_update(
+            &mirror,
+            ANILIST_PROVIDER_ID,
+            52991,
+            AniListScoreFormat::Point100,
+        );
+
+        assert_eq!(
+            translated,
+            AnimeListUpdateRequest {
+                provider_id: MAL_PROVIDER_ID.to_string(),
+                list_type: Some(ListType::Anime),
+                entry_id: Some(52991),
+                media_id: Some(52991),
+                user_status: Some("watching".to_string()),
+                user_score: Some(9),
+                user_episodes_watched: Some(4),
+                user_start_date: Some("2026-10-01".to_string()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn translate_mirror_update_maps_status_score_and_dates_to_anilist() {
+        let update = AnimeListUpdateRequest {
+            provider_id: MAL_PROVIDER_ID.to_string(),
+            entry_id: Some(52991),
+            user_status: Some("on_hold".to_string()),
+            user_score: Some(7),
+            user_start_date: Some("2026-10".to_string()),
+            user_finish_date: Some(" 2026-10-18 ".to_string()),
+            ..Default::default()
+        };
+        let mirror = mirror_request(&update, 52991, ANILIST_PROVIDER_ID);
+
+        let translated =
+            translate_mirror_update(&mirror, MAL_PROVIDER_ID, 154587, AniListScoreFormat::Point5);
+
+        assert_eq!(translated.provider_id, ANILIST_PROVIDER_ID);
+        assert_eq!(
+            (translated.entry_id, translated.media_id),
+            (None, Some(154587))
+        );
+        assert_eq!(translated.user_status.as_deref(), Some("onHold"));
+        assert_eq!(translated.user_score, Some(4));
+        assert_eq!(translated.user_start_date, None);
+        assert_eq!(translated.user_finish_date.as_deref(), Some("2026-10-18"));
+        assert_eq!(translated.mirror_from, None);
+    }
+
+    #[test]
+    fn scores_convert_between_anilist_formats_and_percent() {
+        assert_eq!(score_from_percent(0, AniListScoreFormat::Point3), 0);
+        assert_eq!(score_from_percent(100, AniListScoreFormat::Point100), 100);
+        assert_eq!(score_from_percent(100, AniListScoreFormat::Point10), 10);
+        assert_eq!(score_from_percent(60, AniListScoreFormat::Point5), 3);
+        assert_eq!(score_from_percent(10, AniListScoreFormat::Point5), 1);
+        assert_eq!(score_from_percent(10, AniListScoreFormat::Point3), 1);
+
+        assert_eq!(score_to_percent(8, AniListScoreFormat::Point10Decimal), 80);
+        assert_eq!(score_to_percent(4, AniListScoreFormat::Point5), 80);
+        assert_eq!(score_to_percent(2, AniListScoreFormat::Point3), 67);
+        assert_eq!(score_to_percent(150, AniListScoreFormat::Point100), 100);
+    }
+}
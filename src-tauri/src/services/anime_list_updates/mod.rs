@@ -0,0 +1,1296 @@
+use std::{
+    any::Any,
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use tauri_plugin_http::reqwest;
+use tokio::{
+    sync::{Mutex, Notify},
+    time::Instant,
+};
+
+use crate::auth::anilist::PROVIDER_ID as ANILIST_PROVIDER_ID;
+use crate::auth::mal::PROVIDER_ID as MAL_PROVIDER_ID;
+use crate::auth::token_manager::get_access_token;
+use crate::services::anilist::update_anilist_list_entry;
+use crate::services::myanimelist::update_myanimelist_list_entry;
+
+use self::dead_letters::{DeadLetterStore, DEAD_LETTERS_FILE_NAME};
+use self::events::{emit_list_update_event, ListUpdateEventKind};
+use self::journal::{UpdateJournal, JOURNAL_FILE_NAME};
+use self::mirror::{mirror_request, resolve_mirror_update, MIRROR_PROVIDER_IDS};
+use self::retry::retry_delay;
+
+pub use self::dead_letters::DeadLetteredUpdate;
+pub use self::events::{
+    ListUpdateEvent, LIST_UPDATE_DROPPED_EVENT, LIST_UPDATE_FAILED_EVENT, LIST_UPDATE_QUEUED_EVENT,
+    LIST_UPDATE_STARTED_EVENT, LIST_UPDATE_SUCCEEDED_EVENT,
+};
+pub use self::mirror::ListUpdatesConfig;
+pub use self::retry::{retry_after_header, ListUpdateError};
+
+mod dead_letters;
+mod events;
+mod journal;
+mod mirror;
+mod retry;
+
+const UPDATE_INTERVAL_MS: u64 = 1000;
+const UPDATE_QUEUE_CAPACITY: usize = 256;
+const WORKER_RESTART_DELAY_MS: u64 = 1000;
+const WORKER_CRASH_RETRY_LIMIT: u8 = 3;
+// Roughly an hour of backoff before a transient failure is dead-lettered.
+const TRANSIENT_RETRY_LIMIT: u32 = 12;
+const RECENT_FAILURE_LIMIT: usize = 20;
+
+macro_rules! update_worker_log {
+    ($($arg:tt)*) => {
+        {
+            #[cfg(debug_assertions)]
+            {
+                eprintln!($($arg)*);
+            }
+
+            #[cfg(not(debug_assertions))]
+            {
+                let _ = format_args!($($arg)*);
+            }
+        }
+    };
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ListType {
+    #[default]
+    Anime,
+    Manga,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimeListUpdateRequest {
+    pub provider_id: String,
+    #[serde(default)]
+    pub list_type: Option<ListType>,
+    #[serde(alias = "id")]
+    pub entry_id: Option<u64>,
+    #[serde(default, alias = "mediaId")]
+    pub media_id: Option<u64>,
+    pub user_status: Option<String>,
+    pub user_score: Option<u32>,
+    pub user_episodes_watched: Option<u32>,
+    pub user_volumes_read: Option<u32>,
+    pub user_chapters_read: Option<u32>,
+    pub is_rewatching: Option<bool>,
+    pub is_rereading: Option<bool>,
+    pub user_comments: Option<String>,
+    pub user_num_times_rewatched: Option<u32>,
+    pub user_num_times_reread: Option<u32>,
+    pub user_start_date: Option<String>,
+    pub user_finish_date: Option<String>,
+    // Provider the update was made on when the queue mirrored it to this one;
+    // `media_id` and `user_score` are still in that provider's terms.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirror_from: Option<String>,
+}
+
+impl AnimeListUpdateRequest {
+    // Entries not on the list yet are only known by `media_id`.
+    fn targets_same_entry(&self, other: &Self) -> bool {
+        self.provider_id == other.provider_id
+            && self.mirror_from == other.mirror_from
+            && self.list_type.unwrap_or_default() == other.list_type.unwrap_or_default()
+            && match (self.entry_id, other.entry_id) {
+                (Some(entry_id), Some(other_entry_id)) => entry_id == other_entry_id,
+                _ => self.media_id.is_some() && self.media_id == other.media_id,
+            }
+    }
+
+    // Fields set on `later` win; fields it leaves out keep their queued value.
+    fn merge(&mut self, later: Self) {
+        self.list_type = later.list_type.or(self.list_type);
+        self.entry_id = later.entry_id.or(self.entry_id);
+        self.media_id = later.media_id.or(self.media_id);
+        self.user_status = later.user_status.or(self.user_status.take());
+        self.user_score = later.user_score.or(self.user_score);
+        self.user_episodes_watched = later.user_episodes_watched.or(self.user_episodes_watched);
+        self.user_volumes_read = later.user_volumes_read.or(self.user_volumes_read);
+        self.user_chapters_read = later.user_chapters_read.or(self.user_chapters_read);
+        self.is_rewatching = later.is_rewatching.or(self.is_rewatching);
+        self.is_rereading = later.is_rereading.or(self.is_rereading);
+        self.user_comments = later.user_comments.or(self.user_comments.take());
+        self.user_num_times_rewatched = later
+            .user_num_times_rewatched
+            .or(self.user_num_times_rewatched);
+        self.user_num_times_reread = later.user_num_times_reread.or(self.user_num_times_reread);
+        self.user_start_date = later.user_start_date.or(self.user_start_date.take());
+        self.user_finish_date = later.user_finish_date.or(self.user_finish_date.take());
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListUpdateQueueItem {
+    pub id: u64,
+    pub request: AnimeListUpdateRequest,
+    // Attempts that already failed and are being retried.
+    pub attempts: u32,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimeListUpdateQueueSnapshot {
+    pub pending: Vec<ListUpdateQueueItem>,
+    pub in_flight: Option<ListUpdateQueueItem>,
+    // Newest first; the full list is available through the dead letter
+    // commands.
+    pub recently_failed: Vec<DeadLetteredUpdate>,
+}
+
+#[derive(Debug, Clone)]
+struct QueuedAnimeListUpdate {
+    id: u64,
+    request: AnimeListUpdateRequest,
+    crash_retries: u8,
+    attempts: u32,
+    // Failed attempts that count toward `TRANSIENT_RETRY_LIMIT`; offline
+    // ones are only in `attempts`.
+    counted_retries: u32,
+    last_error: Option<String>,
+    // Set on a retry so the worker leaves the update alone until its backoff
+    // has passed.
+    not_before: Option<Instant>,
+}
+
+impl QueuedAnimeListUpdate {
+    fn new(id: u64, request: AnimeListUpdateRequest) -> Self {
+        Self {
+            id,
+            request,
+            crash_retries: 0,
+            attempts: 0,
+            counted_retries: 0,
+            last_error: None,
+            not_before: None,
+        }
+    }
+
+    fn item(&self) -> ListUpdateQueueItem {
+        ListUpdateQueueItem {
+            id: self.id,
+            request: self.request.clone(),
+            attempts: self.attempts,
+            last_error: self.last_error.clone(),
+        }
+    }
+
+    fn event(&self, error: Option<&str>, retry_in: Option<Duration>) -> ListUpdateEvent {
+        ListUpdateEvent {
+            id: self.id,
+            request: self.request.clone(),
+            attempt: self.attempts + 1,
+            error: error.map(str::to_string),
+            retry_in_ms: retry_in.map(|delay| delay.as_millis() as u64),
+        }
+    }
+
+    // Offline failures say nothing about the update itself, so they are
+    // retried for as long as it takes to get back online.
+    fn schedule_transient_retry(mut self, counts_toward_limit: bool) -> Option<Self> {
+        if counts_toward_limit {
+            if self.counted_retries >= TRANSIENT_RETRY_LIMIT {
+                return None;
+            }
+            self.counted_retries += 1;
+        }
+
+        self.attempts += 1;
+        Some(self)
+    }
+
+    fn is_due(&self, now: Instant) -> bool {
+        self.not_before.is_none_or(|not_before| not_before <= now)
+    }
+
+    fn schedule_retry(mut self) -> Option<Self> {
+        if self.crash_retries >= WORKER_CRASH_RETRY_LIMIT {
+            return None;
+        }
+
+        self.crash_retries += 1;
+        Some(self)
+    }
+}
+
+struct PendingAnimeListUpdates {
+    items: Mutex<VecDeque<QueuedAnimeListUpdate>>,
+    // Always locked after `items` when both are needed.
+    journal: Mutex<UpdateJournal>,
+    // The update the worker is running, if any. Locked after `items`.
+    in_flight: Mutex<Option<ListUpdateQueueItem>>,
+    next_id: AtomicU64,
+    notify: Notify,
+    capacity: usize,
+}
+
+impl PendingAnimeListUpdates {
+    #[cfg(test)]
+    fn new(capacity: usize) -> Self {
+        Self::restore(capacity, UpdateJournal::open(None).0, Vec::new())
+    }
+
+    // Updates replayed from the journal are kept even past `capacity`; the
+    // limit only applies to new ones.
+    fn restore(
+        capacity: usize,
+        journal: UpdateJournal,
+        pending: Vec<(u64, AnimeListUpdateRequest)>,
+    ) -> Self {
+        let next_id = pending.iter().map(|(id, _)| id + 1).max().unwrap_or(1);
+        let mut items = VecDeque::with_capacity(capacity.max(pending.len()));
+        items.extend(
+            pending
+                .into_iter()
+                .map(|(id, request)| QueuedAnimeListUpdate::new(id, request)),
+        );
+
+        Self {
+            items: Mutex::new(items),
+            journal: Mutex::new(journal),
+            in_flight: Mutex::new(None),
+            next_id: AtomicU64::new(next_id),
+            notify: Notify::new(),
+            capacity,
+        }
+    }
+
+    // Updates for an entry that already has one waiting are merged into it,
+    // so repeated clicks end up as a single request carrying the latest
+    // values. The update the worker is running is no longer in `items` and is
+    // left alone.
+    async fn enqueue(
+        &self,
+        update: AnimeListUpdateRequest,
+    ) -> Result<QueuedAnimeListUpdate, String> {
+        let mut items = self.items.lock().await;
+        let queued = match items
+            .iter_mut()
+            .rev()
+            .find(|queued| queued.request.targets_same_entry(&update))
+        {
+            Some(queued) => {
+                queued.request.merge(update);
+                queued.clone()
+            }
+            None => {
+                if items.len() >= self.capacity {
+                    return Err("Update queue is full".to_string());
+                }
+
+                let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                let queued = QueuedAnimeListUpdate::new(id, update);
+                items.push_back(queued.clone());
+                queued
+            }
+        };
+
+        // Replaying the journal keeps the last record for an id, so a merged
+        // update is simply written again. An update that only lives in memory
+        // is still better than refusing it.
+        if let Err(err) = self
+            .journal
+            .lock()
+            .await
+            .record_enqueued(queued.id, &queued.request)
+        {
+            update_worker_log!("Anime list update journal write failed: {err}");
+        }
+
+        drop(items);
+        self.notify.notify_one();
+        Ok(queued)
+    }
+
+    // Keeps ids unique across restarts for updates that left the journal,
+    // such as dead-lettered ones.
+    fn reserve_ids_through(&self, id: u64) {
+        self.next_id.fetch_max(id + 1, Ordering::Relaxed);
+    }
+
+    // Marks an update as done for good, whether it succeeded or was given up
+    // on.
+    async fn finish(&self, id: u64) {
+        let items = self.items.lock().await;
+        // The worker handles one update at a time, so every other live update
+        // is back in `items` by now.
+        let live = items.iter().map(|item| (item.id, &item.request));
+        if let Err(err) = self.journal.lock().await.record_finished(id, live) {
+            update_worker_log!("Anime list update journal write failed: {err}");
+        }
+        self.clear_in_flight(id).await;
+    }
+
+    async fn requeue_front(&self, update: QueuedAnimeListUpdate) -> Result<(), String> {
+        let mut items = self.items.lock().await;
+        if items.len() >= self.capacity {
+            return Err("Update queue is full while restarting worker".to_string());
+        }
+
+        let id = update.id;
+        items.push_front(update);
+        self.clear_in_flight(id).await;
+        drop(items);
+        self.notify.notify_one();
+        Ok(())
+    }
+
+    async fn clear_in_flight(&self, id: u64) {
+        let mut in_flight = self.in_flight.lock().await;
+        if in_flight.as_ref().is_some_and(|item| item.id == id) {
+            *in_flight = None;
+        }
+    }
+
+    async fn pop_front(&self) -> QueuedAnimeListUpdate {
+        loop {
+            let notified = self.notify.notified();
+            let next_due = {
+                let mut items = self.items.lock().await;
+                match next_ready(&items, Instant::now()) {
+                    Ok(index) => {
+                        let update = items.remove(index).expect("index should be in the queue");
+                        // Set while `items` is still locked so a snapshot
+                        // never misses the update in between.
+                        *self.in_flight.lock().await = Some(update.item());
+                        return update;
+                    }
+                    Err(next_due) => next_due,
+                }
+            };
+
+            match next_due {
+                Some(next_due) => {
+                    let _ = tokio::time::timeout_at(next_due, notified).await;
+                }
+                None => notified.await,
+            }
+        }
+    }
+
+    async fn snapshot(&self) -> (Vec<ListUpdateQueueItem>, Option<ListUpdateQueueItem>) {
+        let items = self.items.lock().await;
+        let in_flight = self.in_flight.lock().await.clone();
+        (
+            items.iter().map(QueuedAnimeListUpdate::item).collect(),
+            in_flight,
+        )
+    }
+
+    #[cfg(test)]
+    async fn len(&self) -> usize {
+        self.items.lock().await.len()
+    }
+}
+
+// Index of the first update the worker can run now, or when the earliest
+// waiting one becomes due. An update never overtakes a waiting one for the
+// same entry, so the older values being retried never land after it.
+fn next_ready(
+    items: &VecDeque<QueuedAnimeListUpdate>,
+    now: Instant,
+) -> Result<usize, Option<Instant>> {
+    let mut next_due: Option<Instant> = None;
+    for (index, update) in items.iter().enumerate() {
+        if let Some(not_before) = update.not_before.filter(|&not_before| not_before > now) {
+            next_due = Some(next_due.map_or(not_before, |due| due.min(not_before)));
+            continue;
+        }
+
+        let blocked = items.iter().take(index).any(|earlier| {
+            !earlier.is_due(now) && earlier.request.targets_same_entry(&update.request)
+        });
+        if !blocked {
+            return Ok(index);
+        }
+    }
+
+    Err(next_due)
+}
+
+struct AnimeListUpdateQueueState {
+    app: tauri::AppHandle,
+    pending_updates: PendingAnimeListUpdates,
+    dead_letters: DeadLetterStore,
+    mirror_to_linked_providers: AtomicBool,
+    supervisor_running: AtomicBool,
+}
+
+impl AnimeListUpdateQueueState {
+    fn new(app: tauri::AppHandle, config: ListUpdatesConfig) -> Self {
+        let data_dir = match app.path().app_local_data_dir() {
+            Ok(dir) => Some(dir),
+            Err(err) => {
+                update_worker_log!("Anime list update journal location unavailable: {err}");
+                None
+            }
+        };
+        let (journal, pending) =
+            UpdateJournal::open(data_dir.as_ref().map(|dir| dir.join(JOURNAL_FILE_NAME)));
+        if !pending.is_empty() {
+            update_worker_log!(
+                "Anime list updates restored from journal (count={})",
+                pending.len()
+            );
+        }
+        let pending_updates =
+            PendingAnimeListUpdates::restore(UPDATE_QUEUE_CAPACITY, journal, pending);
+        let mut dead_letters =
+            DeadLetterStore::load(data_dir.map(|dir| dir.join(DEAD_LETTERS_FILE_NAME)));
+        if let Some(id) = dead_letters.max_id() {
+            pending_updates.reserve_ids_through(id);
+        }
+
+        Self {
+            app,
+            pending_updates,
+            dead_letters,
+            mirror_to_linked_providers: AtomicBool::new(config.mirror_to_linked_providers),
+            supervisor_running: AtomicBool::new(false),
+        }
+    }
+
+    // Returns the id of the queued update, which is an existing one when the
+    // request was merged into it.
+    async fn enqueue(self: &Arc<Self>, update: AnimeListUpdateRequest) -> Result<u64, String> {
+        let queued = self.pending_updates.enqueue(update).await?;
+        self.emit(ListUpdateEventKind::Queued, &queued.event(None, None));
+        self.ensure_worker_supervisor();
+        Ok(queued.id)
+    }
+
+    // Queues the update once more for every other provider the user is
+    // signed in to. Mirrors are never mirrored again.
+    async fn enqueue_mirrors(self: &Arc<Self>, update: &AnimeListUpdateRequest, media_id: u64) {
+        if update.mirror_from.is_some() || !self.mirror_to_linked_providers.load(Ordering::Acquire)
+        {
+            return;
+        }
+
+        for provider_id in MIRROR_PROVIDER_IDS {
+            if provider_id == update.provider_id
+                || get_access_token(&self.app, provider_id).await.is_err()
+            {
+                continue;
+            }
+
+            let mirror = mirror_request(update, media_id, provider_id);
+            let context = update_log_context(&mirror);
+            if let Err(err) = self.enqueue(mirror.clone()).await {
+                update_worker_log!(
+                    "Anime list update mirror could not be queued ({context}): {err}"
+                );
+                // Dead-lettered so the mirror can still be resubmitted.
+                let id = self.pending_updates.next_id.fetch_add(1, Ordering::Relaxed);
+                self.dead_letter(QueuedAnimeListUpdate::new(id, mirror), err)
+                    .await;
+            }
+        }
+    }
+
+    fn emit(&self, kind: ListUpdateEventKind, event: &ListUpdateEvent) {
+        emit_list_update_event(&self.app, kind, event);
+    }
+
+    async fn dead_letter(&self, update: QueuedAnimeListUpdate, error: String) {
+        let failed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let dead_letter = DeadLetteredUpdate {
+            id: update.id,
+            request: update.request.clone(),
+            error: error.clone(),
+            attempts: update.attempts + 1,
+            failed_at,
+        };
+
+        if let Err(err) = self.dead_letters.push(dead_letter).await {
+            update_worker_log!("Anime list update dead letter write failed: {err}");
+        }
+        self.drop_update(&update, &error).await;
+    }
+
+    async fn drop_update(&self, update: &QueuedAnimeListUpdate, error: &str) {
+        self.pending_updates.finish(update.id).await;
+        self.emit(
+            ListUpdateEventKind::Dropped,
+            &update.event(Some(error), None),
+        );
+    }
+
+    fn ensure_worker_supervisor(self: &Arc<Self>) {
+        if self
+            .supervisor_running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return;
+        }
+
+        let state = Arc::clone(self);
+        tauri::async_runtime::spawn(async move {
+            let _supervisor_guard = WorkerSupervisorGuard::new(Arc::clone(&state));
+            run_worker_supervisor(state).await;
+        });
+    }
+}
+
+struct WorkerSupervisorGuard {
+    state: Arc<AnimeListUpdateQueueState>,
+}
+
+impl WorkerSupervisorGuard {
+    fn new(state: Arc<AnimeListUpdateQueueState>) -> Self {
+        Self { state }
+    }
+}
+
+impl Drop for WorkerSupervisorGuard {
+    fn drop(&mut self) {
+        self.state
+            .supervisor_running
+            .store(false, Ordering::Release);
+    }
+}
+
+pub struct AnimeListUpdateQueue {
+    state: Arc<AnimeListUpdateQueueState>,
+}
+
+impl AnimeListUpdateQueue {
+    pub fn new(app: tauri::AppHandle, config: ListUpdatesConfig) -> Self {
+        let queue = Self {
+            state: Arc::new(AnimeListUpdateQueueState::new(app, config)),
+        };
+        queue.ensure_worker_supervisor();
+        queue
+    }
+
+    pub async fn enqueue(&self, update: AnimeListUpdateRequest) -> Result<u64, String> {
+        self.state.enqueue(update).await
+    }
+
+    pub fn configure(&self, config: ListUpdatesConfig) {
+        self.state
+            .mirror_to_linked_providers
+            .store(config.mirror_to_linked_providers, Ordering::Release);
+    }
+
+    pub async fn snapshot(&self) -> AnimeListUpdateQueueSnapshot {
+        let (pending, in_flight) = self.state.pending_updates.snapshot().await;
+        AnimeListUpdateQueueSnapshot {
+            pending,
+            in_flight,
+            recently_failed: self.state.dead_letters.recent(RECENT_FAILURE_LIMIT).await,
+        }
+    }
+
+    pub async fn dead_letters(&self) -> Vec<DeadLetteredUpdate> {
+        self.state.dead_letters.list().await
+    }
+
+    pub async fn resubmit_dead_letter(&self, id: u64) -> Result<u64, String> {
+        let dead_letter = self.state.dead_letters.take(id).await?;
+        match self.enqueue(dead_letter.request.clone()).await {
+            Ok(id) => Ok(id),
+            Err(err) => {
+                self.state.dead_letters.push(dead_letter).await?;
+                Err(err)
+            }
+        }
+    }
+
+    pub async fn discard_dead_letter(&self, id: u64) -> Result<(), String> {
+        self.state.dead_letters.take(id).await.map(|_| ())
+    }
+
+    fn ensure_worker_supervisor(&self) {
+        self.state.ensure_worker_supervisor();
+    }
+}
+
+#[tauri::command]
+pub async fn enqueue_anime_list_update(
+    update: AnimeListUpdateRequest,
+    app: tauri::AppHandle,
+) -> Result<u64, String> {
+    app.state::<AnimeListUpdateQueue>().enqueue(update).await
+}
+
+#[tauri::command]
+pub async fn configure_anime_list_updates(
+    config: ListUpdatesConfig,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    app.state::<AnimeListUpdateQueue>().configure(config);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_anime_list_update_queue(
+    app: tauri::AppHandle,
+) -> Result<AnimeListUpdateQueueSnapshot, String> {
+    Ok(app.state::<AnimeListUpdateQueue>().snapshot().await)
+}
+
+#[tauri::command]
+pub async fn list_dead_letter_anime_list_updates(
+    app: tauri::AppHandle,
+) -> Result<Vec<DeadLetteredUpdate>, String> {
+    Ok(app.state::<AnimeListUpdateQueue>().dead_letters().await)
+}
+
+#[tauri::command]
+pub async fn resubmit_dead_letter_anime_list_update(
+    id: u64,
+    app: tauri::AppHandle,
+) -> Result<u64, String> {
+    app.state::<AnimeListUpdateQueue>()
+        .resubmit_dead_letter(id)
+        .await
+}
+
+#[tauri::command]
+pub async fn discard_dead_letter_anime_list_update(
+    id: u64,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    app.state::<AnimeListUpdateQueue>()
+        .discard_dead_letter(id)
+        .await
+}
+
+fn update_log_context(update: &AnimeListUpdateRequest) -> String {
+    format!(
+        "provider={}, list_type={:?}, entry_id={:?}, media_id={:?}, mirror_from={:?}",
+        update.provider_id,
+        update.list_type.unwrap_or_default(),
+        update.entry_id,
+        update.media_id,
+        update.mirror_from
+    )
+}
+
+async fn run_worker_supervisor(state: Arc<AnimeListUpdateQueueState>) {
+    let client = reqwest::Client::new();
+    let interval = Duration::from_millis(UPDATE_INTERVAL_MS);
+    let restart_delay = Duration::from_millis(WORKER_RESTART_DELAY_MS);
+
+    update_worker_log!(
+        "Anime list update worker supervisor started (queue_capacity={}, interval_ms={}, crash_retry_limit={}, transient_retry_limit={})",
+        UPDATE_QUEUE_CAPACITY,
+        UPDATE_INTERVAL_MS,
+        WORKER_CRASH_RETRY_LIMIT,
+        TRANSIENT_RETRY_LIMIT
+    );
+
+    loop {
+        let queued_update = state.pending_updates.pop_front().await;
+        let context = update_log_context(&queued_update.request);
+        update_worker_log!("Anime list update received ({context})");
+        state.emit(
+            ListUpdateEventKind::Started,
+            &queued_update.event(None, None),
+        );
+
+        let app = state.app.clone();
+        let client = client.clone();
+        let update = queued_update.request.clone();
+        let worker =
+            tauri::async_runtime::spawn(async move { handle_update(&app, &client, &update).await });
+
+        match worker.await {
+            Ok(Ok(media_id)) => {
+                update_worker_log!("Anime list update completed ({context})");
+                state.pending_updates.finish(queued_update.id).await;
+                state.emit(
+                    ListUpdateEventKind::Succeeded,
+                    &queued_update.event(None, None),
+                );
+                if let Some(media_id) = media_id {
+                    state
+                        .enqueue_mirrors(&queued_update.request, media_id)
+                        .await;
+                }
+            }
+            Ok(Err(err)) if err.transient => {
+                match queued_update.clone().schedule_transient_retry(!err.offline) {
+                    Some(mut retry_update) => {
+                        let delay = retry_delay(
+                            retry_update.attempts,
+                            err.retry_after,
+                            rand::thread_rng().gen(),
+                        );
+                        update_worker_log!(
+                            "Anime list update failed, retrying in {}ms ({context}): {err}",
+                            delay.as_millis()
+                        );
+                        retry_update.last_error = Some(err.message.clone());
+                        // The update waits in the queue rather than holding up the
+                        // worker, so other entries keep going in the meantime.
+                        retry_update.not_before = Some(Instant::now() + delay);
+                        match state.pending_updates.requeue_front(retry_update).await {
+                            Ok(()) => state.emit(
+                                ListUpdateEventKind::Failed,
+                                &queued_update.event(Some(&err.message), Some(delay)),
+                            ),
+                            Err(requeue_err) => {
+                                update_worker_log!(
+                                "Anime list update requeue failed after transient failure ({context}): {requeue_err}"
+                            );
+                                state.emit(
+                                    ListUpdateEventKind::Failed,
+                                    &queued_update.event(Some(&err.message), None),
+                                );
+                                state.dead_letter(queued_update, err.message).await;
+                            }
+                        }
+                    }
+                    None => {
+                        update_worker_log!(
+                        "Anime list update dead-lettered after transient retries exhausted ({context}): {err}"
+                    );
+                        state.emit(
+                            ListUpdateEventKind::Failed,
+                            &queued_update.event(Some(&err.message), None),
+                        );
+                        state.dead_letter(queued_update, err.message).await;
+                    }
+                }
+            }
+            Ok(Err(err)) => {
+                update_worker_log!("Anime list update dead-lettered ({context}): {err}");
+                state.emit(
+                    ListUpdateEventKind::Failed,
+                    &queued_update.event(Some(&err.message), None),
+                );
+                state.dead_letter(queued_update, err.message).await;
+            }
+            Err(join_err) => {
+                let failure = join_error_message(join_err);
+                update_worker_log!("Anime list update worker crashed ({context}): {failure}");
+
+                let failed_event = queued_update.event(Some(&failure), Some(restart_delay));
+                if let Some(mut retry_update) = queued_update.clone().schedule_retry() {
+                    retry_update.last_error = Some(failure.clone());
+                    match state.pending_updates.requeue_front(retry_update).await {
+                        Ok(()) => state.emit(ListUpdateEventKind::Failed, &failed_event),
+                        Err(err) => {
+                            update_worker_log!(
+                                "Anime list update requeue failed after worker crash ({context}): {err}"
+                            );
+                            state.emit(
+                                ListUpdateEventKind::Failed,
+                                &queued_update.event(Some(&failure), None),
+                            );
+                            state.drop_update(&queued_update, &failure).await;
+                        }
+                    }
+                } else {
+                    update_worker_log!(
+                        "Anime list update dropped after worker crash retries exhausted ({context})"
+                    );
+                    state.emit(
+                        ListUpdateEventKind::Failed,
+                        &queued_update.event(Some(&failure), None),
+                    );
+                    state.drop_update(&queued_update, &failure).await;
+                }
+
+                tokio::time::sleep(restart_delay).await;
+                continue;
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+fn join_error_message(join_err: tauri::Error) -> String {
+    match join_err {
+        tauri::Error::JoinError(join_err) if join_err.is_panic() => {
+            panic_payload_message(join_err.into_panic())
+        }
+        other => other.to_string(),
+    }
+}
+
+fn panic_payload_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&'static str>() {
+        return (*message).to_string();
+    }
+
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message.clone();
+    }
+
+    "unknown panic payload".to_string()
+}
+
+fn validate_supported_provider(provider_id: &str) -> Result<(), String> {
+    match provider_id {
+        ANILIST_PROVIDER_ID | MAL_PROVIDER_ID => Ok(()),
+        _ => Err(format!("Provider not supported: {provider_id}")),
+    }
+}
+
+// Returns the provider's media id for the updated entry when it is known,
+// which is what mirrors to the other providers are looked up by.
+async fn handle_update(
+    app: &tauri::AppHandle,
+    client: &reqwest::Client,
+    update: &AnimeListUpdateRequest,
+) -> Result<Option<u64>, ListUpdateError> {
+    validate_supported_provider(&update.provider_id)?;
+
+    let resolved;
+    let update = match update.mirror_from.as_deref() {
+        Some(source_provider_id) => {
+            resolved = resolve_mirror_update(app, client, update, source_provider_id).await?;
+            &resolved
+        }
+        None => update,
+    };
+
+    match update.provider_id.as_str() {
+        ANILIST_PROVIDER_ID => update_anilist_list_entry(app, client, update).await,
+        MAL_PROVIDER_ID => update_myanimelist_list_entry(app, client, update)
+            .await
+            .map(Some),
+        _ => unreachable!("provider should have been validated"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::runtime::Runtime;
+
+    fn sample_update() -> AnimeListUpdateRequest {
+        AnimeListUpdateRequest {
+            provider_id: MAL_PROVIDER_ID.to_string(),
+            list_type: Some(ListType::Anime),
+            entry_id: Some(1),
+            media_id: None,
+            user_status: Some("completed".to_string()),
+            user_score: None,
+            user_episodes_watched: None,
+            user_volumes_read: None,
+            user_chapters_read: None,
+            is_rewatching: None,
+            is_rereading: None,
+            user_comments: None,
+            user_num_times_rewatched: None,
+            user_num_times_reread: None,
+            user_start_date: None,
+            user_finish_date: None,
+            mirror_from: None,
+        }
+    }
+
+    #[test]
+    fn list_type_defaults_to_anime_and_request_aliases_deserialize() {
+        let request: AnimeListUpdateRequest = serde_json::from_value(serde_json::json!({
+            "providerId": "myanimelist",
+            "id": 42,
+            "mediaId": 7,
+            "userStatus": "completed"
+        }))
+        .expect("request should deserialize");
+
+        assert_eq!(request.provider_id, "myanimelist");
+        assert!(matches!(
+            request.list_type.unwrap_or_default(),
+            ListType::Anime
+        ));
+        assert_eq!(request.entry_id, Some(42));
+        assert_eq!(request.media_id, Some(7));
+        assert_eq!(request.user_status.as_deref(), Some("completed"));
+    }
+
+    #[test]
+    fn validate_supported_provider_accepts_known_ids_and_rejects_others() {
+        assert!(validate_supported_provider(ANILIST_PROVIDER_ID).is_ok());
+        assert!(validate_supported_provider(MAL_PROVIDER_ID).is_ok());
+        assert_eq!(
+            validate_supported_provider("unknown").unwrap_err(),
+            "Provider not supported: unknown"
+        );
+    }
+
+    #[test]
+    fn pending_updates_accept_items_without_worker() {
+        let runtime = Runtime::new().expect("runtime should build");
+        let pending = PendingAnimeListUpdates::new(2);
+
+        runtime
+            .block_on(async { pending.enqueue(sample_update()).await })
+            .expect("enqueue should succeed");
+
+        assert_eq!(runtime.block_on(async { pending.len().await }), 1);
+    }
+
+    #[test]
+    fn pending_updates_requeue_front_preserves_crashed_item() {
+        let runtime = Runtime::new().expect("runtime should build");
+        let pending = PendingAnimeListUpdates::new(3);
+        let first = sample_update();
+        let mut second = sample_update();
+        second.entry_id = Some(2);
+
+        runtime
+            .block_on(async { pending.enqueue(first.clone()).await })
+            .expect("first enqueue should succeed");
+        runtime
+            .block_on(async { pending.enqueue(second.clone()).await })
+            .expect("second enqueue should succeed");
+
+        let popped = runtime.block_on(async { pending.pop_front().await });
+        runtime
+            .block_on(async { pending.requeue_front(popped.clone()).await })
+            .expect("requeue should succeed");
+
+        let first_again = runtime.block_on(async { pending.pop_front().await });
+        let second_after = runtime.block_on(async { pending.pop_front().await });
+
+        assert_eq!(first_again.request.entry_id, first.entry_id);
+        assert_eq!(second_after.request.entry_id, second.entry_id);
+    }
+
+    #[test]
+    fn pending_updates_restore_journaled_items_past_capacity() {
+        let runtime = Runtime::new().expect("runtime should build");
+        let mut restored = sample_update();
+        restored.entry_id = Some(9);
+        let pending = PendingAnimeListUpdates::restore(
+            1,
+            UpdateJournal::open(None).0,
+            vec![(4, sample_update()), (7, restored)],
+        );
+
+        assert_eq!(runtime.block_on(async { pending.len().await }), 2);
+        let mut overflow = sample_update();
+        overflow.entry_id = Some(12);
+        assert_eq!(
+            runtime
+                .block_on(async { pending.enqueue(overflow).await })
+                .unwrap_err(),
+            "Update queue is full"
+        );
+
+        let first = runtime.block_on(async { pending.pop_front().await });
+        let second = runtime.block_on(async { pending.pop_front().await });
+        assert_eq!((first.id, second.id), (4, 7));
+        assert_eq!(second.request.entry_id, Some(9));
+
+        runtime
+            .block_on(async { pending.enqueue(sample_update()).await })
+            .expect("enqueue should succeed");
+        let next = runtime.block_on(async { pending.pop_front().await });
+        assert_eq!(next.id, 8);
+    }
+
+    #[test]
+    fn pending_updates_snapshot_tracks_the_in_flight_update() {
+        let runtime = Runtime::new().expect("runtime should build");
+        let pending = PendingAnimeListUpdates::new(3);
+        let mut second = sample_update();
+        second.entry_id = Some(2);
+
+        runtime.block_on(async {
+            pending
+                .enqueue(sample_update())
+                .await
+                .expect("enqueue should succeed");
+            pending
+                .enqueue(second)
+                .await
+                .expect("enqueue should succeed");
+        });
+
+        let popped = runtime.block_on(pending.pop_front());
+        let (queued, in_flight) = runtime.block_on(pending.snapshot());
+        assert_eq!(
+            queued.iter().map(|item| item.id).collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert_eq!(in_flight, Some(popped.item()));
+
+        let mut retry = popped
+            .schedule_transient_retry(true)
+            .expect("retry should exist");
+        retry.last_error = Some("AniList update failed: 503".to_string());
+        runtime
+            .block_on(pending.requeue_front(retry))
+            .expect("requeue should succeed");
+        let (queued, in_flight) = runtime.block_on(pending.snapshot());
+        assert_eq!(in_flight, None);
+        assert_eq!(queued[0].attempts, 1);
+        assert_eq!(
+            queued[0].last_error.as_deref(),
+            Some("AniList update failed: 503")
+        );
+
+        let popped = runtime.block_on(pending.pop_front());
+        runtime.block_on(pending.finish(popped.id));
+        assert_eq!(runtime.block_on(pending.snapshot()).1, None);
+    }
+
+    #[test]
+    fn list_update_event_serializes_attempt_and_retry_delay() {
+        let mut queued = QueuedAnimeListUpdate::new(7, sample_update());
+        queued.attempts = 2;
+
+        let event =
+            serde_json::to_value(queued.event(Some("rate limited"), Some(Duration::from_secs(4))))
+                .expect("event should serialize");
+
+        assert_eq!(event["id"], 7);
+        assert_eq!(event["attempt"], 3);
+        assert_eq!(event["error"], "rate limited");
+        assert_eq!(event["retryInMs"], 4000);
+        assert_eq!(event["request"]["providerId"], MAL_PROVIDER_ID);
+    }
+
+    #[test]
+    fn merge_overrides_only_the_fields_set_on_the_later_update() {
+        let mut queued = AnimeListUpdateRequest {
+            provider_id: ANILIST_PROVIDER_ID.to_string(),
+            entry_id: Some(10),
+            user_status: Some("current".to_string()),
+            user_score: Some(70),
+            user_episodes_watched: Some(3),
+            user_comments: Some("rewatch later".to_string()),
+            ..Default::default()
+        };
+
+        queued.merge(AnimeListUpdateRequest {
+            provider_id: ANILIST_PROVIDER_ID.to_string(),
+            list_type: Some(ListType::Anime),
+            media_id: Some(52991),
+            user_status: Some("completed".to_string()),
+            user_episodes_watched: Some(4),
+            user_finish_date: Some("2026-10-18".to_string()),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            queued,
+            AnimeListUpdateRequest {
+                provider_id: ANILIST_PROVIDER_ID.to_string(),
+                list_type: Some(ListType::Anime),
+                entry_id: Some(10),
+                media_id: Some(52991),
+                user_status: Some("completed".to_string()),
+                user_score: Some(70),
+                user_episodes_watched: Some(4),
+                user_comments: Some("rewatch later".to_string()),
+                user_finish_date: Some("2026-10-18".to_string()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn targets_same_entry_matches_provider_list_type_and_ids() {
+        let entry = |entry_id: Option<u64>, media_id: Option<u64>| AnimeListUpdateRequest {
+            provider_id: MAL_PROVIDER_ID.to_string(),
+            entry_id,
+            media_id,
+            ..Default::default()
+        };
+
+        assert!(entry(Some(1), None).targets_same_entry(&entry(Some(1), Some(7))));
+        assert!(entry(None, Some(7)).targets_same_entry(&entry(Some(1), Some(7))));
+        assert!(!entry(Some(1), Some(7)).targets_same_entry(&entry(Some(2), Some(7))));
+        assert!(!entry(Some(1), None).targets_same_entry(&entry(None, None)));
+        assert!(!entry(None, None).targets_same_entry(&entry(None, None)));
+
+        let manga = AnimeListUpdateRequest {
+            list_type: Some(ListType::Manga),
+            ..entry(Some(1), None)
+        };
+        assert!(!manga.targets_same_entry(&entry(Some(1), None)));
+        let anilist = AnimeListUpdateRequest {
+            provider_id: ANILIST_PROVIDER_ID.to_string(),
+            ..entry(Some(1), None)
+        };
+        assert!(!anilist.targets_same_entry(&entry(Some(1), None)));
+        // Mirrors carry the source provider's media id until they run.
+        let mirror = AnimeListUpdateRequest {
+            mirror_from: Some(ANILIST_PROVIDER_ID.to_string()),
+            ..entry(None, Some(7))
+        };
+        assert!(!mirror.targets_same_entry(&entry(None, Some(7))));
+        assert!(mirror.targets_same_entry(&mirror.clone()));
+    }
+
+    #[test]
+    fn pending_updates_coalesce_updates_for_the_same_entry() {
+        let runtime = Runtime::new().expect("runtime should build");
+        let pending = PendingAnimeListUpdates::new(2);
+        let episode = |entry_id: u64, watched: u32| AnimeListUpdateRequest {
+            entry_id: Some(entry_id),
+            user_episodes_watched: Some(watched),
+            ..sample_update()
+        };
+
+        runtime.block_on(async {
+            for watched in 1..=5 {
+                pending
+                    .enqueue(episode(1, watched))
+                    .await
+                    .expect("enqueue should succeed");
+            }
+            pending
+                .enqueue(episode(2, 8))
+                .await
+                .expect("enqueue should succeed");
+            // Merging does not take up a slot in a full queue.
+            pending
+                .enqueue(episode(2, 9))
+                .await
+                .expect("merge should succeed");
+        });
+
+        assert_eq!(runtime.block_on(pending.len()), 2);
+        let first = runtime.block_on(pending.pop_front());
+        let second = runtime.block_on(pending.pop_front());
+        assert_eq!(
+            (first.id, first.request.user_episodes_watched),
+            (1, Some(5))
+        );
+        assert_eq!(first.request.user_status.as_deref(), Some("completed"));
+        assert_eq!(
+            (second.id, second.request.user_episodes_watched),
+            (2, Some(9))
+        );
+    }
+
+    #[test]
+    fn pending_updates_reject_when_queue_is_full() {
+        let runtime = Runtime::new().expect("runtime should build");
+        let pending = PendingAnimeListUpdates::new(1);
+
+        runtime
+            .block_on(async { pending.enqueue(sample_update()).await })
+            .expect("first enqueue should succeed");
+
+        let mut other_entry = sample_update();
+        other_entry.entry_id = Some(2);
+        let error = runtime.block_on(async { pending.enqueue(other_entry).await });
+
+        assert_eq!(error.unwrap_err(), "Update queue is full");
+    }
+
+    #[test]
+    fn queued_update_limits_worker_crash_retries() {
+        let queued = QueuedAnimeListUpdate::new(1, sample_update());
+
+        let queued = queued.schedule_retry().expect("retry 1 should exist");
+        let queued = queued.schedule_retry().expect("retry 2 should exist");
+        let queued = queued.schedule_retry().expect("retry 3 should exist");
+
+        assert!(queued.schedule_retry().is_none());
+    }
+
+    #[test]
+    fn queued_update_limits_transient_retries() {
+        let mut queued = QueuedAnimeListUpdate::new(1, sample_update());
+        for attempt in 1..=TRANSIENT_RETRY_LIMIT {
+            queued = queued
+                .schedule_transient_retry(true)
+                .unwrap_or_else(|| panic!("retry {attempt} should exist"));
+            assert_eq!(queued.attempts, attempt);
+        }
+
+        assert!(queued.schedule_transient_retry(true).is_none());
+    }
+
+    #[test]
+    fn queued_update_keeps_retrying_offline_failures() {
+        let mut queued = QueuedAnimeListUpdate::new(1, sample_update());
+        for _ in 0..TRANSIENT_RETRY_LIMIT * 2 {
+            queued = queued
+                .schedule_transient_retry(false)
+                .expect("offline retry should exist");
+        }
+
+        assert_eq!(queued.attempts, TRANSIENT_RETRY_LIMIT * 2);
+        assert_eq!(queued.counted_retries, 0);
+        assert!(queued.schedule_transient_retry(true).is_some());
+    }
+
+    #[test]
+    fn pending_updates_skip_deferred_retries_without_reordering_their_entry() {
+        let runtime = Runtime::new().expect("runtime should build");
+        let pending = PendingAnimeListUpdates::new(4);
+        let mut other = sample_update();
+        other.entry_id = Some(2);
+        let mut newer = sample_update();
+        newer.user_episodes_watched = Some(5);
+
+        runtime.block_on(async {
+            pending
+                .enqueue(sample_update())
+                .await
+                .expect("enqueue should succeed");
+            pending
+                .enqueue(other)
+                .await
+                .expect("enqueue should succeed");
+        });
+
+        let failed = runtime.block_on(pending.pop_front());
+        let retry_at = runtime.block_on(async {
+            // Arrives while the first update is in flight, so it is not merged.
+            pending
+                .enqueue(newer)
+                .await
+                .expect("enqueue should succeed");
+
+            let mut retry = failed
+                .schedule_transient_retry(true)
+                .expect("retry should exist");
+            let retry_at = Instant::now() + Duration::from_secs(60);
+            retry.not_before = Some(retry_at);
+            pending
+                .requeue_front(retry)
+                .await
+                .expect("requeue should succeed");
+            retry_at
+        });
+
+        let next = runtime.block_on(pending.pop_front());
+        assert_eq!(next.request.entry_id, Some(2));
+        runtime.block_on(pending.finish(next.id));
+
+        let items = runtime.block_on(pending.items.lock()).clone();
+        assert_eq!(
+            items.iter().map(|item| item.id).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(next_ready(&items, Instant::now()), Err(Some(retry_at)));
+        assert_eq!(next_ready(&items, retry_at), Ok(0));
+    }
+}
@@ -0,0 +1,244 @@
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tauri_plugin_http::reqwest;
+
+const RETRY_BASE_DELAY_MS: u64 = 2_000;
+const RETRY_MAX_DELAY_MS: u64 = 15 * 60 * 1000;
+const RETRY_AFTER_MAX: Duration = Duration::from_secs(60 * 60);
+const HTTP_DATE_MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+// Transient failures are worth retrying as they are; permanent ones will fail
+// the same way until the request or the account changes. Offline failures
+// are transient ones caused by the user's side (no connection, no usable
+// token) rather than by the update, so they do not use up its retries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListUpdateError {
+    pub transient: bool,
+    pub offline: bool,
+    pub message: String,
+    pub retry_after: Option<Duration>,
+}
+
+impl ListUpdateError {
+    pub fn transient(message: String) -> Self {
+        Self {
+            transient: true,
+            offline: false,
+            message,
+            retry_after: None,
+        }
+    }
+
+    pub fn offline(message: String) -> Self {
+        Self {
+            transient: true,
+            offline: true,
+            message,
+            retry_after: None,
+        }
+    }
+
+    pub fn permanent(message: String) -> Self {
+        Self {
+            transient: false,
+            offline: false,
+            message,
+            retry_after: None,
+        }
+    }
+
+    pub fn from_status(
+        status: reqwest::StatusCode,
+        retry_after: Option<Duration>,
+        message: String,
+    ) -> Self {
+        let transient = status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            || status == reqwest::StatusCode::REQUEST_TIMEOUT
+            || status.is_server_error();
+
+        Self {
+            transient,
+            offline: false,
+            message,
+            retry_after: retry_after.filter(|_| transient),
+        }
+    }
+
+    // Decode failures mean the provider answered with something unexpected;
+    // everything else never got a usable response. A failed connection is
+    // most likely the user being offline.
+    pub fn from_transport(error: &reqwest::Error, message: String) -> Self {
+        if error.is_decode() || error.is_builder() {
+            Self::permanent(message)
+        } else if error.is_connect() {
+            Self::offline(message)
+        } else {
+            Self::transient(message)
+        }
+    }
+}
+
+impl From<String> for ListUpdateError {
+    fn from(message: String) -> Self {
+        Self::permanent(message)
+    }
+}
+
+impl fmt::Display for ListUpdateError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+pub fn retry_after_header(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_retry_after(value, SystemTime::now()))
+}
+
+// `Retry-After` is either a number of seconds or an HTTP date.
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let retry_at = parse_http_date(value)?;
+    Some(retry_at.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+// Only the IMF-fixdate form ("Sun, 06 Nov 1994 08:49:37 GMT") is accepted;
+// the obsolete forms are not sent by the providers we talk to.
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let (_, date) = value.split_once(", ")?;
+    let parts: Vec<&str> = date.split_whitespace().collect();
+    let [day, month, year, time, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+
+    let day: u32 = day.parse().ok()?;
+    let month = HTTP_DATE_MONTHS.iter().position(|name| name == month)? as u32 + 1;
+    let year: i64 = year.parse().ok()?;
+    let clock = time
+        .split(':')
+        .map(|part| part.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let [hours, minutes, seconds] = clock.as_slice() else {
+        return None;
+    };
+    if !(1..=31).contains(&day) || *hours > 23 || *minutes > 59 || *seconds > 60 {
+        return None;
+    }
+
+    let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(days * 86_400 + hours * 3_600 + minutes * 60 + seconds))
+}
+
+// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+// Delay before retry number `attempt` (starting at 1). `jitter` is a random
+// value in [0, 1); half of the exponential delay is kept and the rest is
+// randomized so clients that failed together do not retry together.
+pub(super) fn retry_delay(attempt: u32, retry_after: Option<Duration>, jitter: f64) -> Duration {
+    if let Some(retry_after) = retry_after {
+        return retry_after.min(RETRY_AFTER_MAX);
+    }
+
+    let exponent = attempt.saturating_sub(1).min(20);
+    let delay_ms = RETRY_BASE_DELAY_MS
+        .saturating_mul(1 << exponent)
+        .min(RETRY_MAX_DELAY_MS);
+    let half_ms = delay_ms / 2;
+
+    Duration::from_millis(half_ms + (half_ms as f64 * jitter.clamp(0.0, 1.0)) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_status_separates_transient_and_permanent_failures() {
+        let retry_after = Some(Duration::from_secs(30));
+        for status in [
+            reqwest::StatusCode::TOO_MANY_REQUESTS,
+            reqwest::StatusCode::REQUEST_TIMEOUT,
+            reqwest::StatusCode::BAD_GATEWAY,
+            reqwest::StatusCode::SERVICE_UNAVAILABLE,
+        ] {
+            let error = ListUpdateError::from_status(status, retry_after, status.to_string());
+            assert!(error.transient, "{status} should be transient");
+            assert_eq!(error.retry_after, retry_after);
+        }
+
+        for status in [
+            reqwest::StatusCode::BAD_REQUEST,
+            reqwest::StatusCode::UNAUTHORIZED,
+            reqwest::StatusCode::NOT_FOUND,
+        ] {
+            let error = ListUpdateError::from_status(status, retry_after, status.to_string());
+            assert!(!error.transient, "{status} should be permanent");
+            assert_eq!(error.retry_after, None);
+        }
+    }
+
+    #[test]
+    fn parse_retry_after_accepts_seconds_and_http_dates() {
+        let now = UNIX_EPOCH + Duration::from_secs(784_111_700);
+
+        assert_eq!(
+            parse_retry_after(" 120 ", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT", now),
+            Some(Duration::from_secs(77))
+        );
+        assert_eq!(
+            parse_retry_after(
+                "Sun, 06 Nov 1994 08:49:37 GMT",
+                now + Duration::from_secs(600)
+            ),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(
+            parse_retry_after("Sunday, 06-Nov-94 08:49:37 GMT", now),
+            None
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn retry_delay_grows_exponentially_with_jitter_and_honors_retry_after() {
+        assert_eq!(retry_delay(1, None, 0.0), Duration::from_secs(1));
+        assert_eq!(retry_delay(1, None, 1.0), Duration::from_secs(2));
+        assert_eq!(retry_delay(3, None, 0.5), Duration::from_secs(6));
+        assert_eq!(
+            retry_delay(40, None, 1.0),
+            Duration::from_millis(RETRY_MAX_DELAY_MS)
+        );
+        assert_eq!(
+            retry_delay(1, Some(Duration::from_secs(90)), 0.0),
+            Duration::from_secs(90)
+        );
+        assert_eq!(
+            retry_delay(1, Some(Duration::from_secs(86_400)), 0.0),
+            RETRY_AFTER_MAX
+        );
+    }
+}
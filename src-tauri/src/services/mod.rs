@@ -3,3 +3,4 @@ pub mod anime_list_updates;
 pub mod discord_rpc;
 pub mod myanimelist;
 pub mod player_detection;
+pub mod scrobbler;
@@ -5,7 +5,9 @@ use tauri_plugin_zustand::ManagerExt;
 
 use crate::auth::mal::PROVIDER_ID as MAL_PROVIDER_ID;
 use crate::auth::token_manager::get_access_token;
-use crate::services::anime_list_updates::AnimeListUpdateRequest;
+use crate::services::anime_list_updates::{
+    retry_after_header, AnimeListUpdateRequest, ListUpdateError,
+};
 
 use super::mapping::{
     map_anime_entry_to_domain, map_mal_statistics, map_manga_entry_to_domain,
@@ -282,8 +284,12 @@ pub async fn update_myanimelist_list_entry(
     app: &tauri::AppHandle,
     client: &reqwest::Client,
     update: &AnimeListUpdateRequest,
-) -> Result<(), String> {
-    let token = get_access_token(app, MAL_PROVIDER_ID).await?;
+) -> Result<u64, ListUpdateError> {
+    // A missing token may come back after a refresh or a new login, so the
+    // queue keeps retrying rather than giving up on the update.
+    let token = get_access_token(app, MAL_PROVIDER_ID)
+        .await
+        .map_err(ListUpdateError::offline)?;
     let payload = build_mal_update_payload(update)?;
 
     let update_base_url = match payload.list_type {
@@ -298,11 +304,19 @@ pub async fn update_myanimelist_list_entry(
         .form(&payload.params)
         .send()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| ListUpdateError::from_transport(&e, e.to_string()))?;
 
     let status = response.status();
-    let body = response.text().await.map_err(|e| e.to_string())?;
+    let retry_after = retry_after_header(response.headers());
+    let body = response
+        .text()
+        .await
+        .map_err(|e| ListUpdateError::from_transport(&e, e.to_string()))?;
     validate_update_response(status, &body)
+        .map_err(|message| ListUpdateError::from_status(status, retry_after, message))?;
+
+    // MyAnimeList keys list entries by the media id.
+    Ok(payload.entry_id)
 }
 
 #[tauri::command]
@@ -546,6 +560,7 @@ mod tests {
             user_num_times_reread: None,
             user_start_date: None,
             user_finish_date: None,
+            mirror_from: None,
         }
     }
 
@@ -0,0 +1,413 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use tokio::sync::Mutex;
+
+use crate::auth::anilist::PROVIDER_ID as ANILIST_PROVIDER_ID;
+use crate::auth::mal::PROVIDER_ID as MAL_PROVIDER_ID;
+
+use super::matcher::{normalize_title, StoredAnimeListItem};
+use super::types::{ListEntryMatch, ListMatchSource};
+
+const ALIASES_FILE_NAME: &str = "title-aliases.json";
+const ALIASES_FILE_VERSION: u32 = 1;
+
+// Maps a parsed title, as players and fansubs name it, to a provider media.
+// `title` is stored normalized; an alias without a season applies to every
+// season parsed from that title.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TitleAlias {
+    pub provider_id: String,
+    pub title: String,
+    #[serde(default)]
+    pub season: Option<u32>,
+    #[serde(default)]
+    pub episode_offset: i32,
+    pub media_id: u64,
+    #[serde(default)]
+    pub media_title: Option<String>,
+}
+
+impl TitleAlias {
+    fn normalized(mut self) -> Result<Self, String> {
+        if self.provider_id != ANILIST_PROVIDER_ID && self.provider_id != MAL_PROVIDER_ID {
+            return Err(format!("Unsupported provider: {}", self.provider_id));
+        }
+
+        self.title = normalize_title(&self.title);
+        if self.title.is_empty() {
+            return Err("Alias title cannot be empty".to_string());
+        }
+        if self.media_id == 0 {
+            return Err("Alias media id cannot be 0".to_string());
+        }
+
+        self.media_title = self
+            .media_title
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty());
+        Ok(self)
+    }
+
+    fn has_same_key(&self, other: &TitleAlias) -> bool {
+        self.provider_id == other.provider_id
+            && self.title == other.title
+            && self.season == other.season
+    }
+
+    fn map_episode(&self, episode: Option<u32>) -> Option<u32> {
+        let episode = i64::from(episode?) + i64::from(self.episode_offset);
+        u32::try_from(episode).ok().filter(|episode| *episode > 0)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct TitleAliasFile {
+    version: u32,
+    aliases: Vec<TitleAlias>,
+}
+
+fn read_aliases_file(path: &Path) -> Result<Vec<TitleAlias>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => parse_aliases(&contents),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(error) => Err(format!("Failed to read title aliases: {error}")),
+    }
+}
+
+fn parse_aliases(contents: &str) -> Result<Vec<TitleAlias>, String> {
+    let file: TitleAliasFile = serde_json::from_str(contents)
+        .map_err(|error| format!("Failed to parse title aliases: {error}"))?;
+    if file.version != ALIASES_FILE_VERSION {
+        return Err(format!(
+            "Unsupported title aliases version: {}",
+            file.version
+        ));
+    }
+
+    file.aliases
+        .into_iter()
+        .map(TitleAlias::normalized)
+        .collect()
+}
+
+fn serialize_aliases(aliases: &[TitleAlias]) -> Result<String, String> {
+    serde_json::to_string_pretty(&TitleAliasFile {
+        version: ALIASES_FILE_VERSION,
+        aliases: aliases.to_vec(),
+    })
+    .map_err(|error| format!("Failed to serialize title aliases: {error}"))
+}
+
+fn write_aliases_file(path: &Path, aliases: &[TitleAlias]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed to save title aliases: {error}"))?;
+    }
+
+    // Write next to the store and rename so a crash never leaves half a file.
+    let temporary_path = path.with_extension("json.tmp");
+    std::fs::write(&temporary_path, serialize_aliases(aliases)?)
+        .and_then(|_| std::fs::rename(&temporary_path, path))
+        .map_err(|error| format!("Failed to save title aliases: {error}"))
+}
+
+fn upsert_alias(aliases: &mut Vec<TitleAlias>, alias: TitleAlias) {
+    match aliases
+        .iter_mut()
+        .find(|existing| existing.has_same_key(&alias))
+    {
+        Some(existing) => *existing = alias,
+        None => aliases.push(alias),
+    }
+}
+
+// An alias for the parsed season wins over one that applies to every season.
+fn find_alias<'a>(
+    aliases: &'a [TitleAlias],
+    provider_id: &str,
+    title: &str,
+    season: Option<u32>,
+) -> Option<&'a TitleAlias> {
+    let title = normalize_title(title);
+    let mut candidates = aliases
+        .iter()
+        .filter(|alias| alias.provider_id == provider_id && alias.title == title);
+
+    candidates
+        .clone()
+        .find(|alias| season.is_some() && alias.season == season)
+        .or_else(|| candidates.find(|alias| alias.season.is_none()))
+}
+
+pub(crate) fn alias_list_match(
+    alias: &TitleAlias,
+    items: &[StoredAnimeListItem],
+    episode: Option<u32>,
+) -> ListEntryMatch {
+    let item = items.iter().find(|item| item.id == alias.media_id);
+
+    ListEntryMatch {
+        provider_id: alias.provider_id.clone(),
+        media_id: alias.media_id,
+        entry_id: item.and_then(|item| item.entry_id),
+        title: item
+            .map(|item| item.title.clone())
+            .or_else(|| alias.media_title.clone())
+            .unwrap_or_default(),
+        total_episodes: item.map(|item| item.total_episodes).unwrap_or_default(),
+        episode: alias.map_episode(episode),
+        confidence: 1.0,
+        source: ListMatchSource::Alias,
+    }
+}
+
+pub struct TitleAliasState {
+    path: Option<PathBuf>,
+    aliases: Mutex<Vec<TitleAlias>>,
+}
+
+impl TitleAliasState {
+    fn from_path(path: Option<PathBuf>) -> Self {
+        let aliases = match path.as_deref().map(read_aliases_file) {
+            Some(Ok(aliases)) => aliases,
+            Some(Err(error)) => {
+                eprintln!("{error}");
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+
+        Self {
+            path,
+            aliases: Mutex::new(aliases),
+        }
+    }
+
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = match app.path().app_local_data_dir() {
+            Ok(dir) => Some(dir.join(ALIASES_FILE_NAME)),
+            Err(error) => {
+                eprintln!("failed to resolve title aliases location: {error}");
+                None
+            }
+        };
+
+        Self::from_path(path)
+    }
+
+    fn persist(&self, aliases: &[TitleAlias]) -> Result<(), String> {
+        match self.path.as_deref() {
+            Some(path) => write_aliases_file(path, aliases),
+            None => Ok(()),
+        }
+    }
+
+    pub async fn list(&self) -> Vec<TitleAlias> {
+        self.aliases.lock().await.clone()
+    }
+
+    pub async fn add(&self, alias: TitleAlias) -> Result<Vec<TitleAlias>, String> {
+        let alias = alias.normalized()?;
+        let mut guard = self.aliases.lock().await;
+        let mut aliases = guard.clone();
+        upsert_alias(&mut aliases, alias);
+
+        self.persist(&aliases)?;
+        *guard = aliases;
+        Ok(guard.clone())
+    }
+
+    pub async fn delete(
+        &self,
+        provider_id: &str,
+        title: &str,
+        season: Option<u32>,
+    ) -> Result<Vec<TitleAlias>, String> {
+        let title = normalize_title(title);
+        let mut guard = self.aliases.lock().await;
+        let aliases: Vec<TitleAlias> = guard
+            .iter()
+            .filter(|alias| {
+                !(alias.provider_id == provider_id
+                    && alias.title == title
+                    && alias.season == season)
+            })
+            .cloned()
+            .collect();
+        if aliases.len() == guard.len() {
+            return Err(format!("No title alias for {title}"));
+        }
+
+        self.persist(&aliases)?;
+        *guard = aliases;
+        Ok(guard.clone())
+    }
+
+    // Imported aliases replace existing ones with the same title and season.
+    pub async fn import(&self, contents: &str) -> Result<Vec<TitleAlias>, String> {
+        let imported = parse_aliases(contents)?;
+        let mut guard = self.aliases.lock().await;
+        let mut aliases = guard.clone();
+        for alias in imported {
+            upsert_alias(&mut aliases, alias);
+        }
+
+        self.persist(&aliases)?;
+        *guard = aliases;
+        Ok(guard.clone())
+    }
+
+    pub async fn export(&self) -> Result<String, String> {
+        serialize_aliases(&self.aliases.lock().await)
+    }
+
+    pub(crate) async fn find(
+        &self,
+        provider_id: &str,
+        title: &str,
+        season: Option<u32>,
+    ) -> Option<TitleAlias> {
+        find_alias(&self.aliases.lock().await, provider_id, title, season).cloned()
+    }
+}
+
+#[tauri::command]
+pub async fn list_title_aliases(app: tauri::AppHandle) -> Result<Vec<TitleAlias>, String> {
+    Ok(app.state::<TitleAliasState>().list().await)
+}
+
+#[tauri::command]
+pub async fn add_title_alias(
+    alias: TitleAlias,
+    app: tauri::AppHandle,
+) -> Result<Vec<TitleAlias>, String> {
+    app.state::<TitleAliasState>().add(alias).await
+}
+
+#[tauri::command]
+pub async fn delete_title_alias(
+    provider_id: String,
+    title: String,
+    season: Option<u32>,
+    app: tauri::AppHandle,
+) -> Result<Vec<TitleAlias>, String> {
+    app.state::<TitleAliasState>()
+        .delete(&provider_id, &title, season)
+        .await
+}
+
+#[tauri::command]
+pub async fn import_title_aliases(
+    contents: String,
+    app: tauri::AppHandle,
+) -> Result<Vec<TitleAlias>, String> {
+    app.state::<TitleAliasState>().import(&contents).await
+}
+
+#[tauri::command]
+pub async fn export_title_aliases(app: tauri::AppHandle) -> Result<String, String> {
+    app.state::<TitleAliasState>().export().await
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::runtime::Runtime;
+
+    use super::*;
+
+    fn alias(title: &str, season: Option<u32>, episode_offset: i32, media_id: u64) -> TitleAlias {
+        TitleAlias {
+            provider_id: ANILIST_PROVIDER_ID.to_string(),
+            title: title.to_string(),
+            season,
+            episode_offset,
+            media_id,
+            media_title: None,
+        }
+    }
+
+    fn temporary_store_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("kioku-title-aliases-{}-{name}", std::process::id()))
+            .join(ALIASES_FILE_NAME)
+    }
+
+    #[test]
+    fn find_alias_matches_normalized_titles_and_prefers_the_parsed_season() {
+        let aliases = [
+            alias("Kusuriya no Hitorigoto", None, 0, 161645),
+            alias("Kusuriya no Hitorigoto", Some(2), -24, 176301),
+        ]
+        .map(|alias| alias.normalized().expect("valid alias"));
+
+        assert_eq!(aliases[0].title, "kusuriya no hitorigoto");
+        assert_eq!(
+            find_alias(&aliases, "anilist", "Kusuriya_no_Hitorigoto", None)
+                .map(|alias| alias.media_id),
+            Some(161645)
+        );
+        assert_eq!(
+            find_alias(&aliases, "anilist", "Kusuriya no Hitorigoto", Some(2))
+                .map(|alias| alias.media_id),
+            Some(176301)
+        );
+        assert_eq!(
+            find_alias(&aliases, "anilist", "Kusuriya no Hitorigoto", Some(3))
+                .map(|alias| alias.media_id),
+            Some(161645)
+        );
+        assert!(find_alias(&aliases, "myanimelist", "Kusuriya no Hitorigoto", None).is_none());
+
+        let list_match = alias_list_match(&aliases[1], &[], Some(26));
+        assert_eq!(list_match.episode, Some(2));
+        assert_eq!(list_match.confidence, 1.0);
+        assert_eq!(list_match.source, ListMatchSource::Alias);
+        assert_eq!(alias_list_match(&aliases[1], &[], Some(24)).episode, None);
+    }
+
+    #[test]
+    fn aliases_persist_and_round_trip_through_export_and_import() {
+        let runtime = Runtime::new().expect("runtime should build");
+        let path = temporary_store_path("round-trip");
+        let _ = std::fs::remove_file(&path);
+
+        runtime.block_on(async {
+            let state = TitleAliasState::from_path(Some(path.clone()));
+            state
+                .add(alias("Frieren", None, 0, 154587))
+                .await
+                .expect("alias should be added");
+            let aliases = state
+                .add(alias("  FRIEREN ", None, 0, 52991))
+                .await
+                .expect("alias should be replaced");
+            assert_eq!(aliases.len(), 1);
+            assert_eq!(aliases[0].media_id, 52991);
+            assert!(state.add(alias("!!", None, 0, 1)).await.is_err());
+
+            let exported = state.export().await.expect("aliases should export");
+
+            let reloaded = TitleAliasState::from_path(Some(path.clone()));
+            assert_eq!(reloaded.list().await, aliases);
+            reloaded
+                .delete("anilist", "Frieren", None)
+                .await
+                .expect("alias should be deleted");
+            assert!(reloaded.list().await.is_empty());
+            assert!(reloaded.delete("anilist", "Frieren", None).await.is_err());
+
+            assert_eq!(
+                reloaded
+                    .import(&exported)
+                    .await
+                    .expect("aliases should import"),
+                aliases
+            );
+        });
+
+        let _ = std::fs::remove_dir_all(path.parent().expect("store directory"));
+    }
+}
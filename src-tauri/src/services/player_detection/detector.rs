@@ -1,28 +1,63 @@
+use std::borrow::Cow;
 use std::collections::HashSet;
 
+use regex::Regex;
+
+use super::mpc_web;
+#[cfg(target_os = "linux")]
+use super::mpris::{list_mpris_players, MprisDetectionSource};
+use super::mpv_ipc::{find_ipc_server_arg, query_playback_status};
+#[cfg(target_os = "linux")]
+use super::parser::parse_anime_from_title;
 use super::parser::{
     is_url_source, looks_like_windows_path, normalize_source_arg, parse_anime_from_source,
+    ParsedRelease,
 };
-use super::processes::{list_processes, ProcessSnapshot};
-use super::types::{AnimePlaybackDetection, DetectPlayingAnimeRequest, SupportedPlayer};
-use super::util::{dedup_players, split_command_line};
-
-pub(crate) struct DetectionCandidate {
-    pub process_id: u32,
-    pub detection: AnimePlaybackDetection,
-}
+use super::playlist::{find_playlist_arg, read_playlist, resolve_playlist_release};
+use super::processes::ProcessSnapshot;
+use super::sources::{PlaybackSource, ProcessSource, SystemPlaybackSource, SystemProcessSource};
+use super::types::{
+    AnimePlaybackDetection, CustomPlayerDefinition, DetectPlayingAnimeRequest, DetectionCandidate,
+    DetectionSourcesConfig, MediaArgRegex, MediaSourceKind, MpvIpcConfig, PlayerOptionStyle,
+    PlayerPlaybackStatus, SupportedPlayer,
+};
+use super::util::{dedup_players, normalize_custom_players, split_command_line};
+use super::vlc_http;
 
+#[derive(Default)]
 pub(crate) struct DetectionCycleResult {
     pub detections: Vec<DetectionCandidate>,
     pub matched_player_pids: HashSet<u32>,
+    pub focused_process_id: Option<u32>,
 }
 
 #[tauri::command]
 pub async fn detect_playing_anime(
     request: Option<DetectPlayingAnimeRequest>,
 ) -> Result<Option<AnimePlaybackDetection>, String> {
+    let sources = request
+        .as_ref()
+        .and_then(|payload| payload.sources.clone())
+        .unwrap_or_default();
+    let custom_players = normalize_custom_players(
+        request
+            .as_ref()
+            .and_then(|payload| payload.custom_players.clone())
+            .unwrap_or_default(),
+    )?;
     let selected_players = resolve_selected_players(request);
-    let cycle_result = collect_detection_cycle_result(&selected_players)?;
+    // Talking to the players blocks on sockets, D-Bus and `/proc`.
+    let cycle_result = tauri::async_runtime::spawn_blocking(move || {
+        collect_detection_cycle_result(
+            &SystemProcessSource,
+            &SystemPlaybackSource,
+            &selected_players,
+            &custom_players,
+            &sources,
+        )
+    })
+    .await
+    .map_err(|err| format!("Detection cycle failed: {err}"))??;
 
     let mut best: Option<(u8, u32, AnimePlaybackDetection)> = None;
     for candidate in cycle_result.detections {
@@ -42,46 +77,263 @@ pub async fn detect_playing_anime(
 }
 
 pub(crate) fn collect_detection_cycle_result(
+    process_source: &dyn ProcessSource,
+    playback_source: &dyn PlaybackSource,
     selected_players: &[SupportedPlayer],
+    custom_players: &[CustomPlayerDefinition],
+    sources: &DetectionSourcesConfig,
 ) -> Result<DetectionCycleResult, String> {
-    let processes = list_processes()?;
+    let processes = process_source.list_processes()?;
+    let sources = &resolve_cycle_sources(sources, &processes, selected_players, custom_players);
     let mut detections = Vec::new();
     let mut matched_player_pids = HashSet::new();
 
     for process in processes {
+        // Custom definitions are checked first so a wrapper sharing a built-in
+        // alias can override how its arguments are read.
+        if let Some(definition) = match_process_to_custom_player(&process, custom_players) {
+            matched_player_pids.insert(process.pid);
+
+            let Some((source, parsed)) = extract_custom_media_source(definition, &process)
+                .map(|path| (MediaSourceKind::CommandLine, path))
+                .or_else(|| {
+                    process_source
+                        .find_open_media_file(process.pid)
+                        .map(|path| (MediaSourceKind::OpenFile, path))
+                })
+                .and_then(|(source, path)| Some((source, parse_anime_from_source(&path)?)))
+                .or_else(|| {
+                    parse_playlist_source(&process, None)
+                        .map(|parsed| (MediaSourceKind::Playlist, parsed))
+                })
+            else {
+                continue;
+            };
+
+            detections.push(DetectionCandidate {
+                process_id: process.pid,
+                source,
+                detection: AnimePlaybackDetection::for_custom_player(
+                    definition.id.clone(),
+                    parsed.anime_title,
+                    parsed.episode,
+                )
+                .with_kind(parsed.kind)
+                .with_season(parsed.season),
+            });
+            continue;
+        }
+
         let Some(player) = match_process_to_player(&process, selected_players) else {
             continue;
         };
 
         matched_player_pids.insert(process.pid);
 
-        let Some(source) = extract_media_source(player, &process.args, &process.command_line)
+        let playback_status = playback_source.query_playback_status(player, &process, sources);
+        if playback_status
+            .as_ref()
+            .is_some_and(|status| status.stopped)
+        {
+            continue;
+        }
+        let Some((source, parsed)) = playback_status
+            .as_ref()
+            .and_then(|status| status.media_path.clone())
+            .map(|path| (MediaSourceKind::PlayerInterface, path))
+            .or_else(|| {
+                extract_media_source(player, &process.args, &process.command_line)
+                    .map(|path| (MediaSourceKind::CommandLine, path))
+            })
+            .or_else(|| {
+                process_source
+                    .find_open_media_file(process.pid)
+                    .map(|path| (MediaSourceKind::OpenFile, path))
+            })
+            .and_then(|(source, path)| Some((source, parse_anime_from_source(&path)?)))
+            .or_else(|| {
+                parse_playlist_source(&process, playback_status.as_ref())
+                    .map(|parsed| (MediaSourceKind::Playlist, parsed))
+            })
         else {
             continue;
         };
 
-        let Some(parsed) = parse_anime_from_source(&source) else {
-            continue;
-        };
+        let mut detection = AnimePlaybackDetection::new(player, parsed.anime_title, parsed.episode)
+            .with_kind(parsed.kind)
+            .with_season(parsed.season);
+        if let Some(status) = playback_status {
+            detection = detection.with_playback_status(status);
+        }
 
         detections.push(DetectionCandidate {
             process_id: process.pid,
-            detection: AnimePlaybackDetection {
-                player,
-                anime_title: parsed.anime_title,
-                episode: parsed.episode,
-            },
+            source,
+            detection,
         });
     }
 
+    #[cfg(target_os = "linux")]
+    if selected_players.contains(&SupportedPlayer::Mpris) {
+        collect_mpris_candidates(&mut detections, &mut matched_player_pids);
+    }
+
     detections.sort_by_key(|candidate| candidate.process_id);
 
     Ok(DetectionCycleResult {
         detections,
         matched_player_pids,
+        focused_process_id: None,
     })
 }
 
+// Players already matched through their process keep their richer detection;
+// MPRIS only contributes players we could not identify from /proc. A missing
+// session bus is not an error since headless sessions have none.
+#[cfg(target_os = "linux")]
+fn collect_mpris_candidates(
+    detections: &mut Vec<DetectionCandidate>,
+    matched_player_pids: &mut HashSet<u32>,
+) {
+    let Ok(players) = list_mpris_players() else {
+        return;
+    };
+
+    for player in players {
+        let Some(process_id) = player.process_id else {
+            continue;
+        };
+        if matched_player_pids.contains(&process_id) {
+            continue;
+        }
+
+        let parsed = match player.detection_source() {
+            Some(MprisDetectionSource::Url(url)) => parse_anime_from_source(&url),
+            Some(MprisDetectionSource::Title(title)) => parse_anime_from_title(&title),
+            None => None,
+        };
+        let Some(parsed) = parsed else {
+            continue;
+        };
+
+        matched_player_pids.insert(process_id);
+        detections.push(DetectionCandidate {
+            process_id,
+            source: MediaSourceKind::Mpris,
+            detection: AnimePlaybackDetection::new(
+                SupportedPlayer::Mpris,
+                parsed.anime_title,
+                parsed.episode,
+            )
+            .with_kind(parsed.kind)
+            .with_season(parsed.season)
+            .with_playback_status(player.status),
+        });
+    }
+}
+
+pub(crate) fn query_player_playback_status(
+    player: SupportedPlayer,
+    process: &ProcessSnapshot,
+    sources: &DetectionSourcesConfig,
+) -> Option<PlayerPlaybackStatus> {
+    match player {
+        SupportedPlayer::Mpv => {
+            let endpoint = resolve_mpv_ipc_endpoint(&sources.mpv_ipc, process)?;
+            query_playback_status(&endpoint).ok()
+        }
+        SupportedPlayer::Vlc => {
+            let endpoint = vlc_http::resolve_endpoint(&sources.vlc_http, &process_args(process))?;
+            vlc_http::query_playback_status(&endpoint).ok()
+        }
+        SupportedPlayer::MpcHc | SupportedPlayer::MpcBe => {
+            let port = mpc_web::resolve_port(&sources.mpc_web, player)?;
+            mpc_web::query_playback_status(port).ok()
+        }
+        SupportedPlayer::Mpris | SupportedPlayer::Custom => None,
+    }
+}
+
+fn parse_playlist_source(
+    process: &ProcessSnapshot,
+    status: Option<&PlayerPlaybackStatus>,
+) -> Option<ParsedRelease> {
+    let playlist = find_playlist_arg(&process_args(process))?;
+    let entries = read_playlist(&playlist.path).ok()?;
+    resolve_playlist_release(&entries, status, playlist.follows_position)
+}
+
+fn process_args(process: &ProcessSnapshot) -> Vec<String> {
+    if process.args.is_empty() {
+        split_command_line(&process.command_line)
+    } else {
+        process.args.clone()
+    }
+}
+
+fn resolve_mpv_ipc_endpoint(config: &MpvIpcConfig, process: &ProcessSnapshot) -> Option<String> {
+    if !config.enabled {
+        return None;
+    }
+
+    find_ipc_server_arg(&process_args(process)).or_else(|| {
+        config
+            .socket_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(str::to_string)
+    })
+}
+
+// The configured mpv socket and each MPC web port can only belong to one
+// instance. When several players would answer on the same one there is no
+// telling which owns it, so none of them use it and detection falls back to
+// their command lines and open files.
+fn resolve_cycle_sources<'a>(
+    sources: &'a DetectionSourcesConfig,
+    processes: &[ProcessSnapshot],
+    selected_players: &[SupportedPlayer],
+    custom_players: &[CustomPlayerDefinition],
+) -> Cow<'a, DetectionSourcesConfig> {
+    let players: Vec<(SupportedPlayer, &ProcessSnapshot)> = processes
+        .iter()
+        .filter(|process| match_process_to_custom_player(process, custom_players).is_none())
+        .filter_map(|process| Some((match_process_to_player(process, selected_players)?, process)))
+        .collect();
+
+    let unrouted_mpv_count = players
+        .iter()
+        .filter(|(player, process)| {
+            *player == SupportedPlayer::Mpv && find_ipc_server_arg(&process_args(process)).is_none()
+        })
+        .count();
+    let mut mpc_ports = HashSet::new();
+    let mpc_port_shared = players
+        .iter()
+        .filter_map(|(player, _)| match player {
+            SupportedPlayer::MpcHc | SupportedPlayer::MpcBe => {
+                mpc_web::resolve_port(&sources.mpc_web, *player)
+            }
+            _ => None,
+        })
+        .any(|port| !mpc_ports.insert(port));
+
+    let drop_mpv_socket = unrouted_mpv_count > 1 && sources.mpv_ipc.socket_path.is_some();
+    if !drop_mpv_socket && !mpc_port_shared {
+        return Cow::Borrowed(sources);
+    }
+
+    let mut sources = sources.clone();
+    if drop_mpv_socket {
+        sources.mpv_ipc.socket_path = None;
+    }
+    if mpc_port_shared {
+        sources.mpc_web.enabled = false;
+    }
+    Cow::Owned(sources)
+}
+
 fn resolve_selected_players(request: Option<DetectPlayingAnimeRequest>) -> Vec<SupportedPlayer> {
     let players = request
         .and_then(|payload| payload.players)
@@ -116,6 +368,22 @@ fn match_process_to_player(
     process: &ProcessSnapshot,
     selected_players: &[SupportedPlayer],
 ) -> Option<SupportedPlayer> {
+    selected_players
+        .iter()
+        .copied()
+        .find(|player| process_matches(process, |value| player.matches_process_name(value)))
+}
+
+fn match_process_to_custom_player<'a>(
+    process: &ProcessSnapshot,
+    custom_players: &'a [CustomPlayerDefinition],
+) -> Option<&'a CustomPlayerDefinition> {
+    custom_players
+        .iter()
+        .find(|definition| process_matches(process, |value| definition.matches_process_name(value)))
+}
+
+fn process_matches(process: &ProcessSnapshot, matches_name: impl Fn(&str) -> bool) -> bool {
     let executable = process.args.first().cloned().unwrap_or_else(|| {
         split_command_line(&process.command_line)
             .into_iter()
@@ -123,19 +391,9 @@ fn match_process_to_player(
             .unwrap_or_default()
     });
 
-    for player in selected_players {
-        if player.matches_process_name(&process.name)
-            || player.matches_process_name(&executable)
-            || process
-                .args
-                .iter()
-                .any(|arg| player.matches_process_name(arg))
-        {
-            return Some(*player);
-        }
-    }
-
-    None
+    matches_name(&process.name)
+        || matches_name(&executable)
+        || process.args.iter().any(|arg| matches_name(arg))
 }
 
 fn extract_media_source(
@@ -143,17 +401,70 @@ fn extract_media_source(
     args: &[String],
     command_line: &str,
 ) -> Option<String> {
-    let mut args = if args.is_empty() {
+    let args = if args.is_empty() {
         split_command_line(command_line)
     } else {
         args.to_vec()
     };
 
+    find_media_source_arg(args, player.option_style(), |value| {
+        player.matches_process_name(value)
+    })
+}
+
+// An explicit pattern or index is trusted as-is, since custom players are often
+// launched with sources that carry no recognizable video extension.
+fn extract_custom_media_source(
+    definition: &CustomPlayerDefinition,
+    process: &ProcessSnapshot,
+) -> Option<String> {
+    let args = process_args(process);
+
+    if let Some(MediaArgRegex(regex)) = definition.media_arg_regex.as_ref() {
+        return args
+            .iter()
+            .rev()
+            .find_map(|arg| capture_media_source(regex, arg));
+    }
+
+    if let Some(index) = definition.media_arg_index {
+        return args.get(index).and_then(|arg| trim_source_arg(arg));
+    }
+
+    find_media_source_arg(args, definition.option_style, |value| {
+        definition.matches_process_name(value)
+    })
+}
+
+fn capture_media_source(regex: &Regex, value: &str) -> Option<String> {
+    let captures = regex.captures(value)?;
+    let matched = captures
+        .name("source")
+        .or_else(|| captures.get(1))
+        .or_else(|| captures.get(0))?;
+
+    trim_source_arg(matched.as_str())
+}
+
+fn trim_source_arg(value: &str) -> Option<String> {
+    let trimmed = value.trim().trim_matches('"').trim_matches('\'').trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+fn find_media_source_arg(
+    mut args: Vec<String>,
+    option_style: PlayerOptionStyle,
+    is_executable: impl Fn(&str) -> bool,
+) -> Option<String> {
     if args.is_empty() {
         return None;
     }
 
-    if player.matches_process_name(&args[0]) {
+    if is_executable(&args[0]) {
         args.remove(0);
     }
 
@@ -165,7 +476,7 @@ fn extract_media_source(
             continue;
         }
 
-        if is_player_option(player, value) {
+        if is_option_arg(option_style, value) {
             continue;
         }
 
@@ -177,16 +488,19 @@ fn extract_media_source(
     candidate
 }
 
-fn is_player_option(player: SupportedPlayer, value: &str) -> bool {
-    let normalized = value.to_ascii_lowercase();
-    if normalized == "--" || normalized.starts_with('-') {
+fn is_option_arg(option_style: PlayerOptionStyle, value: &str) -> bool {
+    if value == "--" {
         return true;
     }
 
-    match player {
-        SupportedPlayer::Mpv => false,
-        SupportedPlayer::MpcHc | SupportedPlayer::MpcBe => {
-            normalized.starts_with('/') && !looks_like_windows_path(value) && !is_url_source(value)
+    match option_style {
+        PlayerOptionStyle::Dash => value.starts_with('-'),
+        PlayerOptionStyle::DoubleDash => value.starts_with("--"),
+        PlayerOptionStyle::Slash => {
+            value.starts_with('-')
+                || (value.starts_with('/')
+                    && !looks_like_windows_path(value)
+                    && !is_url_source(value))
         }
     }
 }
@@ -194,6 +508,11 @@ fn is_player_option(player: SupportedPlayer, value: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::player_detection::sources::fakes::{
+        process, ScriptedPlaybackSource, ScriptedProcessSource,
+    };
+    use crate::services::player_detection::types::MpcWebConfig;
+    use crate::services::player_detection::util::normalize_custom_player;
 
     #[test]
     fn resolve_selected_players_deduplicates_and_falls_back_when_empty() {
@@ -203,11 +522,13 @@ mod tests {
                 SupportedPlayer::Mpv,
                 SupportedPlayer::MpcBe,
             ]),
+            ..Default::default()
         }));
         assert_eq!(selected, vec![SupportedPlayer::Mpv, SupportedPlayer::MpcBe]);
 
         let fallback = resolve_selected_players(Some(DetectPlayingAnimeRequest {
             players: Some(Vec::new()),
+            ..Default::default()
         }));
         assert_eq!(fallback, SupportedPlayer::all());
     }
@@ -244,16 +565,289 @@ mod tests {
         );
     }
 
+    #[test]
+    fn resolve_mpv_ipc_endpoint_prefers_process_args_over_configured_path() {
+        let process = ProcessSnapshot {
+            pid: 42,
+            name: "mpv".to_string(),
+            command_line: String::new(),
+            args: vec![
+                "mpv".to_string(),
+                "--input-ipc-server=/tmp/from-args".to_string(),
+                "/anime/Frieren".to_string(),
+            ],
+        };
+        let configured = MpvIpcConfig {
+            enabled: true,
+            socket_path: Some("/tmp/configured".to_string()),
+        };
+
+        assert_eq!(
+            resolve_mpv_ipc_endpoint(&configured, &process),
+            Some("/tmp/from-args".to_string())
+        );
+
+        let without_arg = ProcessSnapshot {
+            args: vec!["mpv".to_string(), "/anime/Frieren".to_string()],
+            ..process.clone()
+        };
+        assert_eq!(
+            resolve_mpv_ipc_endpoint(&configured, &without_arg),
+            Some("/tmp/configured".to_string())
+        );
+        assert_eq!(
+            resolve_mpv_ipc_endpoint(&MpvIpcConfig::default(), &process),
+            None
+        );
+    }
+
+    #[test]
+    fn resolve_cycle_sources_drops_configured_socket_for_several_unrouted_mpv_instances() {
+        let sources = DetectionSourcesConfig {
+            mpv_ipc: MpvIpcConfig {
+                enabled: true,
+                socket_path: Some("/tmp/configured".to_string()),
+            },
+            ..Default::default()
+        };
+        let mpv = |pid: u32, args: &[&str]| ProcessSnapshot {
+            pid,
+            name: "mpv".to_string(),
+            command_line: String::new(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        };
+        let single = vec![
+            mpv(1, &["mpv", "/anime/Frieren - 01.mkv"]),
+            mpv(
+                2,
+                &[
+                    "mpv",
+                    "--input-ipc-server=/tmp/own",
+                    "/anime/Frieren - 02.mkv",
+                ],
+            ),
+        ];
+        let players = SupportedPlayer::all();
+
+        let resolved = resolve_cycle_sources(&sources, &single, &players, &[]);
+        assert_eq!(
+            resolved.mpv_ipc.socket_path.as_deref(),
+            Some("/tmp/configured")
+        );
+
+        let several = vec![
+            mpv(1, &["mpv", "/anime/Frieren - 01.mkv"]),
+            mpv(3, &["mpv", "/anime/Frieren - 03.mkv"]),
+        ];
+        let resolved = resolve_cycle_sources(&sources, &several, &players, &[]);
+        assert_eq!(resolved.mpv_ipc.socket_path, None);
+        assert!(resolved.mpv_ipc.enabled);
+    }
+
+    #[test]
+    fn resolve_cycle_sources_disables_mpc_web_when_players_share_a_port() {
+        let mpc = |pid: u32, name: &str| ProcessSnapshot {
+            pid,
+            name: name.to_string(),
+            command_line: String::new(),
+            args: vec![name.to_string()],
+        };
+        let processes = vec![mpc(1, "mpc-hc64.exe"), mpc(2, "mpc-be64.exe")];
+        let players = SupportedPlayer::all();
+        let mut sources = DetectionSourcesConfig {
+            mpc_web: MpcWebConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let resolved = resolve_cycle_sources(&sources, &processes, &players, &[]);
+        assert!(!resolved.mpc_web.enabled);
+
+        sources.mpc_web.mpc_be_port = Some(13580);
+        let resolved = resolve_cycle_sources(&sources, &processes, &players, &[]);
+        assert!(matches!(resolved, Cow::Borrowed(_)));
+        assert!(resolved.mpc_web.enabled);
+    }
+
+    #[test]
+    fn extract_media_source_handles_vlc_command_lines_and_file_mrls() {
+        let args = vec![
+            "/usr/bin/vlc".to_string(),
+            "--started-from-file".to_string(),
+            "--extraintf=http".to_string(),
+            "file:///home/user/Anime/Frieren%20-%2007.mkv".to_string(),
+        ];
+
+        assert_eq!(
+            extract_media_source(SupportedPlayer::Vlc, &args, ""),
+            Some("file:///home/user/Anime/Frieren%20-%2007.mkv".to_string())
+        );
+
+        let process = ProcessSnapshot {
+            pid: 7,
+            name: "vlc".to_string(),
+            command_line: "cvlc /anime/Frieren - 07.mkv".to_string(),
+            args: vec!["cvlc".to_string(), "/anime/Frieren - 07.mkv".to_string()],
+        };
+        assert_eq!(
+            match_process_to_player(&process, &SupportedPlayer::all()),
+            Some(SupportedPlayer::Vlc)
+        );
+    }
+
     #[test]
     fn mpc_switch_detection_does_not_confuse_paths_or_urls_with_options() {
-        assert!(is_player_option(SupportedPlayer::MpcHc, "/play"));
-        assert!(!is_player_option(
-            SupportedPlayer::MpcHc,
+        assert!(is_option_arg(PlayerOptionStyle::Slash, "/play"));
+        assert!(!is_option_arg(
+            PlayerOptionStyle::Slash,
             r"C:\Anime\Frieren - 01.mkv"
         ));
-        assert!(!is_player_option(
-            SupportedPlayer::MpcHc,
+        assert!(!is_option_arg(
+            PlayerOptionStyle::Slash,
             "https://example.com/Frieren-01.mkv"
         ));
     }
+
+    fn custom_player(id: &str, aliases: &[&str]) -> CustomPlayerDefinition {
+        CustomPlayerDefinition {
+            id: id.to_string(),
+            process_aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn match_process_to_custom_player_uses_aliases_from_definition() {
+        let process = ProcessSnapshot {
+            pid: 11,
+            name: "wine-preloader".to_string(),
+            command_line: String::new(),
+            args: vec![
+                "C:\\Program Files\\DAUM\\PotPlayer\\PotPlayerMini64.exe".to_string(),
+                "Z:\\anime\\Frieren - 03.mkv".to_string(),
+            ],
+        };
+        let definitions = vec![
+            custom_player("iina", &["iina"]),
+            custom_player("potplayer", &["PotPlayerMini64.exe"]),
+        ];
+
+        assert_eq!(
+            match_process_to_custom_player(&process, &definitions).map(|item| item.id.as_str()),
+            Some("potplayer")
+        );
+        assert_eq!(
+            match_process_to_player(&process, &SupportedPlayer::all()),
+            None
+        );
+    }
+
+    #[test]
+    fn extract_custom_media_source_honours_option_style_index_and_pattern() {
+        let process = ProcessSnapshot {
+            pid: 12,
+            name: "PotPlayerMini64.exe".to_string(),
+            command_line: String::new(),
+            args: vec![
+                "PotPlayerMini64.exe".to_string(),
+                "/sub=Z:\\anime\\Frieren - 03.ass".to_string(),
+                "/seek=120".to_string(),
+                "Z:\\anime\\Frieren - 03.mkv".to_string(),
+            ],
+        };
+
+        let slash_style = CustomPlayerDefinition {
+            option_style: PlayerOptionStyle::Slash,
+            ..custom_player("potplayer", &["PotPlayerMini64.exe"])
+        };
+        assert_eq!(
+            extract_custom_media_source(&slash_style, &process),
+            Some("Z:\\anime\\Frieren - 03.mkv".to_string())
+        );
+
+        let wrapper = ProcessSnapshot {
+            pid: 13,
+            name: "anime-mpv".to_string(),
+            command_line: "anime-mpv --profile=anime --play=\"/anime/Dungeon Meshi - 02\" --"
+                .to_string(),
+            args: Vec::new(),
+        };
+        let by_pattern = CustomPlayerDefinition {
+            option_style: PlayerOptionStyle::DoubleDash,
+            media_arg_pattern: Some("^--play=(?P<source>.+)$".to_string()),
+            ..custom_player("anime-mpv", &["anime-mpv"])
+        };
+        let by_pattern = normalize_custom_player(by_pattern).unwrap();
+        assert_eq!(
+            extract_custom_media_source(&by_pattern, &wrapper),
+            Some("/anime/Dungeon Meshi - 02".to_string())
+        );
+
+        let by_index = CustomPlayerDefinition {
+            media_arg_index: Some(2),
+            ..custom_player("anime-mpv", &["anime-mpv"])
+        };
+        assert_eq!(
+            extract_custom_media_source(&by_index, &wrapper),
+            Some("--play=/anime/Dungeon Meshi - 02".to_string())
+        );
+    }
+
+    #[test]
+    fn option_styles_only_skip_their_own_prefixes() {
+        assert!(is_option_arg(PlayerOptionStyle::Dash, "-fs"));
+        assert!(is_option_arg(PlayerOptionStyle::DoubleDash, "--fs"));
+        assert!(!is_option_arg(
+            PlayerOptionStyle::DoubleDash,
+            "-episode-01.mkv"
+        ));
+        assert!(!is_option_arg(
+            PlayerOptionStyle::Dash,
+            "/anime/Frieren - 01.mkv"
+        ));
+        assert!(is_option_arg(PlayerOptionStyle::Slash, "-fs"));
+    }
+
+    #[test]
+    fn stopped_player_does_not_fall_back_to_its_command_line() {
+        let vlc = process(
+            10,
+            &[
+                "vlc",
+                "/anime/[SubsPlease] Sousou no Frieren - 05 (1080p).mkv",
+            ],
+        );
+        let process_source = ScriptedProcessSource::new(vec![Ok(vec![vlc])]);
+        let stopped = ScriptedPlaybackSource::default().with_status(
+            10,
+            PlayerPlaybackStatus {
+                stopped: true,
+                ..Default::default()
+            },
+        );
+
+        let result = collect_detection_cycle_result(
+            &process_source,
+            &stopped,
+            &[SupportedPlayer::Vlc],
+            &[],
+            &DetectionSourcesConfig::default(),
+        )
+        .expect("cycle should succeed");
+        assert!(result.detections.is_empty());
+        assert!(result.matched_player_pids.contains(&10));
+
+        let result = collect_detection_cycle_result(
+            &process_source,
+            &ScriptedPlaybackSource::default(),
+            &[SupportedPlayer::Vlc],
+            &[],
+            &DetectionSourcesConfig::default(),
+        )
+        .expect("cycle should succeed");
+        assert_eq!(result.detections.len(), 1);
+        assert_eq!(result.detections[0].source, MediaSourceKind::CommandLine);
+    }
 }
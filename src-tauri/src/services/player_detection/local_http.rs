@@ -0,0 +1,167 @@
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::time::Duration;
+
+use base64::{engine::general_purpose, Engine as _};
+
+const LOCAL_HTTP_IO_TIMEOUT_MS: u64 = 500;
+const LOCAL_HTTP_MAX_RESPONSE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LocalHttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+pub(crate) fn get_local(
+    port: u16,
+    path: &str,
+    password: Option<&str>,
+) -> Result<LocalHttpResponse, String> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let timeout = Duration::from_millis(LOCAL_HTTP_IO_TIMEOUT_MS);
+
+    let mut stream = TcpStream::connect_timeout(&address, timeout)
+        .map_err(|error| format!("Failed to connect to {address}: {error}"))?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .map_err(|error| format!("Failed to configure read timeout: {error}"))?;
+    stream
+        .set_write_timeout(Some(timeout))
+        .map_err(|error| format!("Failed to configure write timeout: {error}"))?;
+
+    stream
+        .write_all(build_get_request(port, path, password).as_bytes())
+        .map_err(|error| format!("Failed to write HTTP request to {address}: {error}"))?;
+
+    let mut raw = Vec::new();
+    stream
+        .take(LOCAL_HTTP_MAX_RESPONSE_BYTES)
+        .read_to_end(&mut raw)
+        .map_err(|error| format!("Failed to read HTTP response from {address}: {error}"))?;
+
+    parse_response(&raw)
+}
+
+// Players embed tiny HTTP servers, so an HTTP/1.0 request keeps responses
+// unchunked and closes the connection once the body is written.
+fn build_get_request(port: u16, path: &str, password: Option<&str>) -> String {
+    let mut request = format!(
+        "GET {path} HTTP/1.0\r\nHost: 127.0.0.1:{port}\r\nAccept: */*\r\nConnection: close\r\n"
+    );
+
+    if let Some(password) = password.filter(|value| !value.is_empty()) {
+        let credentials = general_purpose::STANDARD.encode(format!(":{password}"));
+        request.push_str(&format!("Authorization: Basic {credentials}\r\n"));
+    }
+
+    request.push_str("\r\n");
+    request
+}
+
+fn parse_response(raw: &[u8]) -> Result<LocalHttpResponse, String> {
+    let header_end = raw
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| "HTTP response is missing a header terminator".to_string())?;
+
+    let head = String::from_utf8_lossy(&raw[..header_end]);
+    let status_line = head.lines().next().unwrap_or_default();
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|value| value.parse::<u16>().ok())
+        .filter(|_| status_line.starts_with("HTTP/"))
+        .ok_or_else(|| format!("Invalid HTTP status line: {status_line}"))?;
+
+    Ok(LocalHttpResponse {
+        status,
+        body: String::from_utf8_lossy(&raw[header_end + 4..]).to_string(),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod stub_server {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    pub(crate) fn spawn(
+        status_line: &'static str,
+        body: String,
+    ) -> (u16, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("stub server should bind");
+        let port = listener
+            .local_addr()
+            .expect("stub server should have an address")
+            .port();
+
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("client should connect");
+            let mut request = Vec::new();
+            let mut buffer = [0_u8; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).expect("request should read");
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+
+            write!(
+                stream,
+                "HTTP/1.0 {status_line}\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            )
+            .expect("response should write");
+
+            String::from_utf8_lossy(&request).to_string()
+        });
+
+        (port, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_get_request_adds_basic_auth_only_when_a_password_is_set() {
+        let anonymous = build_get_request(8080, "/requests/status.json", None);
+        assert!(anonymous.starts_with("GET /requests/status.json HTTP/1.0\r\n"));
+        assert!(!anonymous.contains("Authorization"));
+        assert!(anonymous.ends_with("\r\n\r\n"));
+
+        let authenticated = build_get_request(8080, "/requests/status.json", Some("secret"));
+        assert!(authenticated.contains("Authorization: Basic OnNlY3JldA==\r\n"));
+        assert_eq!(
+            build_get_request(8080, "/", Some("")),
+            build_get_request(8080, "/", None)
+        );
+    }
+
+    #[test]
+    fn parse_response_reads_status_and_body() {
+        let response =
+            parse_response(b"HTTP/1.1 401 Unauthorized\r\nServer: stub\r\n\r\n{\"a\":1}")
+                .expect("response should parse");
+
+        assert_eq!(response.status, 401);
+        assert_eq!(response.body, "{\"a\":1}");
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\n").is_err());
+        assert!(parse_response(b"garbage\r\n\r\n").is_err());
+    }
+
+    #[test]
+    fn get_local_round_trips_against_a_stub_server() {
+        let (port, server) = stub_server::spawn("200 OK", "hello".to_string());
+
+        let response = get_local(port, "/variables.html", Some("pw")).expect("request should work");
+        let request = server.join().expect("server should finish");
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "hello");
+        assert!(request.starts_with("GET /variables.html HTTP/1.0\r\n"));
+        assert!(request.contains(&format!("Host: 127.0.0.1:{port}\r\n")));
+    }
+}
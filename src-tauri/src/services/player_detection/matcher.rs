@@ -0,0 +1,674 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::Deserialize;
+use tauri::Manager;
+use tauri_plugin_zustand::ManagerExt;
+
+use crate::auth::anilist::PROVIDER_ID as ANILIST_PROVIDER_ID;
+use crate::auth::mal::PROVIDER_ID as MAL_PROVIDER_ID;
+use crate::services::anilist::search_anilist_media;
+use crate::services::anime_list_updates::ListType;
+use crate::services::myanimelist::{search_myanimelist_media, MyAnimeListListType};
+
+use super::aliases::{alias_list_match, TitleAliasState};
+use super::relations::{seasonal_list_match, AnimeRelationsState};
+use super::types::{AnimePlaybackDetection, ListEntryMatch, ListMatchSource};
+
+const MIN_MATCH_CONFIDENCE: f64 = 0.5;
+// Fuzzy matches never tie with an exact title match.
+const MAX_FUZZY_SIMILARITY: f64 = 0.95;
+const SEASON_MATCH_BONUS: f64 = 0.05;
+const SEASON_MISMATCH_PENALTY: f64 = 0.3;
+const EPISODE_COUNT_PENALTY: f64 = 0.15;
+const SEARCH_RESULT_LIMIT: u32 = 10;
+// What the provider mappings write when a media has no alternative titles.
+const UNKNOWN_TITLE: &str = "Unknown";
+
+// The subset of a synchronized list entry matching needs, read back from the
+// provider stores the frontend persists.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StoredAnimeListItem {
+    pub id: u64,
+    #[serde(default)]
+    pub entry_id: Option<u64>,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub alternative_titles: String,
+    #[serde(default)]
+    pub total_episodes: u32,
+    #[serde(default)]
+    pub user_status: String,
+    #[serde(default)]
+    pub user_episodes_watched: u32,
+    #[serde(default)]
+    pub user_start_date: Option<String>,
+    #[serde(default)]
+    pub user_finish_date: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct StoredAnimeList {
+    #[serde(default)]
+    watching: Vec<StoredAnimeListItem>,
+    #[serde(default)]
+    completed: Vec<StoredAnimeListItem>,
+    #[serde(default)]
+    on_hold: Vec<StoredAnimeListItem>,
+    #[serde(default)]
+    dropped: Vec<StoredAnimeListItem>,
+    #[serde(default)]
+    plan_to_watch: Vec<StoredAnimeListItem>,
+}
+
+impl StoredAnimeList {
+    fn into_items(self) -> Vec<StoredAnimeListItem> {
+        [
+            self.watching,
+            self.completed,
+            self.on_hold,
+            self.dropped,
+            self.plan_to_watch,
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+pub(crate) struct ActiveAnimeList {
+    pub provider_id: String,
+    pub items: Vec<StoredAnimeListItem>,
+}
+
+fn read_active_provider_id<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<String, String> {
+    let provider_id: Option<String> = app.zustand().get_or_default("provider", "activeProvider");
+    let provider_id =
+        provider_id.ok_or_else(|| "No anime list provider is selected".to_string())?;
+
+    if provider_id != ANILIST_PROVIDER_ID && provider_id != MAL_PROVIDER_ID {
+        return Err(format!("Unsupported provider: {provider_id}"));
+    }
+
+    Ok(provider_id)
+}
+
+pub(crate) fn read_active_anime_list<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> Result<ActiveAnimeList, String> {
+    let provider_id = read_active_provider_id(app)?;
+    let list: Option<StoredAnimeList> = app
+        .zustand()
+        .get_or_default(provider_id.as_str(), "animeListData");
+    let list = list.ok_or_else(|| format!("The {provider_id} anime list is not synchronized"))?;
+
+    Ok(ActiveAnimeList {
+        provider_id,
+        items: list.into_items(),
+    })
+}
+
+pub(crate) fn normalize_title(value: &str) -> String {
+    let folded: String = value
+        .chars()
+        .flat_map(char::to_lowercase)
+        .map(|character| match fold_diacritic(character) {
+            Some(folded) => folded,
+            None if character.is_alphanumeric() => character,
+            None => ' ',
+        })
+        .collect();
+
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn fold_diacritic(character: char) -> Option<char> {
+    let folded = match character {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => 'a',
+        'ç' => 'c',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' => 'e',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => 'i',
+        'ñ' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' => 'u',
+        'ý' | 'ÿ' => 'y',
+        _ => return None,
+    };
+
+    Some(folded)
+}
+
+fn candidate_titles(item: &StoredAnimeListItem) -> impl Iterator<Item = &str> {
+    std::iter::once(item.title.as_str())
+        .chain(alternative_titles_regex().split(&item.alternative_titles))
+        .map(str::trim)
+        .filter(|title| !title.is_empty() && *title != UNKNOWN_TITLE)
+}
+
+fn alternative_titles_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"(?:\s/\s|[,\n;|])+").expect("valid alternative titles regex"))
+}
+
+// A normalized title with its season marker ("season 2", "2nd season", "s2",
+// a trailing "ii" or "2") split off, so "Spy x Family Season 2" compares as
+// "spy x family" in season 2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SeasonedTitle {
+    base: String,
+    season: Option<u32>,
+}
+
+impl SeasonedTitle {
+    fn parse(value: &str) -> Self {
+        let normalized = normalize_title(value);
+        let tokens: Vec<&str> = normalized
+            .split(' ')
+            .filter(|token| !token.is_empty())
+            .collect();
+        let mut kept = Vec::with_capacity(tokens.len());
+        let mut season = None;
+        let mut index = 0;
+
+        while index < tokens.len() {
+            let token = tokens[index];
+            let next = tokens.get(index + 1).copied();
+
+            if token == "season" {
+                if let Some(number) = next.and_then(parse_season_number) {
+                    season = Some(number);
+                    index += 2;
+                    continue;
+                }
+            }
+
+            if next == Some("season") {
+                if let Some(number) = parse_ordinal(token) {
+                    season = Some(number);
+                    index += 2;
+                    continue;
+                }
+            }
+
+            if let Some(number) = token.strip_prefix('s').and_then(parse_season_number) {
+                season = Some(number);
+                index += 1;
+                continue;
+            }
+
+            kept.push(token);
+            index += 1;
+        }
+
+        if season.is_none() && kept.len() > 1 {
+            let trailing = kept.last().copied().and_then(parse_trailing_season);
+            if trailing.is_some() {
+                kept.pop();
+                season = trailing;
+            }
+        }
+
+        Self {
+            base: kept.join(" "),
+            season,
+        }
+    }
+}
+
+fn parse_season_number(value: &str) -> Option<u32> {
+    if value.is_empty() || !value.chars().all(|character| character.is_ascii_digit()) {
+        return None;
+    }
+
+    value
+        .parse()
+        .ok()
+        .filter(|number| (1..=30).contains(number))
+}
+
+fn parse_ordinal(value: &str) -> Option<u32> {
+    ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| value.strip_suffix(suffix))
+        .and_then(parse_season_number)
+}
+
+fn parse_trailing_season(value: &str) -> Option<u32> {
+    let number = match value {
+        "ii" => 2,
+        "iii" => 3,
+        "iv" => 4,
+        "v" => 5,
+        "vi" => 6,
+        _ => value
+            .parse()
+            .ok()
+            .filter(|number| (2..=9).contains(number))?,
+    };
+
+    Some(number)
+}
+
+fn token_dice(left: &str, right: &str) -> f64 {
+    let left: HashSet<&str> = left.split(' ').collect();
+    let right: HashSet<&str> = right.split(' ').collect();
+    let shared = left.intersection(&right).count();
+
+    (2 * shared) as f64 / (left.len() + right.len()) as f64
+}
+
+fn bigram_dice(left: &str, right: &str) -> f64 {
+    let bigrams = |value: &str| -> Vec<(char, char)> {
+        let characters: Vec<char> = value.chars().filter(|c| *c != ' ').collect();
+        characters
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect()
+    };
+    let left = bigrams(left);
+    let mut right = bigrams(right);
+    if left.is_empty() || right.is_empty() {
+        return 0.0;
+    }
+
+    let total = left.len() + right.len();
+    let mut shared = 0;
+    for bigram in left {
+        if let Some(position) = right.iter().position(|candidate| *candidate == bigram) {
+            right.swap_remove(position);
+            shared += 1;
+        }
+    }
+
+    (2 * shared) as f64 / total as f64
+}
+
+fn title_similarity(query: &str, candidate: &str) -> f64 {
+    if query.is_empty() || candidate.is_empty() {
+        return 0.0;
+    }
+
+    if query == candidate {
+        return 1.0;
+    }
+
+    let mut similarity = token_dice(query, candidate).max(bigram_dice(query, candidate));
+    let padded_query = format!(" {query} ");
+    let padded_candidate = format!(" {candidate} ");
+    if padded_candidate.contains(&padded_query) || padded_query.contains(&padded_candidate) {
+        let ratio =
+            query.len().min(candidate.len()) as f64 / query.len().max(candidate.len()) as f64;
+        similarity = similarity.max(0.7 + ratio * 0.2);
+    }
+
+    similarity.min(MAX_FUZZY_SIMILARITY)
+}
+
+pub(crate) struct MatchQuery {
+    title: SeasonedTitle,
+    episode: Option<u32>,
+}
+
+impl MatchQuery {
+    pub(crate) fn from_detection(detection: &AnimePlaybackDetection) -> Option<Self> {
+        let mut title = SeasonedTitle::parse(&detection.anime_title);
+        if title.base.is_empty() {
+            return None;
+        }
+
+        title.season = detection.season.or(title.season);
+        Some(Self {
+            title,
+            episode: detection.episode,
+        })
+    }
+}
+
+fn score_list_entry(query: &MatchQuery, item: &StoredAnimeListItem) -> f64 {
+    let mut best = 0.0_f64;
+
+    for candidate in candidate_titles(item) {
+        let candidate = SeasonedTitle::parse(candidate);
+        let mut score = title_similarity(&query.title.base, &candidate.base);
+
+        if query.title.season.is_some() || candidate.season.is_some() {
+            if query.title.season.unwrap_or(1) == candidate.season.unwrap_or(1) {
+                score += SEASON_MATCH_BONUS;
+            } else {
+                score -= SEASON_MISMATCH_PENALTY;
+            }
+        }
+
+        best = best.max(score);
+    }
+
+    if let Some(episode) = query.episode {
+        if item.total_episodes > 0 && episode > item.total_episodes {
+            best -= EPISODE_COUNT_PENALTY;
+        }
+    }
+
+    best.clamp(0.0, 1.0)
+}
+
+pub(crate) fn find_best_list_match<'a>(
+    query: &MatchQuery,
+    items: &'a [StoredAnimeListItem],
+) -> Option<(&'a StoredAnimeListItem, f64)> {
+    items
+        .iter()
+        .map(|item| (item, score_list_entry(query, item)))
+        .filter(|(_, score)| *score >= MIN_MATCH_CONFIDENCE)
+        .fold(None, |best, candidate| match best {
+            Some((_, best_score)) if best_score >= candidate.1 => best,
+            _ => Some(candidate),
+        })
+}
+
+fn list_entry_match(
+    provider_id: &str,
+    item: &StoredAnimeListItem,
+    episode: Option<u32>,
+    confidence: f64,
+    source: ListMatchSource,
+) -> ListEntryMatch {
+    ListEntryMatch {
+        provider_id: provider_id.to_string(),
+        media_id: item.id,
+        entry_id: item.entry_id,
+        title: item.title.clone(),
+        total_episodes: item.total_episodes,
+        episode,
+        confidence: (confidence * 100.0).round() / 100.0,
+        source,
+    }
+}
+
+// Search results serialize to the same shape as synchronized list entries.
+fn items_from_search_result<T: serde::Serialize>(
+    result: T,
+) -> Result<Vec<StoredAnimeListItem>, String> {
+    serde_json::to_value(result)
+        .and_then(serde_json::from_value)
+        .map_err(|error| format!("Failed to read search results: {error}"))
+}
+
+async fn search_provider(
+    app: &tauri::AppHandle,
+    provider_id: &str,
+    title: &str,
+) -> Result<Vec<StoredAnimeListItem>, String> {
+    if provider_id == ANILIST_PROVIDER_ID {
+        let result = search_anilist_media(
+            app.clone(),
+            title.to_string(),
+            Some(ListType::Anime),
+            Some(SEARCH_RESULT_LIMIT),
+        )
+        .await?;
+        items_from_search_result(result)
+    } else {
+        let result = search_myanimelist_media(
+            app.clone(),
+            title.to_string(),
+            Some(MyAnimeListListType::Anime),
+            Some(SEARCH_RESULT_LIMIT),
+        )
+        .await?;
+        items_from_search_result(result)
+    }
+}
+
+// Releases numbered from the first season are moved onto the sequel the
+// episode belongs to. The episode count no longer contradicts the match
+// then, so the penalty it cost is given back.
+async fn apply_seasonal_episode(
+    app: &tauri::AppHandle,
+    list: &ActiveAnimeList,
+    list_match: ListEntryMatch,
+) -> ListEntryMatch {
+    let Some(episode) = list_match.episode else {
+        return list_match;
+    };
+    if list.provider_id != ANILIST_PROVIDER_ID
+        || list_match.total_episodes == 0
+        || episode <= list_match.total_episodes
+    {
+        return list_match;
+    }
+
+    match app
+        .state::<AnimeRelationsState>()
+        .to_seasonal_episode(app, list_match.media_id, episode)
+        .await
+    {
+        Ok(Some(seasonal)) => {
+            let mut mapped = seasonal_list_match(&list_match, seasonal, &list.items);
+            mapped.confidence = (mapped.confidence + EPISODE_COUNT_PENALTY).min(1.0);
+            mapped
+        }
+        Ok(None) => list_match,
+        Err(error) => {
+            eprintln!("failed to map absolute episode {episode}: {error}");
+            list_match
+        }
+    }
+}
+
+// User aliases always win. Otherwise matches against the synchronized list
+// first and only searches the provider when nothing there is close enough.
+pub(crate) async fn resolve_list_match(
+    app: &tauri::AppHandle,
+    detection: &AnimePlaybackDetection,
+) -> Result<Option<ListEntryMatch>, String> {
+    let Some(query) = MatchQuery::from_detection(detection) else {
+        return Ok(None);
+    };
+
+    // Aliases resolve without the synchronized list, which only fills in the
+    // list entry when it is available.
+    let provider_id = read_active_provider_id(app)?;
+    if let Some(alias) = app
+        .state::<TitleAliasState>()
+        .find(&provider_id, &query.title.base, query.title.season)
+        .await
+    {
+        let items = read_active_anime_list(app)
+            .map(|list| list.items)
+            .unwrap_or_default();
+        return Ok(Some(alias_list_match(&alias, &items, detection.episode)));
+    }
+
+    let list = read_active_anime_list(app)?;
+
+    let list_match = match find_best_list_match(&query, &list.items) {
+        Some((item, confidence)) => Some(list_entry_match(
+            &list.provider_id,
+            item,
+            detection.episode,
+            confidence,
+            ListMatchSource::List,
+        )),
+        None => {
+            let results = search_provider(app, &list.provider_id, &detection.anime_title).await?;
+            find_best_list_match(&query, &results).map(|(item, confidence)| {
+                list_entry_match(
+                    &list.provider_id,
+                    item,
+                    detection.episode,
+                    confidence,
+                    ListMatchSource::Search,
+                )
+            })
+        }
+    };
+
+    match list_match {
+        Some(list_match) => Ok(Some(apply_seasonal_episode(app, &list, list_match).await)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::types::SupportedPlayer;
+    use super::*;
+
+    fn item(id: u64, title: &str, alternative_titles: &str, total: u32) -> StoredAnimeListItem {
+        StoredAnimeListItem {
+            id,
+            title: title.to_string(),
+            alternative_titles: alternative_titles.to_string(),
+            total_episodes: total,
+            ..Default::default()
+        }
+    }
+
+    fn query(title: &str, season: Option<u32>, episode: Option<u32>) -> MatchQuery {
+        let detection =
+            AnimePlaybackDetection::new(SupportedPlayer::Mpv, title.to_string(), episode)
+                .with_season(season);
+        MatchQuery::from_detection(&detection).expect("title should normalize")
+    }
+
+    fn sample_list() -> Vec<StoredAnimeListItem> {
+        vec![
+            item(
+                52991,
+                "Sousou no Frieren",
+                "Frieren: Beyond Journey's End, 葬送のフリーレン",
+                28,
+            ),
+            item(50265, "Spy x Family", "SPY×FAMILY, Unknown", 12),
+            item(53887, "Spy x Family Season 2", "SPY×FAMILY Season 2", 12),
+            item(
+                54492,
+                "Kusuriya no Hitorigoto",
+                "The Apothecary Diaries, 薬屋のひとりごと",
+                24,
+            ),
+            item(1, "Mob Psycho 100", "", 12),
+            item(2, "Mob Psycho 100 III", "", 12),
+        ]
+    }
+
+    #[test]
+    fn stored_anime_list_reads_persisted_store_shape() {
+        let list: StoredAnimeList = serde_json::from_str(
+            r#"{
+                "watching": [{
+                    "id": 52991,
+                    "entryId": 900,
+                    "title": "Sousou no Frieren",
+                    "imageUrl": "https://example.invalid/cover.jpg",
+                    "alternativeTitles": "Frieren: Beyond Journey's End",
+                    "totalEpisodes": 28,
+                    "userStatus": "watching",
+                    "userEpisodesWatched": 4,
+                    "userStartDate": "2024-01-02",
+                    "userFinishDate": null
+                }],
+                "completed": [],
+                "onHold": [],
+                "dropped": [],
+                "planToWatch": [{ "id": 1, "title": "Dandadan" }]
+            }"#,
+        )
+        .expect("store should deserialize");
+
+        let items = list.into_items();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].entry_id, Some(900));
+        assert_eq!(items[0].user_start_date.as_deref(), Some("2024-01-02"));
+        assert_eq!(items[1].title, "Dandadan");
+
+        let searched = items_from_search_result(serde_json::json!([
+            { "id": 7, "title": "Dandadan", "alternativeTitles": "Unknown", "totalEpisodes": 12 }
+        ]))
+        .expect("search results should convert");
+        assert_eq!(searched[0].id, 7);
+        assert_eq!(searched[0].entry_id, None);
+    }
+
+    #[test]
+    fn seasoned_title_splits_common_season_markers() {
+        let parse = |value: &str| {
+            let title = SeasonedTitle::parse(value);
+            (title.base, title.season)
+        };
+
+        assert_eq!(
+            parse("Pokémon: Mewtwo Strikes Back"),
+            ("pokemon mewtwo strikes back".to_string(), None)
+        );
+        assert_eq!(
+            parse("Spy x Family Season 2"),
+            ("spy x family".to_string(), Some(2))
+        );
+        assert_eq!(
+            parse("Shingeki no Kyojin 3rd Season"),
+            ("shingeki no kyojin".to_string(), Some(3))
+        );
+        assert_eq!(parse("Oshi no Ko S2"), ("oshi no ko".to_string(), Some(2)));
+        assert_eq!(
+            parse("Mob Psycho 100 III"),
+            ("mob psycho 100".to_string(), Some(3))
+        );
+        assert_eq!(
+            parse("Mob Psycho 100"),
+            ("mob psycho 100".to_string(), None)
+        );
+        assert_eq!(parse("Kaguya-sama 2"), ("kaguya sama".to_string(), Some(2)));
+        assert_eq!(parse("86"), ("86".to_string(), None));
+    }
+
+    #[test]
+    fn find_best_list_match_scores_exact_fuzzy_and_alternative_titles() {
+        let items = sample_list();
+        let best = |title: &str, season: Option<u32>, episode: Option<u32>| {
+            find_best_list_match(&query(title, season, episode), &items)
+                .map(|(item, confidence)| (item.id, (confidence * 100.0).round() / 100.0))
+        };
+
+        assert_eq!(best("Sousou no Frieren", None, Some(5)), Some((52991, 1.0)));
+        assert_eq!(
+            best("the apothecary diaries", None, Some(3)),
+            Some((54492, 1.0))
+        );
+        assert_eq!(best("葬送のフリーレン", None, Some(1)), Some((52991, 1.0)));
+
+        let (id, confidence) =
+            best("Kusuriya no Hitorigoto (2023)", None, Some(3)).expect("fuzzy match");
+        assert_eq!(id, 54492);
+        assert!((0.8..1.0).contains(&confidence), "{confidence}");
+
+        let (id, confidence) = best("Sousou no Frieran", None, Some(5)).expect("typo match");
+        assert_eq!(id, 52991);
+        assert!((0.8..1.0).contains(&confidence), "{confidence}");
+
+        assert_eq!(best("Dandadan", None, Some(1)), None);
+    }
+
+    #[test]
+    fn find_best_list_match_uses_season_and_episode_count() {
+        let items = sample_list();
+        let best = |title: &str, season: Option<u32>, episode: Option<u32>| {
+            find_best_list_match(&query(title, season, episode), &items).map(|(item, _)| item.id)
+        };
+
+        assert_eq!(best("Spy x Family", None, Some(3)), Some(50265));
+        assert_eq!(best("Spy x Family", Some(2), Some(3)), Some(53887));
+        assert_eq!(best("Spy x Family S2", None, Some(3)), Some(53887));
+        assert_eq!(best("Mob Psycho 100", Some(3), Some(1)), Some(2));
+
+        // Episode 20 cannot belong to a 12 episode season, which lowers the
+        // confidence even for an exact title.
+        let (_, confidence) = find_best_list_match(&query("Spy x Family", None, Some(20)), &items)
+            .expect("still matched");
+        assert!(confidence < 0.9, "{confidence}");
+    }
+}
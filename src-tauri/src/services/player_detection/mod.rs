@@ -1,16 +1,56 @@
+mod aliases;
 mod detector;
+mod local_http;
+mod matcher;
+mod mpc_web;
+#[cfg(target_os = "linux")]
+mod mpris;
+mod mpv_ipc;
 mod observer;
 mod parser;
+mod playlist;
+#[cfg(target_os = "linux")]
+mod proc_connector;
 mod processes;
+mod relations;
+mod selection;
+mod sources;
+mod tokenizer;
 mod types;
 mod util;
+mod vlc_http;
 
+pub use aliases::{
+    add_title_alias, delete_title_alias, export_title_aliases, import_title_aliases,
+    list_title_aliases, TitleAlias, TitleAliasState,
+};
 pub use detector::detect_playing_anime;
+pub(crate) use matcher::{read_active_anime_list, StoredAnimeListItem};
 pub use observer::{
     configure_playback_observer, get_playback_observer_state, start_playback_observer,
     PlaybackObserverState,
 };
+pub use parser::{parse_release_name, ParsedRelease};
+pub use relations::AnimeRelationsState;
 pub use types::{
-    AnimePlaybackDetection, ConfigurePlaybackObserverRequest, DetectPlayingAnimeRequest,
-    PlaybackObserverSnapshot, SupportedPlayer,
+    AnimePlaybackDetection, ConfigurePlaybackObserverRequest, CustomPlayerDefinition,
+    DetectPlayingAnimeRequest, DetectionCandidate, DetectionSelectionConfig,
+    DetectionSourcesConfig, DetectionStabilityConfig, ListEntryMatch, ListMatchSource,
+    MediaSourceKind, MpcWebConfig, MpvIpcConfig, PlaybackObserverSnapshot, PlaybackProgress,
+    PlayerOptionStyle, PlayerPriorityEntry, SelectionPolicy, SupportedPlayer, TransitionReason,
+    VlcHttpConfig,
 };
+
+// Entry points for the `cargo fuzz` targets in `src-tauri/fuzz`.
+#[cfg(fuzzing)]
+pub mod fuzzing {
+    use super::ParsedRelease;
+
+    pub fn parse_anime_from_source(source: &str) -> Option<ParsedRelease> {
+        super::parser::parse_anime_from_source(source)
+    }
+
+    pub fn split_command_line(value: &str) -> Vec<String> {
+        super::util::split_command_line(value)
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use super::local_http::get_local;
+use super::types::{MpcWebConfig, PlayerPlaybackStatus, SupportedPlayer};
+
+const MPC_VARIABLES_PATH: &str = "/variables.html";
+const MPC_DEFAULT_WEB_PORT: u16 = 13579;
+
+// Values of the `state` variable reported by MPC-HC and MPC-BE; anything else
+// means nothing is loaded or playback is stopped.
+const MPC_STATE_PAUSED: i32 = 1;
+const MPC_STATE_PLAYING: i32 = 2;
+
+pub(crate) fn resolve_port(config: &MpcWebConfig, player: SupportedPlayer) -> Option<u16> {
+    if !config.enabled {
+        return None;
+    }
+
+    let port = match player {
+        SupportedPlayer::MpcBe => config.mpc_be_port.or(config.port),
+        _ => config.port,
+    };
+    Some(port.unwrap_or(MPC_DEFAULT_WEB_PORT))
+}
+
+pub(crate) fn query_playback_status(port: u16) -> Result<PlayerPlaybackStatus, String> {
+    let response = get_local(port, MPC_VARIABLES_PATH, None)
+        .map_err(|error| format!("Failed to query MPC web interface: {error}"))?;
+
+    if response.status != 200 {
+        return Err(format!(
+            "MPC web interface returned status {}",
+            response.status
+        ));
+    }
+
+    parse_variables_page(&response.body)
+}
+
+fn parse_variables_page(html: &str) -> Result<PlayerPlaybackStatus, String> {
+    let variables: HashMap<&str, String> = variable_regex()
+        .captures_iter(html)
+        .filter_map(|captures| {
+            let id = captures.name("id")?.as_str();
+            let value = decode_html_entities(captures.name("value")?.as_str().trim());
+            Some((id, value))
+        })
+        .collect();
+
+    let state = variables
+        .get("state")
+        .and_then(|value| value.parse::<i32>().ok())
+        .ok_or_else(|| "MPC web interface response is missing the player state".to_string())?;
+
+    if state != MPC_STATE_PAUSED && state != MPC_STATE_PLAYING {
+        return Ok(PlayerPlaybackStatus::default());
+    }
+
+    Ok(PlayerPlaybackStatus {
+        media_path: variables
+            .get("filepath")
+            .filter(|value| !value.is_empty())
+            .cloned(),
+        media_title: variables
+            .get("file")
+            .filter(|value| !value.is_empty())
+            .cloned(),
+        position_seconds: parse_milliseconds(variables.get("position")),
+        duration_seconds: parse_milliseconds(variables.get("duration"))
+            .filter(|value| *value > 0.0),
+        paused: Some(state == MPC_STATE_PAUSED),
+        playlist_position: None,
+        stopped: false,
+    })
+}
+
+fn parse_milliseconds(value: Option<&String>) -> Option<f64> {
+    value
+        .and_then(|raw| raw.parse::<f64>().ok())
+        .filter(|milliseconds| *milliseconds >= 0.0)
+        .map(|milliseconds| milliseconds / 1000.0)
+}
+
+fn decode_html_entities(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn variable_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r#"<p\s+id="(?P<id>[a-z]+)"\s*>(?P<value>[^<]*)</p>"#)
+            .expect("valid MPC variable regex")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::local_http::stub_server;
+    use super::*;
+
+    const VARIABLES_FIXTURE: &str = include_str!("fixtures/mpc_variables.html");
+
+    #[test]
+    fn parse_variables_page_reads_fixture_file_state_and_position() {
+        let status = parse_variables_page(VARIABLES_FIXTURE).expect("fixture should parse");
+
+        assert_eq!(
+            status.media_path.as_deref(),
+            Some(
+                r"D:\Anime\Sousou no Frieren\[SubsPlease] Sousou no Frieren - 05 (1080p) [ABCD1234].mkv"
+            )
+        );
+        assert_eq!(
+            status.media_title.as_deref(),
+            Some("[SubsPlease] Sousou no Frieren - 05 (1080p) [ABCD1234].mkv")
+        );
+        assert_eq!(status.position_seconds, Some(312.48));
+        assert_eq!(status.duration_seconds, Some(1420.032));
+        assert_eq!(status.paused, Some(true));
+    }
+
+    #[test]
+    fn parse_variables_page_decodes_entities_and_handles_idle_players() {
+        let playing = parse_variables_page(
+            r#"<p id="filepath">C:\Anime\Tom &amp; Jerry - 01.mkv</p><p id="state">2</p><p id="position">0</p><p id="duration">0</p>"#,
+        )
+        .expect("page should parse");
+        assert_eq!(
+            playing.media_path.as_deref(),
+            Some(r"C:\Anime\Tom & Jerry - 01.mkv")
+        );
+        assert_eq!(playing.paused, Some(false));
+        assert_eq!(playing.duration_seconds, None);
+
+        let idle = parse_variables_page(r#"<p id="filepath"></p><p id="state">-1</p>"#)
+            .expect("idle page should parse");
+        assert_eq!(idle, PlayerPlaybackStatus::default());
+
+        assert!(parse_variables_page("<html></html>").is_err());
+    }
+
+    #[test]
+    fn resolve_port_uses_default_when_enabled_without_port() {
+        assert_eq!(
+            resolve_port(&MpcWebConfig::default(), SupportedPlayer::MpcHc),
+            None
+        );
+        let enabled = MpcWebConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_port(&enabled, SupportedPlayer::MpcBe),
+            Some(MPC_DEFAULT_WEB_PORT)
+        );
+
+        let configured = MpcWebConfig {
+            port: Some(13580),
+            ..enabled
+        };
+        assert_eq!(
+            resolve_port(&configured, SupportedPlayer::MpcBe),
+            Some(13580)
+        );
+
+        let separate = MpcWebConfig {
+            mpc_be_port: Some(13581),
+            ..configured
+        };
+        assert_eq!(resolve_port(&separate, SupportedPlayer::MpcHc), Some(13580));
+        assert_eq!(resolve_port(&separate, SupportedPlayer::MpcBe), Some(13581));
+    }
+
+    #[test]
+    fn query_playback_status_polls_variables_page_from_stub_server() {
+        let (port, server) = stub_server::spawn("200 OK", VARIABLES_FIXTURE.to_string());
+
+        let status = query_playback_status(port).expect("status should be read");
+        let request = server.join().expect("server should finish");
+
+        assert!(request.starts_with("GET /variables.html HTTP/1.0\r\n"));
+        assert_eq!(status.paused, Some(true));
+
+        let (port, server) = stub_server::spawn("404 Not Found", String::new());
+        let error = query_playback_status(port).unwrap_err();
+        server.join().expect("server should finish");
+        assert_eq!(error, "MPC web interface returned status 404");
+    }
+}
@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::Duration;
+
+use zbus::blocking::fdo::DBusProxy;
+use zbus::blocking::{connection, Connection, Proxy};
+use zbus::names::BusName;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::{OwnedValue, Value};
+
+use super::parser::has_video_extension;
+use super::types::PlayerPlaybackStatus;
+
+const MPRIS_BUS_NAME_PREFIX: &str = "org.mpris.MediaPlayer2.";
+const MPRIS_OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const MPRIS_PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+const MPRIS_METHOD_TIMEOUT_MS: u64 = 500;
+const MICROSECONDS_PER_SECOND: f64 = 1_000_000.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MprisPlayerSnapshot {
+    pub bus_name: String,
+    pub process_id: Option<u32>,
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub status: PlayerPlaybackStatus,
+}
+
+impl MprisPlayerSnapshot {
+    // Local files and direct video links carry the release name in the URL;
+    // streaming sites only expose something useful through `xesam:title`.
+    pub(crate) fn detection_source(&self) -> Option<MprisDetectionSource> {
+        if let Some(url) = self.url.as_deref() {
+            let without_query = url.split(['?', '#']).next().unwrap_or(url);
+            if has_video_extension(without_query) {
+                return Some(MprisDetectionSource::Url(url.to_string()));
+            }
+        }
+
+        self.title
+            .as_ref()
+            .map(|title| MprisDetectionSource::Title(title.clone()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MprisDetectionSource {
+    Url(String),
+    Title(String),
+}
+
+// Each connection runs the bus handshake and starts its own executor thread,
+// so one is kept for every poll and only replaced after it fails.
+pub(crate) fn list_mpris_players() -> Result<Vec<MprisPlayerSnapshot>, String> {
+    let mut session = session_connection()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let connection = match session.as_ref() {
+        Some(connection) => connection.clone(),
+        None => session.insert(connect_session_bus()?).clone(),
+    };
+
+    let players = list_mpris_players_on(&connection);
+    if players.is_err() {
+        *session = None;
+    }
+    players
+}
+
+fn session_connection() -> &'static Mutex<Option<Connection>> {
+    static SESSION: OnceLock<Mutex<Option<Connection>>> = OnceLock::new();
+    SESSION.get_or_init(|| Mutex::new(None))
+}
+
+fn connect_session_bus() -> Result<Connection, String> {
+    connection::Builder::session()
+        .map_err(|error| format!("Failed to configure D-Bus session connection: {error}"))?
+        .method_timeout(Duration::from_millis(MPRIS_METHOD_TIMEOUT_MS))
+        .build()
+        .map_err(|error| format!("Failed to connect to the D-Bus session bus: {error}"))
+}
+
+fn list_mpris_players_on(connection: &Connection) -> Result<Vec<MprisPlayerSnapshot>, String> {
+    let dbus = DBusProxy::new(connection)
+        .map_err(|error| format!("Failed to create D-Bus proxy: {error}"))?;
+    let names = dbus
+        .list_names()
+        .map_err(|error| format!("Failed to list D-Bus names: {error}"))?;
+
+    let mut bus_names: Vec<String> = names
+        .into_iter()
+        .map(|name| name.to_string())
+        .filter(|name| name.starts_with(MPRIS_BUS_NAME_PREFIX))
+        .collect();
+    bus_names.sort();
+
+    let mut players = Vec::new();
+    for bus_name in bus_names {
+        // A player may quit between listing and querying; skip it rather than
+        // failing the whole cycle.
+        let Ok(properties) = read_player_properties(connection, &bus_name) else {
+            continue;
+        };
+
+        let process_id = BusName::try_from(bus_name.as_str())
+            .ok()
+            .and_then(|name| dbus.get_connection_unix_process_id(name).ok());
+
+        players.push(snapshot_from_properties(bus_name, process_id, &properties));
+    }
+
+    Ok(players)
+}
+
+fn read_player_properties(
+    connection: &Connection,
+    bus_name: &str,
+) -> Result<HashMap<String, OwnedValue>, String> {
+    let proxy: Proxy<'_> = zbus::blocking::proxy::Builder::new(connection)
+        .destination(bus_name.to_string())
+        .and_then(|builder| builder.path(MPRIS_OBJECT_PATH))
+        .and_then(|builder| builder.interface(MPRIS_PLAYER_INTERFACE))
+        .map(|builder| builder.cache_properties(CacheProperties::No))
+        .and_then(|builder| builder.build())
+        .map_err(|error| format!("Failed to create MPRIS proxy for {bus_name}: {error}"))?;
+
+    let mut properties = HashMap::new();
+    for name in ["Metadata", "PlaybackStatus", "Position"] {
+        // `Position` is optional for players that cannot seek (live streams).
+        if let Ok(value) = proxy.get_property::<OwnedValue>(name) {
+            properties.insert(name.to_string(), value);
+        }
+    }
+
+    if properties.is_empty() {
+        return Err(format!(
+            "MPRIS player {bus_name} did not expose any properties"
+        ));
+    }
+
+    Ok(properties)
+}
+
+fn snapshot_from_properties(
+    bus_name: String,
+    process_id: Option<u32>,
+    properties: &HashMap<String, OwnedValue>,
+) -> MprisPlayerSnapshot {
+    let metadata: HashMap<String, OwnedValue> = properties
+        .get("Metadata")
+        .and_then(|value| value.try_clone().ok())
+        .and_then(|value| HashMap::<String, OwnedValue>::try_from(value).ok())
+        .unwrap_or_default();
+
+    let url = metadata.get("xesam:url").and_then(value_as_string);
+    let title = metadata.get("xesam:title").and_then(value_as_string);
+    let duration_seconds = metadata
+        .get("mpris:length")
+        .and_then(value_as_i64)
+        .filter(|value| *value > 0)
+        .map(|value| value as f64 / MICROSECONDS_PER_SECOND);
+
+    let playback_status = properties.get("PlaybackStatus").and_then(value_as_string);
+    let position_seconds = properties
+        .get("Position")
+        .and_then(value_as_i64)
+        .filter(|value| *value >= 0)
+        .map(|value| value as f64 / MICROSECONDS_PER_SECOND);
+
+    let is_stopped = playback_status.as_deref() == Some("Stopped");
+    let status = if is_stopped {
+        PlayerPlaybackStatus {
+            stopped: true,
+            ..Default::default()
+        }
+    } else {
+        PlayerPlaybackStatus {
+            media_path: url.clone(),
+            media_title: title.clone(),
+            position_seconds,
+            duration_seconds,
+            paused: playback_status.as_deref().map(|status| status == "Paused"),
+            playlist_position: None,
+            stopped: false,
+        }
+    };
+
+    MprisPlayerSnapshot {
+        bus_name,
+        process_id,
+        url: url.filter(|_| !is_stopped),
+        title: title.filter(|_| !is_stopped),
+        status,
+    }
+}
+
+fn value_as_string(value: &OwnedValue) -> Option<String> {
+    match &**value {
+        Value::Str(text) => Some(text.as_str().trim().to_string()).filter(|text| !text.is_empty()),
+        Value::Value(inner) => value_as_string(&OwnedValue::try_from(&**inner).ok()?),
+        _ => None,
+    }
+}
+
+fn value_as_i64(value: &OwnedValue) -> Option<i64> {
+    match &**value {
+        Value::I64(number) => Some(*number),
+        Value::U64(number) => i64::try_from(*number).ok(),
+        Value::I32(number) => Some(i64::from(*number)),
+        Value::U32(number) => Some(i64::from(*number)),
+        Value::Value(inner) => value_as_i64(&OwnedValue::try_from(&**inner).ok()?),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owned(value: Value<'_>) -> OwnedValue {
+        OwnedValue::try_from(value).expect("value should convert")
+    }
+
+    fn metadata(entries: &[(&str, Value<'_>)]) -> OwnedValue {
+        let map: HashMap<String, OwnedValue> = entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), owned(value.try_clone().expect("clone"))))
+            .collect();
+        owned(Value::from(map))
+    }
+
+    #[test]
+    fn snapshot_from_properties_maps_metadata_status_and_position() {
+        let properties = HashMap::from([
+            (
+                "Metadata".to_string(),
+                metadata(&[
+                    (
+                        "xesam:url",
+                        Value::from("file:///anime/Frieren/Sousou%20no%20Frieren%20-%2005.mkv"),
+                    ),
+                    ("xesam:title", Value::from("Sousou no Frieren - 05")),
+                    ("mpris:length", Value::from(1_420_000_000_i64)),
+                ]),
+            ),
+            ("PlaybackStatus".to_string(), owned(Value::from("Paused"))),
+            ("Position".to_string(), owned(Value::from(312_500_000_i64))),
+        ]);
+
+        let snapshot = snapshot_from_properties(
+            "org.mpris.MediaPlayer2.celluloid".to_string(),
+            Some(42),
+            &properties,
+        );
+
+        assert_eq!(snapshot.process_id, Some(42));
+        assert_eq!(snapshot.status.position_seconds, Some(312.5));
+        assert_eq!(snapshot.status.duration_seconds, Some(1420.0));
+        assert_eq!(snapshot.status.paused, Some(true));
+        assert_eq!(
+            snapshot.detection_source(),
+            Some(MprisDetectionSource::Url(
+                "file:///anime/Frieren/Sousou%20no%20Frieren%20-%2005.mkv".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn detection_source_falls_back_to_title_for_streaming_urls_and_clears_stopped_players() {
+        let properties = HashMap::from([
+            (
+                "Metadata".to_string(),
+                metadata(&[
+                    ("xesam:url", Value::from("https://example.com/watch?v=abc")),
+                    ("xesam:title", Value::from("Dr. Stone - 05")),
+                ]),
+            ),
+            ("PlaybackStatus".to_string(), owned(Value::from("Playing"))),
+        ]);
+        let playing = snapshot_from_properties(
+            "org.mpris.MediaPlayer2.firefox".to_string(),
+            None,
+            &properties,
+        );
+        assert_eq!(
+            playing.detection_source(),
+            Some(MprisDetectionSource::Title("Dr. Stone - 05".to_string()))
+        );
+        assert_eq!(playing.status.paused, Some(false));
+        assert_eq!(playing.status.position_seconds, None);
+
+        let mut stopped_properties = properties;
+        stopped_properties.insert("PlaybackStatus".to_string(), owned(Value::from("Stopped")));
+        let stopped = snapshot_from_properties(
+            "org.mpris.MediaPlayer2.firefox".to_string(),
+            None,
+            &stopped_properties,
+        );
+        assert_eq!(stopped.detection_source(), None);
+        assert_eq!(
+            stopped.status,
+            PlayerPlaybackStatus {
+                stopped: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    mod private_bus {
+        use std::collections::HashMap;
+        use std::io::{BufRead, BufReader};
+        use std::process::{Child, Command, Stdio};
+
+        use zbus::interface;
+        use zbus::zvariant::{OwnedValue, Value};
+
+        pub(super) struct PrivateBus {
+            daemon: Child,
+            pub address: String,
+        }
+
+        impl PrivateBus {
+            // Returns None when dbus-daemon is not installed so the suite still
+            // runs on machines without a D-Bus implementation.
+            pub(super) fn start() -> Option<Self> {
+                let mut daemon = Command::new("dbus-daemon")
+                    .args(["--session", "--nofork", "--nopidfile", "--print-address=1"])
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .ok()?;
+
+                let mut address = String::new();
+                let stdout = daemon.stdout.take()?;
+                BufReader::new(stdout).read_line(&mut address).ok()?;
+
+                Some(Self {
+                    daemon,
+                    address: address.trim().to_string(),
+                })
+            }
+        }
+
+        impl Drop for PrivateBus {
+            fn drop(&mut self) {
+                let _ = self.daemon.kill();
+                let _ = self.daemon.wait();
+            }
+        }
+
+        pub(super) struct MockPlayer {
+            pub url: String,
+            pub title: String,
+            pub status: String,
+            pub position: i64,
+        }
+
+        #[interface(name = "org.mpris.MediaPlayer2.Player")]
+        impl MockPlayer {
+            #[zbus(property)]
+            fn metadata(&self) -> HashMap<String, OwnedValue> {
+                HashMap::from([
+                    (
+                        "xesam:url".to_string(),
+                        OwnedValue::try_from(Value::from(self.url.as_str())).expect("url"),
+                    ),
+                    (
+                        "xesam:title".to_string(),
+                        OwnedValue::try_from(Value::from(self.title.as_str())).expect("title"),
+                    ),
+                    (
+                        "mpris:length".to_string(),
+                        OwnedValue::from(1_420_000_000_i64),
+                    ),
+                ])
+            }
+
+            #[zbus(property)]
+            fn playback_status(&self) -> String {
+                self.status.clone()
+            }
+
+            #[zbus(property)]
+            fn position(&self) -> i64 {
+                self.position
+            }
+        }
+    }
+
+    #[test]
+    fn list_mpris_players_on_reads_mock_service_from_private_bus() {
+        let Some(bus) = private_bus::PrivateBus::start() else {
+            eprintln!("dbus-daemon is unavailable; skipping private bus test");
+            return;
+        };
+
+        let _service = connection::Builder::address(bus.address.as_str())
+            .expect("address should parse")
+            .name("org.mpris.MediaPlayer2.mock")
+            .expect("name should be valid")
+            .serve_at(
+                MPRIS_OBJECT_PATH,
+                private_bus::MockPlayer {
+                    url: "file:///anime/Haruna%20Test%20-%2003.mkv".to_string(),
+                    title: "Haruna Test - 03".to_string(),
+                    status: "Playing".to_string(),
+                    position: 60_000_000,
+                },
+            )
+            .expect("object should be served")
+            .build()
+            .expect("mock service should connect");
+
+        let client = connection::Builder::address(bus.address.as_str())
+            .expect("address should parse")
+            .build()
+            .expect("client should connect");
+
+        let players = list_mpris_players_on(&client).expect("players should be listed");
+
+        assert_eq!(players.len(), 1);
+        assert_eq!(players[0].bus_name, "org.mpris.MediaPlayer2.mock");
+        assert_eq!(players[0].process_id, Some(std::process::id()));
+        assert_eq!(players[0].title.as_deref(), Some("Haruna Test - 03"));
+        assert_eq!(players[0].status.position_seconds, Some(60.0));
+        assert_eq!(players[0].status.duration_seconds, Some(1420.0));
+        assert_eq!(players[0].status.paused, Some(false));
+    }
+}
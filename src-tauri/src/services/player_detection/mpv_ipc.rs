@@ -0,0 +1,511 @@
+use std::io;
+use std::io::{BufRead, BufReader, Read, Write};
+#[cfg(windows)]
+use std::os::windows::io::{AsRawHandle, RawHandle};
+#[cfg(windows)]
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::types::PlayerPlaybackStatus;
+use super::util::find_long_option_value;
+
+const MPV_IPC_SERVER_OPTION: &str = "--input-ipc-server";
+const MPV_IPC_SUCCESS: &str = "success";
+const MPV_IPC_IO_TIMEOUT_MS: u64 = 500;
+#[cfg(windows)]
+const MPV_IPC_CANCEL_POLL_MS: u64 = 10;
+#[cfg(windows)]
+const MPV_IPC_CANCEL_ATTEMPTS: u32 = 50;
+
+const MPV_PROPERTY_PATH: &str = "path";
+const MPV_PROPERTY_MEDIA_TITLE: &str = "media-title";
+const MPV_PROPERTY_TIME_POS: &str = "time-pos";
+const MPV_PROPERTY_DURATION: &str = "duration";
+const MPV_PROPERTY_PAUSE: &str = "pause";
+const MPV_PROPERTY_PLAYLIST_POS: &str = "playlist-pos";
+
+#[derive(Debug, Serialize)]
+struct MpvIpcCommand<'a> {
+    command: [&'a str; 2],
+    request_id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct MpvIpcResponse {
+    #[serde(default)]
+    request_id: Option<u64>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    data: Option<Value>,
+    #[serde(default)]
+    event: Option<String>,
+}
+
+pub(crate) fn find_ipc_server_arg(args: &[String]) -> Option<String> {
+    find_long_option_value(args, MPV_IPC_SERVER_OPTION)
+}
+
+pub(crate) fn query_playback_status(endpoint: &str) -> Result<PlayerPlaybackStatus, String> {
+    let stream = MpvIpcStream::connect(endpoint)
+        .map_err(|error| format!("Failed to connect to mpv IPC at {endpoint}: {error}"))?;
+
+    match stream {
+        #[cfg(windows)]
+        MpvIpcStream::Windows(pipe) => read_pipe_playback_status(pipe),
+        #[cfg(unix)]
+        stream => {
+            stream.configure_timeouts()?;
+            MpvIpcClient::new(stream).read_playback_status()
+        }
+    }
+}
+
+// Named pipes opened through `File` have no read timeout, so the exchange runs
+// on a worker thread. Past the deadline its pending I/O is cancelled until the
+// worker gives up, which keeps a hung mpv from piling up blocked threads. A
+// read that ignores cancellation is left to its worker so detection goes on.
+#[cfg(windows)]
+fn read_pipe_playback_status(pipe: std::fs::File) -> Result<PlayerPlaybackStatus, String> {
+    let pipe = Arc::new(pipe);
+    let worker_pipe = Arc::clone(&pipe);
+    let (sender, receiver) = mpsc::sync_channel(1);
+    std::thread::spawn(move || {
+        let _ = sender.send(MpvIpcClient::new(&*worker_pipe).read_playback_status());
+    });
+
+    if let Ok(result) = receiver.recv_timeout(Duration::from_millis(MPV_IPC_IO_TIMEOUT_MS)) {
+        return result;
+    }
+
+    for _ in 0..MPV_IPC_CANCEL_ATTEMPTS {
+        // SAFETY: `pipe` keeps the handle open for the duration of the call, and
+        // a null OVERLAPPED pointer asks for every pending request to be cancelled.
+        unsafe {
+            CancelIoEx(pipe.as_raw_handle(), std::ptr::null_mut());
+        }
+
+        if !matches!(
+            receiver.recv_timeout(Duration::from_millis(MPV_IPC_CANCEL_POLL_MS)),
+            Err(mpsc::RecvTimeoutError::Timeout)
+        ) {
+            break;
+        }
+    }
+
+    Err("Timed out waiting for mpv IPC response".to_string())
+}
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn CancelIoEx(file: RawHandle, overlapped: *mut std::ffi::c_void) -> i32;
+}
+
+struct MpvIpcClient<S: Read + Write> {
+    reader: BufReader<S>,
+    next_request_id: u64,
+}
+
+impl<S: Read + Write> MpvIpcClient<S> {
+    fn new(stream: S) -> Self {
+        Self {
+            reader: BufReader::new(stream),
+            next_request_id: 1,
+        }
+    }
+
+    fn read_playback_status(&mut self) -> Result<PlayerPlaybackStatus, String> {
+        let media_path = self
+            .get_property(MPV_PROPERTY_PATH)?
+            .and_then(|value| value.as_str().map(str::to_string))
+            .filter(|value| !value.trim().is_empty());
+
+        let media_title = self
+            .get_property(MPV_PROPERTY_MEDIA_TITLE)?
+            .and_then(|value| value.as_str().map(str::to_string))
+            .filter(|value| !value.trim().is_empty());
+
+        let position_seconds = self
+            .get_property(MPV_PROPERTY_TIME_POS)?
+            .and_then(|value| value.as_f64());
+        let duration_seconds = self
+            .get_property(MPV_PROPERTY_DURATION)?
+            .and_then(|value| value.as_f64());
+        let paused = self
+            .get_property(MPV_PROPERTY_PAUSE)?
+            .and_then(|value| value.as_bool());
+        // mpv reports -1 when nothing from the playlist is loaded.
+        let playlist_position = self
+            .get_property(MPV_PROPERTY_PLAYLIST_POS)?
+            .and_then(|value| value.as_u64())
+            .and_then(|value| usize::try_from(value).ok());
+
+        Ok(PlayerPlaybackStatus {
+            media_path,
+            media_title,
+            position_seconds,
+            duration_seconds,
+            paused,
+            playlist_position,
+            stopped: false,
+        })
+    }
+
+    fn get_property(&mut self, name: &str) -> Result<Option<Value>, String> {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+
+        let mut payload = serde_json::to_vec(&MpvIpcCommand {
+            command: ["get_property", name],
+            request_id,
+        })
+        .map_err(|error| format!("Failed to encode mpv IPC command: {error}"))?;
+        payload.push(b'\n');
+
+        let writer = self.reader.get_mut();
+        writer
+            .write_all(&payload)
+            .map_err(|error| format!("Failed to write mpv IPC command: {error}"))?;
+        writer
+            .flush()
+            .map_err(|error| format!("Failed to flush mpv IPC stream: {error}"))?;
+
+        loop {
+            let response = self.read_response()?;
+
+            // mpv interleaves asynchronous events with command replies.
+            if response.event.is_some() || response.request_id != Some(request_id) {
+                continue;
+            }
+
+            return match response.error.as_deref() {
+                Some(MPV_IPC_SUCCESS) | None => Ok(response.data.filter(|value| !value.is_null())),
+                // Properties such as `time-pos` are unavailable while nothing is loaded.
+                Some("property unavailable") => Ok(None),
+                Some(error) => Err(format!("mpv IPC returned an error for {name}: {error}")),
+            };
+        }
+    }
+
+    fn read_response(&mut self) -> Result<MpvIpcResponse, String> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = self
+                .reader
+                .read_line(&mut line)
+                .map_err(|error| format!("Failed to read mpv IPC response: {error}"))?;
+
+            if read == 0 {
+                return Err("mpv IPC connection closed".to_string());
+            }
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            return serde_json::from_str(line.trim())
+                .map_err(|error| format!("Failed to decode mpv IPC response: {error}"));
+        }
+    }
+}
+
+enum MpvIpcStream {
+    #[cfg(windows)]
+    Windows(std::fs::File),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixStream),
+}
+
+impl MpvIpcStream {
+    fn connect(endpoint: &str) -> io::Result<Self> {
+        #[cfg(windows)]
+        {
+            return std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(windows_pipe_path(endpoint))
+                .map(Self::Windows);
+        }
+
+        #[cfg(unix)]
+        {
+            return std::os::unix::net::UnixStream::connect(endpoint).map(Self::Unix);
+        }
+
+        #[allow(unreachable_code)]
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "mpv IPC is not supported on this platform",
+        ))
+    }
+
+    #[cfg(unix)]
+    fn configure_timeouts(&self) -> Result<(), String> {
+        let timeout = Some(Duration::from_millis(MPV_IPC_IO_TIMEOUT_MS));
+        match self {
+            Self::Unix(stream) => {
+                stream.set_read_timeout(timeout).map_err(|error| {
+                    format!("Failed to configure mpv IPC read timeout: {error}")
+                })?;
+                stream.set_write_timeout(timeout).map_err(|error| {
+                    format!("Failed to configure mpv IPC write timeout: {error}")
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Read for MpvIpcStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            #[cfg(windows)]
+            Self::Windows(stream) => stream.read(buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for MpvIpcStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            #[cfg(windows)]
+            Self::Windows(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            #[cfg(windows)]
+            Self::Windows(stream) => stream.flush(),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+// mpv accepts bare pipe names on Windows (`--input-ipc-server=mpvsocket`).
+#[cfg(windows)]
+fn windows_pipe_path(endpoint: &str) -> String {
+    if endpoint.starts_with(r"\\") {
+        endpoint.to_string()
+    } else {
+        format!(r"\\.\pipe\{endpoint}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_ipc_server_arg_accepts_inline_and_separate_values() {
+        assert_eq!(
+            find_ipc_server_arg(&[
+                "mpv".to_string(),
+                "--input-ipc-server=/tmp/mpvsocket".to_string(),
+                "/anime".to_string(),
+            ]),
+            Some("/tmp/mpvsocket".to_string())
+        );
+        assert_eq!(
+            find_ipc_server_arg(&[
+                "mpv".to_string(),
+                "--input-ipc-server".to_string(),
+                "\"/tmp/mpv socket\"".to_string(),
+            ]),
+            Some("/tmp/mpv socket".to_string())
+        );
+        assert_eq!(
+            find_ipc_server_arg(&["mpv".to_string(), "--input-ipc-server=".to_string()]),
+            None
+        );
+        assert_eq!(find_ipc_server_arg(&["mpv".to_string()]), None);
+    }
+
+    #[cfg(unix)]
+    mod fake_server {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixListener;
+        use std::path::PathBuf;
+
+        use serde_json::{json, Value};
+
+        pub(super) fn socket_path(name: &str) -> PathBuf {
+            let nonce = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("system time should be valid")
+                .as_nanos();
+            std::env::temp_dir().join(format!(
+                "kioku-mpv-ipc-{}-{name}-{nonce}.sock",
+                std::process::id()
+            ))
+        }
+
+        pub(super) fn spawn(
+            path: &PathBuf,
+            properties: Value,
+        ) -> std::thread::JoinHandle<Vec<String>> {
+            let listener = UnixListener::bind(path).expect("fake mpv socket should bind");
+
+            std::thread::spawn(move || {
+                let (stream, _) = listener.accept().expect("client should connect");
+                let mut writer = stream.try_clone().expect("stream should clone");
+                let reader = BufReader::new(stream);
+                let mut requested = Vec::new();
+
+                for line in reader.lines() {
+                    let Ok(line) = line else {
+                        break;
+                    };
+                    let command: Value =
+                        serde_json::from_str(&line).expect("command should be JSON");
+                    let name = command["command"][1]
+                        .as_str()
+                        .expect("property name")
+                        .to_string();
+                    let request_id = command["request_id"].clone();
+
+                    let reply = match properties.get(&name) {
+                        Some(value) => {
+                            json!({ "data": value, "request_id": request_id, "error": "success" })
+                        }
+                        None => {
+                            json!({ "request_id": request_id, "error": "property unavailable" })
+                        }
+                    };
+
+                    // Events may arrive between replies and must be skipped by the client.
+                    writeln!(writer, "{}", json!({ "event": "playback-restart" }))
+                        .expect("event should write");
+                    writeln!(writer, "{reply}").expect("reply should write");
+                    requested.push(name);
+                }
+
+                requested
+            })
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn query_playback_status_reads_properties_from_fake_server() {
+        let path = fake_server::socket_path("status");
+        let server = fake_server::spawn(
+            &path,
+            serde_json::json!({
+                "path": "/anime/Frieren/[SubsPlease] Sousou no Frieren - 05 (1080p).mkv",
+                "media-title": "Sousou no Frieren - 05",
+                "time-pos": 312.5,
+                "duration": 1420.0,
+                "pause": true,
+                "playlist-pos": 2
+            }),
+        );
+
+        let status = query_playback_status(path.to_str().expect("utf-8 socket path"))
+            .expect("status should be read");
+        let requested = server.join().expect("server should finish");
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            status.media_path.as_deref(),
+            Some("/anime/Frieren/[SubsPlease] Sousou no Frieren - 05 (1080p).mkv")
+        );
+        assert_eq!(
+            status.media_title.as_deref(),
+            Some("Sousou no Frieren - 05")
+        );
+        assert_eq!(status.position_seconds, Some(312.5));
+        assert_eq!(status.duration_seconds, Some(1420.0));
+        assert_eq!(status.paused, Some(true));
+        assert_eq!(status.playlist_position, Some(2));
+        assert_eq!(
+            requested,
+            vec![
+                "path",
+                "media-title",
+                "time-pos",
+                "duration",
+                "pause",
+                "playlist-pos"
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn query_playback_status_treats_unavailable_properties_as_missing() {
+        let path = fake_server::socket_path("idle");
+        let server = fake_server::spawn(&path, serde_json::json!({ "pause": false }));
+
+        let status = query_playback_status(path.to_str().expect("utf-8 socket path"))
+            .expect("status should be read");
+        server.join().expect("server should finish");
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            status,
+            PlayerPlaybackStatus {
+                paused: Some(false),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn query_playback_status_reports_connection_failures() {
+        let error = query_playback_status("/nonexistent/kioku-mpv-ipc.sock").unwrap_err();
+
+        assert!(
+            error.starts_with("Failed to connect to mpv IPC at /nonexistent/kioku-mpv-ipc.sock")
+        );
+    }
+
+    #[test]
+    fn mpv_ipc_client_surfaces_command_errors() {
+        struct ScriptedStream {
+            reply: std::io::Cursor<Vec<u8>>,
+        }
+
+        impl Read for ScriptedStream {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.reply.read(buf)
+            }
+        }
+
+        impl Write for ScriptedStream {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut client = MpvIpcClient::new(ScriptedStream {
+            reply: std::io::Cursor::new(
+                b"{\"request_id\":1,\"error\":\"invalid parameter\"}\n".to_vec(),
+            ),
+        });
+
+        assert_eq!(
+            client.get_property("path").unwrap_err(),
+            "mpv IPC returned an error for path: invalid parameter"
+        );
+        assert_eq!(
+            client.get_property("path").unwrap_err(),
+            "mpv IPC connection closed"
+        );
+    }
+}
@@ -1,24 +1,123 @@
-use std::time::Duration;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use serde::Serialize;
 use tauri::{Emitter, Manager};
 use tokio::sync::{Mutex, RwLock};
 
+use crate::services::scrobbler::ScrobblerState;
+
 use super::detector::{collect_detection_cycle_result, DetectionCycleResult};
+use super::matcher::resolve_list_match;
+use super::selection::select_candidate;
+use super::sources::{PlaybackSource, ProcessSource, SystemPlaybackSource, SystemProcessSource};
 use super::types::{
-    AnimePlaybackDetection, ConfigurePlaybackObserverRequest, PlaybackObserverSnapshot,
-    SupportedPlayer,
+    AnimePlaybackDetection, ConfigurePlaybackObserverRequest, CustomPlayerDefinition,
+    DetectionCandidate, DetectionSelectionConfig, DetectionSourcesConfig, DetectionStabilityConfig,
+    ListEntryMatch, PlaybackObserverSnapshot, PlaybackProgress, SelectionPolicy, SupportedPlayer,
+    TransitionReason,
+};
+use super::util::{
+    dedup_players, normalize_custom_player, normalize_custom_players, normalize_poll_interval_ms,
+    DEFAULT_OBSERVER_POLL_INTERVAL_MS,
 };
-use super::util::{dedup_players, normalize_poll_interval_ms, DEFAULT_OBSERVER_POLL_INTERVAL_MS};
 
 pub const PLAYBACK_EPISODE_DETECTED_EVENT: &str = "player-detection:episode-detected";
 pub const PLAYBACK_EPISODE_CLOSED_EVENT: &str = "player-detection:episode-closed";
+pub const PLAYBACK_PAUSED_EVENT: &str = "player-detection:paused";
+pub const PLAYBACK_RESUMED_EVENT: &str = "player-detection:resumed";
+pub const PLAYBACK_PROGRESS_EVENT: &str = "player-detection:progress";
+
+const PROGRESS_EVENT_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PlaybackObserverEvent {
+    Detected(AnimePlaybackDetection, TransitionReason),
+    Closed(AnimePlaybackDetection, TransitionReason),
+    Paused(AnimePlaybackDetection, PlaybackProgress),
+    Resumed(AnimePlaybackDetection, PlaybackProgress),
+    Progress(AnimePlaybackDetection, PlaybackProgress),
+}
+
+impl PlaybackObserverEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Detected(..) => PLAYBACK_EPISODE_DETECTED_EVENT,
+            Self::Closed(..) => PLAYBACK_EPISODE_CLOSED_EVENT,
+            Self::Paused(..) => PLAYBACK_PAUSED_EVENT,
+            Self::Resumed(..) => PLAYBACK_RESUMED_EVENT,
+            Self::Progress(..) => PLAYBACK_PROGRESS_EVENT,
+        }
+    }
+
+    fn payload(&self) -> PlaybackObserverEventPayload<'_> {
+        match self {
+            Self::Detected(detection, reason) | Self::Closed(detection, reason) => {
+                PlaybackObserverEventPayload::Transition {
+                    detection,
+                    reason: *reason,
+                }
+            }
+            Self::Paused(detection, progress)
+            | Self::Resumed(detection, progress)
+            | Self::Progress(detection, progress) => PlaybackObserverEventPayload::Status {
+                detection,
+                progress,
+            },
+        }
+    }
+}
+
+// Listeners receive the detection itself with the event details alongside it.
+#[derive(Clone, Serialize)]
+#[serde(untagged)]
+enum PlaybackObserverEventPayload<'a> {
+    Transition {
+        #[serde(flatten)]
+        detection: &'a AnimePlaybackDetection,
+        reason: TransitionReason,
+    },
+    Status {
+        #[serde(flatten)]
+        detection: &'a AnimePlaybackDetection,
+        progress: &'a PlaybackProgress,
+    },
+}
+
+// A change seen by the observer but not reported yet; `target` is None for a
+// close.
+struct PendingTransition {
+    target: Option<DetectionCandidate>,
+    since: Instant,
+    cycles: u32,
+}
+
+// The previous and current active episode, and the reason when the cycle
+// changed which one is active.
+type GuardTransition = (
+    Option<AnimePlaybackDetection>,
+    Option<AnimePlaybackDetection>,
+    Option<TransitionReason>,
+);
 
 struct PlaybackObserverStateData {
     active: Option<AnimePlaybackDetection>,
     last_observed: Option<AnimePlaybackDetection>,
     observed_process_id: Option<u32>,
     observed_player: Option<SupportedPlayer>,
+    candidates: Vec<DetectionCandidate>,
+    // Poll count at which each running player process was first seen.
+    process_first_seen: HashMap<u32, u64>,
+    cycle_count: u64,
+    selection: DetectionSelectionConfig,
+    stability: DetectionStabilityConfig,
+    pending: Option<PendingTransition>,
+    last_transition: Option<TransitionReason>,
+    last_progress_at: Option<Instant>,
     selected_players: Vec<SupportedPlayer>,
+    sources: DetectionSourcesConfig,
+    custom_players: Vec<CustomPlayerDefinition>,
     enabled: bool,
     poll_interval_ms: u64,
     last_error: Option<String>,
@@ -31,7 +130,17 @@ impl Default for PlaybackObserverStateData {
             last_observed: None,
             observed_process_id: None,
             observed_player: None,
+            candidates: Vec::new(),
+            process_first_seen: HashMap::new(),
+            cycle_count: 0,
+            selection: DetectionSelectionConfig::default(),
+            stability: DetectionStabilityConfig::default(),
+            pending: None,
+            last_transition: None,
+            last_progress_at: None,
             selected_players: SupportedPlayer::all(),
+            sources: DetectionSourcesConfig::default(),
+            custom_players: Vec::new(),
             enabled: false,
             poll_interval_ms: DEFAULT_OBSERVER_POLL_INTERVAL_MS,
             last_error: None,
@@ -61,14 +170,34 @@ fn apply_configuration_to_guard(
         guard.poll_interval_ms = normalize_poll_interval_ms(poll_interval_ms);
     }
 
+    if let Some(sources) = request.sources {
+        guard.sources = sources;
+    }
+
+    if let Some(custom_players) = request.custom_players {
+        guard.custom_players = custom_players;
+    }
+
+    if let Some(selection) = request.selection {
+        guard.selection = selection;
+    }
+
+    if let Some(stability) = request.stability {
+        guard.stability = stability.normalized();
+    }
+
     if !guard.enabled {
         if guard.active.is_some() {
             guard.last_observed = guard.active.clone();
+            guard.last_transition = Some(TransitionReason::ObserverDisabled);
         }
 
         guard.active = None;
         guard.observed_process_id = None;
         guard.observed_player = None;
+        guard.candidates.clear();
+        guard.process_first_seen.clear();
+        guard.pending = None;
         guard.last_error = None;
     }
 
@@ -83,10 +212,8 @@ fn apply_cycle_success_to_guard(
     guard: &mut PlaybackObserverStateData,
     runtime_config: &ObserverRuntimeConfig,
     cycle_result: &DetectionCycleResult,
-) -> Option<(
-    Option<AnimePlaybackDetection>,
-    Option<AnimePlaybackDetection>,
-)> {
+    now: Instant,
+) -> Option<GuardTransition> {
     let previous_active = guard.active.clone();
 
     if !runtime_config.enabled || !guard.enabled {
@@ -95,27 +222,99 @@ fn apply_cycle_success_to_guard(
 
     guard.last_error = None;
 
-    if guard.selected_players != runtime_config.selected_players {
+    if guard.selected_players != runtime_config.selected_players
+        || guard.sources != runtime_config.sources
+        || guard.custom_players != runtime_config.custom_players
+    {
         return None;
     }
 
-    let observed_process_is_running = runtime_config
+    guard.cycle_count += 1;
+    let cycle_count = guard.cycle_count;
+    guard
+        .process_first_seen
+        .retain(|process_id, _| cycle_result.matched_player_pids.contains(process_id));
+    for candidate in &cycle_result.detections {
+        guard
+            .process_first_seen
+            .entry(candidate.process_id)
+            .or_insert(cycle_count);
+    }
+    guard.candidates = cycle_result.detections.clone();
+
+    let observed_process_id = runtime_config
         .observed_process_id
-        .map(|process_id| cycle_result.matched_player_pids.contains(&process_id))
-        .unwrap_or(false);
+        .filter(|process_id| cycle_result.matched_player_pids.contains(process_id));
+
+    // Extras such as creditless openings are treated like unparseable media so
+    // they never replace or announce an episode.
+    let trackable_detections: Vec<&DetectionCandidate> = cycle_result
+        .detections
+        .iter()
+        .filter(|candidate| candidate.detection.kind.is_trackable())
+        .collect();
+    let selected = select_candidate(
+        &trackable_detections,
+        &guard.selection,
+        observed_process_id,
+        cycle_result.focused_process_id,
+        &guard.process_first_seen,
+    );
+
+    let target = match selected {
+        Some(candidate) => Some(candidate.clone()),
+        // The followed player is still open but showing an extra; keep the
+        // episode it was on.
+        None if observed_process_id.is_some() => {
+            guard.pending = None;
+            return Some((previous_active, guard.active.clone(), None));
+        }
+        None => None,
+    };
 
-    if let Some(observed_process_id) = runtime_config.observed_process_id {
-        if observed_process_is_running {
-            if let Some(updated_detection) = cycle_result
-                .detections
-                .iter()
-                .find(|candidate| candidate.process_id == observed_process_id)
-            {
-                guard.active = Some(updated_detection.detection.clone());
-                guard.observed_process_id = Some(updated_detection.process_id);
-                guard.observed_player = Some(updated_detection.detection.player);
+    if is_active_target(guard, target.as_ref()) {
+        guard.pending = None;
+        if let Some(candidate) = target {
+            set_active_candidate(guard, candidate);
+        }
+        return Some((previous_active, guard.active.clone(), None));
+    }
+
+    let reason = match (&target, guard.observed_process_id) {
+        (None, _) => TransitionReason::PlayerClosed,
+        (Some(_), _) if guard.active.is_none() => TransitionReason::Started,
+        (Some(candidate), Some(process_id)) if candidate.process_id == process_id => {
+            TransitionReason::EpisodeChanged
+        }
+        (Some(_), _) => TransitionReason::PlayerSwitched,
+    };
+    let (since, cycles) = match guard.pending.take() {
+        Some(pending) if is_same_target(pending.target.as_ref(), target.as_ref()) => {
+            (pending.since, pending.cycles + 1)
+        }
+        _ => (now, 1),
+    };
+    // The latest poll replaces the pending target so position and pause state
+    // are current once the change is confirmed.
+    let pending = PendingTransition {
+        target,
+        since,
+        cycles,
+    };
+
+    if !is_confirmed(&guard.stability, &pending, now) {
+        guard.pending = Some(pending);
+        return Some((previous_active, guard.active.clone(), None));
+    }
+
+    match pending.target {
+        Some(candidate) => {
+            if reason == TransitionReason::PlayerSwitched {
+                guard.last_observed = guard.active.clone();
             }
-        } else {
+            set_active_candidate(guard, candidate);
+        }
+        None => {
             if guard.active.is_some() {
                 guard.last_observed = guard.active.clone();
             }
@@ -125,19 +324,99 @@ fn apply_cycle_success_to_guard(
             guard.observed_player = None;
         }
     }
+    guard.last_transition = Some(reason);
 
-    if guard.observed_process_id.is_none() {
-        if let Some(first_detection) = cycle_result.detections.first() {
-            guard.active = Some(first_detection.detection.clone());
-            guard.observed_process_id = Some(first_detection.process_id);
-            guard.observed_player = Some(first_detection.detection.player);
-        } else {
-            guard.active = None;
-            guard.observed_player = None;
+    Some((previous_active, guard.active.clone(), Some(reason)))
+}
+
+fn is_same_target(left: Option<&DetectionCandidate>, right: Option<&DetectionCandidate>) -> bool {
+    match (left, right) {
+        (Some(left), Some(right)) => {
+            left.process_id == right.process_id && left.detection.is_same_episode(&right.detection)
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn is_active_target(
+    guard: &PlaybackObserverStateData,
+    target: Option<&DetectionCandidate>,
+) -> bool {
+    match (target, guard.active.as_ref()) {
+        (Some(target), Some(active)) => {
+            guard.observed_process_id == Some(target.process_id)
+                && active.is_same_episode(&target.detection)
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn is_confirmed(
+    stability: &DetectionStabilityConfig,
+    pending: &PendingTransition,
+    now: Instant,
+) -> bool {
+    let held_for = now.saturating_duration_since(pending.since);
+    match pending.target {
+        Some(_) => {
+            pending.cycles >= stability.confirm_cycles
+                && held_for >= Duration::from_millis(stability.confirm_ms)
         }
+        None => held_for >= Duration::from_millis(stability.close_grace_ms),
+    }
+}
+
+fn set_active_candidate(guard: &mut PlaybackObserverStateData, candidate: DetectionCandidate) {
+    let mut detection = candidate.detection;
+    // The list match is resolved once per episode, not every poll.
+    if let Some(previous) = guard
+        .active
+        .as_ref()
+        .filter(|previous| previous.is_same_episode(&detection))
+    {
+        detection.list_match = previous.list_match.clone();
     }
 
-    Some((previous_active, guard.active.clone()))
+    guard.observed_player = Some(detection.player);
+    guard.observed_process_id = Some(candidate.process_id);
+    guard.active = Some(detection);
+}
+
+// Pause, resume and progress events for the episode active after a cycle.
+// Only backends that report a position produce them.
+fn playback_status_events(
+    guard: &mut PlaybackObserverStateData,
+    previous_active: Option<&AnimePlaybackDetection>,
+    now: Instant,
+) -> Vec<PlaybackObserverEvent> {
+    let Some((active, progress)) = guard.active.as_ref().and_then(|active| {
+        PlaybackProgress::from_detection(active).map(|progress| (active.clone(), progress))
+    }) else {
+        guard.last_progress_at = None;
+        return Vec::new();
+    };
+
+    let previous_active = previous_active.filter(|previous| previous.is_same_episode(&active));
+    let progress_is_recent = previous_active.is_some()
+        && guard.last_progress_at.is_some_and(|reported_at| {
+            now.saturating_duration_since(reported_at) < PROGRESS_EVENT_INTERVAL
+        });
+    let event = match (
+        previous_active.and_then(|previous| previous.paused),
+        active.paused,
+    ) {
+        (Some(false), Some(true)) => PlaybackObserverEvent::Paused(active, progress),
+        (Some(true), Some(false)) => PlaybackObserverEvent::Resumed(active, progress),
+        // A paused player does not move, so progress is only repeated while
+        // playing.
+        _ if progress.paused || progress_is_recent => return Vec::new(),
+        _ => PlaybackObserverEvent::Progress(active, progress),
+    };
+    guard.last_progress_at = Some(now);
+
+    vec![event]
 }
 
 fn apply_cycle_error_to_guard(guard: &mut PlaybackObserverStateData, error: String) {
@@ -152,34 +431,94 @@ fn apply_cycle_error_to_guard(guard: &mut PlaybackObserverStateData, error: Stri
 struct ObserverRuntimeConfig {
     enabled: bool,
     selected_players: Vec<SupportedPlayer>,
+    sources: DetectionSourcesConfig,
+    custom_players: Vec<CustomPlayerDefinition>,
     poll_interval_ms: u64,
     observed_process_id: Option<u32>,
+    selection_policy: SelectionPolicy,
 }
 
 pub struct PlaybackObserverState {
     data: RwLock<PlaybackObserverStateData>,
     worker: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+    process_source: Arc<dyn ProcessSource>,
+    playback_source: Arc<dyn PlaybackSource>,
 }
 
 impl PlaybackObserverState {
-    pub fn new(enabled: bool, selected_players: Vec<SupportedPlayer>) -> Self {
+    pub fn new(
+        enabled: bool,
+        selected_players: Vec<SupportedPlayer>,
+        sources: DetectionSourcesConfig,
+        custom_players: Vec<CustomPlayerDefinition>,
+        selection: DetectionSelectionConfig,
+    ) -> Self {
+        Self::with_sources(
+            enabled,
+            selected_players,
+            sources,
+            custom_players,
+            selection,
+            Arc::new(SystemProcessSource),
+            Arc::new(SystemPlaybackSource),
+        )
+    }
+
+    pub(crate) fn with_sources(
+        enabled: bool,
+        selected_players: Vec<SupportedPlayer>,
+        sources: DetectionSourcesConfig,
+        custom_players: Vec<CustomPlayerDefinition>,
+        selection: DetectionSelectionConfig,
+        process_source: Arc<dyn ProcessSource>,
+        playback_source: Arc<dyn PlaybackSource>,
+    ) -> Self {
         let mut state = PlaybackObserverStateData::default();
         state.enabled = enabled;
         state.selected_players = dedup_players(selected_players);
+        state.sources = sources;
+        state.selection = selection;
+        // A hand-edited store may hold invalid definitions; skip them rather
+        // than refusing to start the observer.
+        state.custom_players = custom_players
+            .into_iter()
+            .filter_map(|definition| {
+                normalize_custom_player(definition)
+                    .map_err(|error| eprintln!("skipping custom player: {error}"))
+                    .ok()
+            })
+            .collect();
 
         Self {
             data: RwLock::new(state),
             worker: Mutex::new(None),
+            process_source,
+            playback_source,
         }
     }
 
+    pub fn with_stability(mut self, stability: DetectionStabilityConfig) -> Self {
+        self.data.get_mut().stability = stability.normalized();
+        self
+    }
+
     fn snapshot_from_guard(guard: &PlaybackObserverStateData) -> PlaybackObserverSnapshot {
         PlaybackObserverSnapshot {
             active: guard.active.clone(),
             last_observed: guard.last_observed.clone(),
             observed_process_id: guard.observed_process_id,
             observed_player: guard.observed_player,
+            candidates: guard.candidates.clone(),
+            selection: guard.selection.clone(),
+            stability: guard.stability.clone(),
+            last_transition: guard.last_transition,
+            progress: guard
+                .active
+                .as_ref()
+                .and_then(PlaybackProgress::from_detection),
             selected_players: guard.selected_players.clone(),
+            sources: guard.sources.clone(),
+            custom_players: guard.custom_players.clone(),
             enabled: guard.enabled,
             poll_interval_ms: guard.poll_interval_ms,
             last_error: guard.last_error.clone(),
@@ -192,14 +531,36 @@ impl PlaybackObserverState {
         Self::snapshot_from_guard(&guard)
     }
 
+    pub(crate) async fn active(&self) -> Option<AnimePlaybackDetection> {
+        self.data.read().await.active.clone()
+    }
+
+    pub(crate) async fn attach_list_match(
+        &self,
+        detection: &AnimePlaybackDetection,
+        list_match: Option<ListEntryMatch>,
+    ) {
+        let mut guard = self.data.write().await;
+        if let Some(active) = guard
+            .active
+            .as_mut()
+            .filter(|active| active.is_same_episode(detection))
+        {
+            active.list_match = list_match;
+        }
+    }
+
     async fn read_runtime_config(&self) -> ObserverRuntimeConfig {
         let guard = self.data.read().await;
 
         ObserverRuntimeConfig {
             enabled: guard.enabled,
             selected_players: guard.selected_players.clone(),
+            sources: guard.sources.clone(),
+            custom_players: guard.custom_players.clone(),
             poll_interval_ms: guard.poll_interval_ms,
             observed_process_id: guard.observed_process_id,
+            selection_policy: guard.selection.policy,
         }
     }
 
@@ -213,7 +574,14 @@ impl PlaybackObserverState {
             apply_configuration_to_guard(&mut guard, request)
         };
 
-        emit_playback_observer_events(&app, previous_active, current_active);
+        emit_playback_observer_events(
+            &app,
+            playback_observer_events(
+                previous_active,
+                current_active,
+                TransitionReason::ObserverDisabled,
+            ),
+        );
 
         if snapshot.enabled {
             self.start_worker(app).await;
@@ -247,20 +615,74 @@ impl PlaybackObserverState {
         }
     }
 
+    // Runs one detection cycle against the injected sources and returns the
+    // events it produced along with how long to wait before the next one.
+    pub(crate) async fn run_cycle(&self) -> (Vec<PlaybackObserverEvent>, u64) {
+        let runtime_config = self.read_runtime_config().await;
+        let poll_interval_ms = runtime_config.poll_interval_ms;
+
+        // A cycle talks to players over sockets, D-Bus and `/proc`, and asking
+        // for the focused window can spawn helper processes, so all of it runs
+        // off the async workers. The focus is only looked up when the policy
+        // uses the answer.
+        let process_source = Arc::clone(&self.process_source);
+        let playback_source = Arc::clone(&self.playback_source);
+        let selected_players = runtime_config.selected_players.clone();
+        let custom_players = runtime_config.custom_players.clone();
+        let sources = runtime_config.sources.clone();
+        let wants_focus = runtime_config.selection_policy == SelectionPolicy::FocusedWindow;
+        let cycle_result = tauri::async_runtime::spawn_blocking(move || {
+            let focused_process_id = if wants_focus {
+                process_source.focused_process_id()
+            } else {
+                None
+            };
+
+            collect_detection_cycle_result(
+                process_source.as_ref(),
+                playback_source.as_ref(),
+                &selected_players,
+                &custom_players,
+                &sources,
+            )
+            .map(|mut result| {
+                result.focused_process_id = focused_process_id;
+                result
+            })
+        })
+        .await
+        .unwrap_or_else(|err| Err(format!("Detection cycle failed: {err}")));
+        let events = match cycle_result {
+            Ok(result) => self.apply_cycle_success(runtime_config, result).await,
+            Err(error) => {
+                self.apply_cycle_error(error).await;
+                Vec::new()
+            }
+        };
+
+        (events, poll_interval_ms)
+    }
+
     async fn apply_cycle_success(
         &self,
-        app: &tauri::AppHandle,
         runtime_config: ObserverRuntimeConfig,
         cycle_result: DetectionCycleResult,
-    ) {
-        let transitions = {
-            let mut guard = self.data.write().await;
-            apply_cycle_success_to_guard(&mut guard, &runtime_config, &cycle_result)
+    ) -> Vec<PlaybackObserverEvent> {
+        let now = Instant::now();
+        let mut guard = self.data.write().await;
+        let Some((previous_active, current_active, reason)) =
+            apply_cycle_success_to_guard(&mut guard, &runtime_config, &cycle_result, now)
+        else {
+            return Vec::new();
         };
+        let status_events = playback_status_events(&mut guard, previous_active.as_ref(), now);
 
-        if let Some((previous_active, current_active)) = transitions {
-            emit_playback_observer_events(app, previous_active, current_active);
-        }
+        let mut events = match reason {
+            Some(reason) => playback_observer_events(previous_active, current_active, reason),
+            None => Vec::new(),
+        };
+        events.extend(status_events);
+        events
     }
 
     async fn apply_cycle_error(&self, error: String) {
@@ -269,51 +691,58 @@ impl PlaybackObserverState {
     }
 }
 
-fn emit_playback_observer_events(
-    app: &tauri::AppHandle,
+fn playback_observer_events(
     previous_active: Option<AnimePlaybackDetection>,
     current_active: Option<AnimePlaybackDetection>,
-) {
-    if previous_active == current_active {
-        return;
+    reason: TransitionReason,
+) -> Vec<PlaybackObserverEvent> {
+    match (previous_active, current_active) {
+        (Some(previous), Some(current)) if previous.is_same_episode(&current) => Vec::new(),
+        (_, Some(current)) => vec![PlaybackObserverEvent::Detected(current, reason)],
+        (Some(previous), None) => vec![PlaybackObserverEvent::Closed(previous, reason)],
+        (None, None) => Vec::new(),
     }
+}
 
-    if let Some(detection) = current_active.as_ref() {
-        if let Err(error) = app.emit(PLAYBACK_EPISODE_DETECTED_EVENT, detection) {
-            eprintln!("failed to emit playback episode detected event: {error}");
+fn emit_playback_observer_events(app: &tauri::AppHandle, events: Vec<PlaybackObserverEvent>) {
+    for event in events {
+        if let Err(error) = app.emit(event.name(), event.payload()) {
+            eprintln!("failed to emit {} event: {error}", event.name());
         }
     }
+}
+
+async fn attach_list_matches(
+    app: &tauri::AppHandle,
+    observer: &PlaybackObserverState,
+    events: &mut [PlaybackObserverEvent],
+) {
+    for event in events {
+        let PlaybackObserverEvent::Detected(detection, _) = event else {
+            continue;
+        };
 
-    if current_active.is_none() {
-        if let Some(previous) = previous_active {
-            if let Err(error) = app.emit(PLAYBACK_EPISODE_CLOSED_EVENT, previous) {
-                eprintln!("failed to emit playback episode closed event: {error}");
+        match resolve_list_match(app, detection).await {
+            Ok(list_match) => {
+                detection.list_match = list_match.clone();
+                observer.attach_list_match(detection, list_match).await;
             }
+            Err(error) => eprintln!("failed to match detected episode to a list entry: {error}"),
         }
     }
 }
 
 async fn run_playback_observer_loop(app: tauri::AppHandle) {
     loop {
-        let runtime_config = app
-            .state::<PlaybackObserverState>()
-            .read_runtime_config()
+        let observer = app.state::<PlaybackObserverState>();
+        let (mut events, poll_interval_ms) = observer.run_cycle().await;
+        attach_list_matches(&app, &observer, &mut events).await;
+        emit_playback_observer_events(&app, events);
+
+        let active = observer.active().await;
+        app.state::<ScrobblerState>()
+            .observe(&app, active.as_ref())
             .await;
-        let poll_interval_ms = runtime_config.poll_interval_ms;
-
-        let cycle_result = collect_detection_cycle_result(&runtime_config.selected_players);
-        match cycle_result {
-            Ok(result) => {
-                app.state::<PlaybackObserverState>()
-                    .apply_cycle_success(&app, runtime_config, result)
-                    .await;
-            }
-            Err(error) => {
-                app.state::<PlaybackObserverState>()
-                    .apply_cycle_error(error)
-                    .await;
-            }
-        }
 
         tokio::time::sleep(Duration::from_millis(poll_interval_ms)).await;
     }
@@ -336,9 +765,13 @@ pub async fn get_playback_observer_state(
 
 #[tauri::command]
 pub async fn configure_playback_observer(
-    request: ConfigurePlaybackObserverRequest,
+    mut request: ConfigurePlaybackObserverRequest,
     app: tauri::AppHandle,
 ) -> Result<PlaybackObserverSnapshot, String> {
+    if let Some(custom_players) = request.custom_players.take() {
+        request.custom_players = Some(normalize_custom_players(custom_players)?);
+    }
+
     Ok(app
         .state::<PlaybackObserverState>()
         .configure(app.clone(), request)
@@ -349,17 +782,20 @@ pub async fn configure_playback_observer(
 mod tests {
     use std::collections::HashSet;
 
-    use super::super::detector::DetectionCandidate;
+    use tokio::runtime::Runtime;
+
+    use super::super::processes::ProcessSnapshot;
+    use super::super::sources::fakes::{process, ScriptedPlaybackSource, ScriptedProcessSource};
+    use super::super::types::{
+        EpisodeKind, ListMatchSource, MediaSourceKind, PlayerPlaybackStatus,
+    };
     use super::*;
 
     fn detection(process_id: u32, player: SupportedPlayer, title: &str) -> DetectionCandidate {
         DetectionCandidate {
             process_id,
-            detection: AnimePlaybackDetection {
-                player,
-                anime_title: title.to_string(),
-                episode: Some(1),
-            },
+            source: MediaSourceKind::CommandLine,
+            detection: AnimePlaybackDetection::new(player, title.to_string(), Some(1)),
         }
     }
 
@@ -371,26 +807,32 @@ mod tests {
         ObserverRuntimeConfig {
             enabled,
             selected_players,
+            sources: DetectionSourcesConfig::default(),
+            custom_players: Vec::new(),
             poll_interval_ms: DEFAULT_OBSERVER_POLL_INTERVAL_MS,
             observed_process_id,
+            selection_policy: SelectionPolicy::MostRecent,
         }
     }
 
     #[test]
     fn configure_guard_deduplicates_players_and_clears_state_when_disabled() {
         let mut guard = PlaybackObserverStateData {
-            active: Some(AnimePlaybackDetection {
-                player: SupportedPlayer::Mpv,
-                anime_title: "Frieren".to_string(),
-                episode: Some(12),
-            }),
+            active: Some(AnimePlaybackDetection::new(
+                SupportedPlayer::Mpv,
+                "Frieren".to_string(),
+                Some(12),
+            )),
             last_observed: None,
             observed_process_id: Some(99),
             observed_player: Some(SupportedPlayer::Mpv),
             selected_players: SupportedPlayer::all(),
+            sources: DetectionSourcesConfig::default(),
+            custom_players: Vec::new(),
             enabled: true,
             poll_interval_ms: DEFAULT_OBSERVER_POLL_INTERVAL_MS,
             last_error: Some("old error".to_string()),
+            ..Default::default()
         };
 
         let (snapshot, previous_active, current_active) = apply_configuration_to_guard(
@@ -403,10 +845,24 @@ mod tests {
                     SupportedPlayer::MpcBe,
                 ]),
                 poll_interval_ms: Some(10),
+                sources: None,
+                custom_players: None,
+                selection: None,
+                stability: Some(DetectionStabilityConfig {
+                    confirm_cycles: 0,
+                    confirm_ms: 0,
+                    close_grace_ms: 3_000,
+                }),
             },
         );
 
         assert!(previous_active.is_some());
+        assert_eq!(
+            snapshot.last_transition,
+            Some(TransitionReason::ObserverDisabled)
+        );
+        assert_eq!(snapshot.stability.confirm_cycles, 1);
+        assert_eq!(snapshot.stability.close_grace_ms, 3_000);
         assert!(current_active.is_none());
         assert!(!snapshot.enabled);
         assert_eq!(
@@ -435,12 +891,14 @@ mod tests {
         let cycle_result = DetectionCycleResult {
             detections: vec![detection(7, SupportedPlayer::Mpv, "Frieren")],
             matched_player_pids: HashSet::from([7]),
+            ..Default::default()
         };
 
         let transitions = apply_cycle_success_to_guard(
             &mut guard,
             &runtime_config(true, vec![SupportedPlayer::Mpv], None),
             &cycle_result,
+            Instant::now(),
         )
         .expect("transition should occur");
 
@@ -453,31 +911,65 @@ mod tests {
         assert_eq!(guard.observed_player, Some(SupportedPlayer::Mpv));
     }
 
+    #[test]
+    fn apply_cycle_success_ignores_creditless_openings_and_previews() {
+        let mut guard = PlaybackObserverStateData {
+            enabled: true,
+            selected_players: vec![SupportedPlayer::Mpv],
+            ..Default::default()
+        };
+        let mut opening = detection(7, SupportedPlayer::Mpv, "Frieren");
+        opening.detection = opening.detection.with_kind(EpisodeKind::OpeningEnding);
+        let mut preview = detection(8, SupportedPlayer::Mpv, "Frieren");
+        preview.detection = preview.detection.with_kind(EpisodeKind::Preview);
+        let cycle_result = DetectionCycleResult {
+            detections: vec![opening, preview],
+            matched_player_pids: HashSet::from([7, 8]),
+            ..Default::default()
+        };
+
+        let transitions = apply_cycle_success_to_guard(
+            &mut guard,
+            &runtime_config(true, vec![SupportedPlayer::Mpv], None),
+            &cycle_result,
+            Instant::now(),
+        )
+        .expect("cycle should apply");
+
+        assert!(transitions.1.is_none());
+        assert_eq!(guard.observed_process_id, None);
+    }
+
     #[test]
     fn apply_cycle_success_updates_existing_observed_process_and_clears_errors() {
         let mut guard = PlaybackObserverStateData {
-            active: Some(AnimePlaybackDetection {
-                player: SupportedPlayer::Mpv,
-                anime_title: "Old".to_string(),
-                episode: Some(1),
-            }),
+            active: Some(AnimePlaybackDetection::new(
+                SupportedPlayer::Mpv,
+                "Old".to_string(),
+                Some(1),
+            )),
             observed_process_id: Some(10),
             observed_player: Some(SupportedPlayer::Mpv),
             selected_players: vec![SupportedPlayer::Mpv],
+            sources: DetectionSourcesConfig::default(),
+            custom_players: Vec::new(),
             enabled: true,
             poll_interval_ms: DEFAULT_OBSERVER_POLL_INTERVAL_MS,
             last_error: Some("boom".to_string()),
             last_observed: None,
+            ..Default::default()
         };
         let cycle_result = DetectionCycleResult {
             detections: vec![detection(10, SupportedPlayer::Mpv, "New")],
             matched_player_pids: HashSet::from([10]),
+            ..Default::default()
         };
 
         let transitions = apply_cycle_success_to_guard(
             &mut guard,
             &runtime_config(true, vec![SupportedPlayer::Mpv], Some(10)),
             &cycle_result,
+            Instant::now(),
         )
         .expect("transition should occur");
 
@@ -493,31 +985,160 @@ mod tests {
         assert_eq!(guard.observed_process_id, Some(10));
     }
 
+    #[test]
+    fn apply_cycle_success_switches_to_the_most_recently_started_player() {
+        let mut guard = PlaybackObserverStateData {
+            enabled: true,
+            selected_players: vec![SupportedPlayer::Mpv],
+            selection: DetectionSelectionConfig {
+                policy: SelectionPolicy::MostRecent,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let config = |observed_process_id| {
+            runtime_config(true, vec![SupportedPlayer::Mpv], observed_process_id)
+        };
+
+        apply_cycle_success_to_guard(
+            &mut guard,
+            &config(None),
+            &DetectionCycleResult {
+                detections: vec![detection(50, SupportedPlayer::Mpv, "Frieren")],
+                matched_player_pids: HashSet::from([50]),
+                ..Default::default()
+            },
+            Instant::now(),
+        )
+        .expect("cycle should apply");
+        assert_eq!(guard.observed_process_id, Some(50));
+
+        // A lower pid started later still counts as the newer player.
+        let transitions = apply_cycle_success_to_guard(
+            &mut guard,
+            &config(Some(50)),
+            &DetectionCycleResult {
+                detections: vec![
+                    detection(12, SupportedPlayer::Mpv, "Dandadan"),
+                    detection(50, SupportedPlayer::Mpv, "Frieren"),
+                ],
+                matched_player_pids: HashSet::from([12, 50]),
+                ..Default::default()
+            },
+            Instant::now(),
+        )
+        .expect("cycle should apply");
+
+        assert_eq!(
+            transitions.1.as_ref().map(|item| item.anime_title.as_str()),
+            Some("Dandadan")
+        );
+        assert_eq!(guard.observed_process_id, Some(12));
+        assert_eq!(guard.candidates.len(), 2);
+        assert_eq!(
+            guard
+                .last_observed
+                .as_ref()
+                .map(|item| item.anime_title.as_str()),
+            Some("Frieren")
+        );
+    }
+
+    #[test]
+    fn apply_cycle_success_keeps_list_match_while_the_episode_continues() {
+        let list_match = ListEntryMatch {
+            provider_id: "anilist".to_string(),
+            media_id: 52991,
+            entry_id: Some(900),
+            title: "Sousou no Frieren".to_string(),
+            total_episodes: 28,
+            episode: Some(1),
+            confidence: 1.0,
+            source: ListMatchSource::List,
+        };
+        let mut matched =
+            AnimePlaybackDetection::new(SupportedPlayer::Mpv, "Frieren".to_string(), Some(1));
+        matched.list_match = Some(list_match.clone());
+        let mut guard = PlaybackObserverStateData {
+            active: Some(matched),
+            observed_process_id: Some(10),
+            observed_player: Some(SupportedPlayer::Mpv),
+            selected_players: vec![SupportedPlayer::Mpv],
+            enabled: true,
+            ..Default::default()
+        };
+
+        apply_cycle_success_to_guard(
+            &mut guard,
+            &runtime_config(true, vec![SupportedPlayer::Mpv], Some(10)),
+            &DetectionCycleResult {
+                detections: vec![detection(10, SupportedPlayer::Mpv, "Frieren")],
+                matched_player_pids: HashSet::from([10]),
+                ..Default::default()
+            },
+            Instant::now(),
+        )
+        .expect("cycle should apply");
+        assert_eq!(
+            guard
+                .active
+                .as_ref()
+                .and_then(|active| active.list_match.as_ref()),
+            Some(&list_match)
+        );
+
+        let mut next_episode = detection(10, SupportedPlayer::Mpv, "Frieren");
+        next_episode.detection.episode = Some(2);
+        apply_cycle_success_to_guard(
+            &mut guard,
+            &runtime_config(true, vec![SupportedPlayer::Mpv], Some(10)),
+            &DetectionCycleResult {
+                detections: vec![next_episode],
+                matched_player_pids: HashSet::from([10]),
+                ..Default::default()
+            },
+            Instant::now(),
+        )
+        .expect("cycle should apply");
+        assert_eq!(
+            guard
+                .active
+                .as_ref()
+                .and_then(|active| active.list_match.as_ref()),
+            None
+        );
+    }
+
     #[test]
     fn apply_cycle_success_closes_missing_process_and_preserves_last_observed() {
         let mut guard = PlaybackObserverStateData {
-            active: Some(AnimePlaybackDetection {
-                player: SupportedPlayer::Mpv,
-                anime_title: "Frieren".to_string(),
-                episode: Some(12),
-            }),
+            active: Some(AnimePlaybackDetection::new(
+                SupportedPlayer::Mpv,
+                "Frieren".to_string(),
+                Some(12),
+            )),
             observed_process_id: Some(10),
             observed_player: Some(SupportedPlayer::Mpv),
             selected_players: vec![SupportedPlayer::Mpv],
+            sources: DetectionSourcesConfig::default(),
+            custom_players: Vec::new(),
             enabled: true,
             poll_interval_ms: DEFAULT_OBSERVER_POLL_INTERVAL_MS,
             last_error: None,
             last_observed: None,
+            ..Default::default()
         };
         let cycle_result = DetectionCycleResult {
             detections: Vec::new(),
             matched_player_pids: HashSet::new(),
+            ..Default::default()
         };
 
         let transitions = apply_cycle_success_to_guard(
             &mut guard,
             &runtime_config(true, vec![SupportedPlayer::Mpv], Some(10)),
             &cycle_result,
+            Instant::now(),
         )
         .expect("transition should occur");
 
@@ -548,11 +1169,13 @@ mod tests {
         let cycle_result = DetectionCycleResult {
             detections: vec![detection(5, SupportedPlayer::Mpv, "Frieren")],
             matched_player_pids: HashSet::from([5]),
+            ..Default::default()
         };
         assert!(apply_cycle_success_to_guard(
             &mut stale_guard,
             &runtime_config(true, vec![SupportedPlayer::MpcBe], None),
             &cycle_result,
+            Instant::now(),
         )
         .is_none());
         assert!(stale_guard.last_error.is_none());
@@ -566,10 +1189,174 @@ mod tests {
             &mut disabled_guard,
             &runtime_config(true, vec![SupportedPlayer::Mpv], None),
             &cycle_result,
+            Instant::now(),
         )
         .is_none());
     }
 
+    #[test]
+    fn apply_cycle_success_confirms_detections_that_stay_stable() {
+        let mut guard = PlaybackObserverStateData {
+            enabled: true,
+            selected_players: vec![SupportedPlayer::Mpv],
+            stability: DetectionStabilityConfig {
+                confirm_cycles: 2,
+                confirm_ms: 1_000,
+                close_grace_ms: 0,
+            },
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let mut poll = |title: &str, elapsed_ms: u64| {
+            apply_cycle_success_to_guard(
+                &mut guard,
+                &runtime_config(true, vec![SupportedPlayer::Mpv], None),
+                &DetectionCycleResult {
+                    detections: vec![detection(7, SupportedPlayer::Mpv, title)],
+                    matched_player_pids: HashSet::from([7]),
+                    ..Default::default()
+                },
+                start + Duration::from_millis(elapsed_ms),
+            )
+            .expect("cycle should apply")
+        };
+
+        // Skipping past the wrong file restarts the window.
+        assert_eq!(poll("Dandadan", 0).2, None);
+        assert_eq!(poll("Frieren", 500).2, None);
+        assert_eq!(poll("Frieren", 1_000).2, None);
+        let transitions = poll("Frieren", 1_500);
+
+        assert_eq!(transitions.2, Some(TransitionReason::Started));
+        assert_eq!(
+            transitions.1.as_ref().map(|item| item.anime_title.as_str()),
+            Some("Frieren")
+        );
+        assert_eq!(guard.last_transition, Some(TransitionReason::Started));
+    }
+
+    #[test]
+    fn apply_cycle_success_waits_out_the_close_grace_period() {
+        let mut guard = PlaybackObserverStateData {
+            active: Some(AnimePlaybackDetection::new(
+                SupportedPlayer::Mpv,
+                "Frieren".to_string(),
+                Some(1),
+            )),
+            observed_process_id: Some(10),
+            observed_player: Some(SupportedPlayer::Mpv),
+            selected_players: vec![SupportedPlayer::Mpv],
+            enabled: true,
+            stability: DetectionStabilityConfig {
+                close_grace_ms: 2_000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let mut poll = |detections: Vec<DetectionCandidate>, elapsed_ms: u64| {
+            let matched_player_pids = detections
+                .iter()
+                .map(|candidate| candidate.process_id)
+                .collect();
+            apply_cycle_success_to_guard(
+                &mut guard,
+                &runtime_config(true, vec![SupportedPlayer::Mpv], Some(10)),
+                &DetectionCycleResult {
+                    detections,
+                    matched_player_pids,
+                    ..Default::default()
+                },
+                start + Duration::from_millis(elapsed_ms),
+            )
+            .expect("cycle should apply")
+        };
+
+        // Reopening the file inside the grace period keeps the episode open.
+        assert_eq!(poll(Vec::new(), 0).2, None);
+        assert_eq!(
+            poll(vec![detection(10, SupportedPlayer::Mpv, "Frieren")], 1_000).2,
+            None
+        );
+        assert_eq!(poll(Vec::new(), 2_000).2, None);
+        assert_eq!(poll(Vec::new(), 3_000).2, None);
+        let transitions = poll(Vec::new(), 4_000);
+
+        assert_eq!(transitions.2, Some(TransitionReason::PlayerClosed));
+        assert!(transitions.1.is_none());
+        assert_eq!(
+            guard
+                .last_observed
+                .as_ref()
+                .map(|item| item.anime_title.as_str()),
+            Some("Frieren")
+        );
+    }
+
+    #[test]
+    fn playback_status_events_report_pauses_and_throttle_progress() {
+        let playing = |position_seconds: f64, paused: bool| {
+            let mut detection =
+                AnimePlaybackDetection::new(SupportedPlayer::Mpv, "Frieren".to_string(), Some(1));
+            detection.position_seconds = Some(position_seconds);
+            detection.duration_seconds = Some(1_400.0);
+            detection.paused = Some(paused);
+            detection
+        };
+        let mut guard = PlaybackObserverStateData {
+            enabled: true,
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let mut poll = |previous: Option<AnimePlaybackDetection>,
+                        current: AnimePlaybackDetection,
+                        elapsed_secs: u64| {
+            guard.active = Some(current);
+            playback_status_events(
+                &mut guard,
+                previous.as_ref(),
+                start + Duration::from_secs(elapsed_secs),
+            )
+            .iter()
+            .map(|event| event.name())
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            poll(None, playing(700.0, false), 0),
+            vec![PLAYBACK_PROGRESS_EVENT]
+        );
+        assert!(poll(Some(playing(700.0, false)), playing(702.0, false), 2).is_empty());
+        assert_eq!(
+            poll(Some(playing(702.0, false)), playing(704.0, true), 4),
+            vec![PLAYBACK_PAUSED_EVENT]
+        );
+        assert!(poll(Some(playing(704.0, true)), playing(704.0, true), 30).is_empty());
+        assert_eq!(
+            poll(Some(playing(704.0, true)), playing(704.0, false), 32),
+            vec![PLAYBACK_RESUMED_EVENT]
+        );
+        assert!(poll(Some(playing(704.0, false)), playing(712.0, false), 40).is_empty());
+        assert_eq!(
+            poll(Some(playing(712.0, false)), playing(714.0, false), 42),
+            vec![PLAYBACK_PROGRESS_EVENT]
+        );
+
+        let mut without_position = playing(0.0, false);
+        without_position.position_seconds = None;
+        assert!(poll(None, without_position, 44).is_empty());
+
+        let progress = PlaybackProgress::from_detection(&playing(700.0, true))
+            .expect("position should be reported");
+        let payload = serde_json::to_value(
+            PlaybackObserverEvent::Paused(playing(700.0, true), progress).payload(),
+        )
+        .expect("payload should serialize");
+        assert_eq!(payload["animeTitle"], "Frieren");
+        assert_eq!(payload["progress"]["percent"], 50.0);
+        assert_eq!(payload["progress"]["paused"], true);
+    }
+
     #[test]
     fn apply_cycle_error_only_updates_enabled_observers() {
         let mut enabled_guard = PlaybackObserverStateData {
@@ -586,4 +1373,161 @@ mod tests {
         apply_cycle_error_to_guard(&mut disabled_guard, "boom".to_string());
         assert!(disabled_guard.last_error.is_none());
     }
+
+    fn scripted_observer(
+        timeline: Vec<Result<Vec<ProcessSnapshot>, String>>,
+        playback_source: ScriptedPlaybackSource,
+    ) -> PlaybackObserverState {
+        PlaybackObserverState::with_sources(
+            true,
+            vec![SupportedPlayer::Mpv],
+            DetectionSourcesConfig::default(),
+            Vec::new(),
+            DetectionSelectionConfig::default(),
+            Arc::new(ScriptedProcessSource::new(timeline)),
+            Arc::new(playback_source),
+        )
+    }
+
+    #[test]
+    fn run_cycle_replays_process_timeline_as_detected_and_closed_events() {
+        let frieren = |episode: &str| {
+            process(
+                42,
+                &[
+                    "mpv",
+                    &format!("/anime/[SubsPlease] Sousou no Frieren - {episode} (1080p).mkv"),
+                ],
+            )
+        };
+        let state = scripted_observer(
+            vec![
+                Ok(Vec::new()),
+                Ok(vec![frieren("01")]),
+                Ok(vec![frieren("01")]),
+                Err("Failed to list running processes: busy".to_string()),
+                Ok(vec![frieren("02")]),
+                Ok(Vec::new()),
+            ],
+            ScriptedPlaybackSource::default(),
+        );
+        let runtime = Runtime::new().expect("runtime should build");
+
+        let mut events = Vec::new();
+        for _ in 0..4 {
+            events.extend(runtime.block_on(state.run_cycle()).0);
+        }
+        assert_eq!(
+            runtime.block_on(state.snapshot()).last_error.as_deref(),
+            Some("Failed to list running processes: busy")
+        );
+        for _ in 0..2 {
+            events.extend(runtime.block_on(state.run_cycle()).0);
+        }
+
+        let episode = |number| {
+            AnimePlaybackDetection::new(
+                SupportedPlayer::Mpv,
+                "Sousou no Frieren".to_string(),
+                Some(number),
+            )
+        };
+        assert_eq!(
+            events,
+            vec![
+                PlaybackObserverEvent::Detected(episode(1), TransitionReason::Started),
+                PlaybackObserverEvent::Detected(episode(2), TransitionReason::EpisodeChanged),
+                PlaybackObserverEvent::Closed(episode(2), TransitionReason::PlayerClosed),
+            ]
+        );
+
+        let snapshot = runtime.block_on(state.snapshot());
+        assert_eq!(snapshot.active, None);
+        assert_eq!(snapshot.last_observed, Some(episode(2)));
+        assert_eq!(snapshot.last_error, None);
+    }
+
+    #[test]
+    fn run_cycle_follows_the_focused_player_and_exposes_every_candidate() {
+        let state = PlaybackObserverState::with_sources(
+            true,
+            vec![SupportedPlayer::Mpv],
+            DetectionSourcesConfig::default(),
+            Vec::new(),
+            DetectionSelectionConfig {
+                policy: SelectionPolicy::FocusedWindow,
+                ..Default::default()
+            },
+            Arc::new(
+                ScriptedProcessSource::new(vec![Ok(vec![
+                    process(10, &["mpv", "/anime/Sousou no Frieren - 03.mkv"]),
+                    process(11, &["mpv", "/anime/Dandadan - 05.mkv"]),
+                ])])
+                .with_focused_process(10),
+            ),
+            Arc::new(ScriptedPlaybackSource::default().with_status(
+                10,
+                PlayerPlaybackStatus {
+                    paused: Some(true),
+                    ..Default::default()
+                },
+            )),
+        );
+        let runtime = Runtime::new().expect("runtime should build");
+
+        runtime.block_on(state.run_cycle());
+        let snapshot = runtime.block_on(state.snapshot());
+
+        assert_eq!(snapshot.observed_process_id, Some(10));
+        assert_eq!(
+            snapshot
+                .active
+                .as_ref()
+                .map(|active| active.anime_title.as_str()),
+            Some("Sousou no Frieren")
+        );
+        assert_eq!(
+            snapshot
+                .candidates
+                .iter()
+                .map(|candidate| (candidate.process_id, candidate.source))
+                .collect::<Vec<_>>(),
+            vec![
+                (10, MediaSourceKind::CommandLine),
+                (11, MediaSourceKind::CommandLine)
+            ]
+        );
+    }
+
+    #[test]
+    fn run_cycle_prefers_media_reported_by_playback_source() {
+        let state = scripted_observer(
+            vec![Ok(vec![process(7, &["mpv", "--idle"])])],
+            ScriptedPlaybackSource::default().with_status(
+                7,
+                PlayerPlaybackStatus {
+                    media_path: Some("/anime/Dandadan - 05.mkv".to_string()),
+                    paused: Some(true),
+                    ..Default::default()
+                },
+            ),
+        );
+        let runtime = Runtime::new().expect("runtime should build");
+
+        let (events, poll_interval_ms) = runtime.block_on(state.run_cycle());
+
+        assert_eq!(poll_interval_ms, DEFAULT_OBSERVER_POLL_INTERVAL_MS);
+        let [PlaybackObserverEvent::Detected(detection, TransitionReason::Started)] =
+            events.as_slice()
+        else {
+            panic!("expected a single detected event, got {events:?}");
+        };
+        assert_eq!(detection.anime_title, "Dandadan");
+        assert_eq!(detection.episode, Some(5));
+        assert_eq!(detection.paused, Some(true));
+        assert_eq!(
+            detection.media_path.as_deref(),
+            Some("/anime/Dandadan - 05.mkv")
+        );
+    }
 }
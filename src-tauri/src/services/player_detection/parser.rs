@@ -1,11 +1,19 @@
 use regex::Regex;
+use serde::Serialize;
 use std::path::Path;
 use std::sync::OnceLock;
 
+use super::tokenizer::{
+    classify_words, split_enclosures, split_glued_keywords, Keyword, KeywordKind, Token, Word,
+};
+use super::types::EpisodeKind;
+
 const VIDEO_EXTENSIONS: &[&str] = &[
     "mkv", "mp4", "avi", "mov", "wmv", "flv", "webm", "m4v", "ts", "m2ts", "mpg", "mpeg", "ogm",
 ];
 
+const METADATA_MARKER: &str = " | ";
+
 // Numeric tokens that commonly represent video resolution, not episode number.
 // This is used by fallback numeric parsing to avoid false positives.
 // Keep this list updated as new distributions become common.
@@ -13,50 +21,435 @@ const NUMBER_TOKENS_TO_IGNORE: &[u32] = &[
     360, 480, 540, 576, 720, 1080, 1440, 2160, 2880, 3840, 4096, 4320, 5120, 7680,
 ];
 
-#[derive(Debug, Clone)]
-pub(crate) struct ParsedAnime {
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedRelease {
     pub anime_title: String,
     pub episode: Option<u32>,
+    pub episode_end: Option<u32>,
+    pub kind: EpisodeKind,
+    pub season: Option<u32>,
+    pub part: Option<u32>,
+    pub version: Option<u32>,
+    pub episode_title: Option<String>,
+    pub release_group: Option<String>,
+    pub resolution: Option<String>,
+    pub video_codecs: Vec<String>,
+    pub audio_codecs: Vec<String>,
+    pub source: Option<String>,
+    pub checksum: Option<String>,
+    pub languages: Vec<String>,
+    pub year: Option<u32>,
+}
+
+impl ParsedRelease {
+    fn record_keyword(&mut self, keyword: &Keyword) {
+        let text = keyword.text.clone();
+        match keyword.kind {
+            KeywordKind::Resolution => {
+                self.resolution.get_or_insert(text);
+            }
+            KeywordKind::Source => {
+                self.source.get_or_insert(text);
+            }
+            KeywordKind::VideoCodec => push_unique(&mut self.video_codecs, text),
+            KeywordKind::AudioCodec => push_unique(&mut self.audio_codecs, text),
+            KeywordKind::Language => push_unique(&mut self.languages, text),
+            KeywordKind::Version => {
+                if self.version.is_none() {
+                    self.version = text[1..].parse::<u32>().ok();
+                }
+            }
+            KeywordKind::ReleaseInfo => {}
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn parse_release_name(name: String) -> Result<Option<ParsedRelease>, String> {
+    let trimmed = name.trim().trim_matches('"').trim_matches('\'').trim();
+    if is_url_source(trimmed) || has_video_extension(trimmed) {
+        Ok(parse_anime_from_source(trimmed))
+    } else {
+        Ok(parse_anime_from_title(trimmed))
+    }
 }
 
-pub(crate) fn parse_anime_from_source(source: &str) -> Option<ParsedAnime> {
+pub(crate) fn parse_anime_from_source(source: &str) -> Option<ParsedRelease> {
     let raw = extract_source_title(source)?;
-    let normalized_title = normalize_title_tokens(&raw);
-    if normalized_title.is_empty() {
+    parse_release(&raw)
+}
+
+// Players that only expose a display title (MPRIS browsers, for example) must
+// not go through path handling, which would treat `Dr. Stone` as a file stem.
+pub(crate) fn parse_anime_from_title(title: &str) -> Option<ParsedRelease> {
+    let trimmed = title.trim();
+    if trimmed.is_empty() {
         return None;
     }
 
-    let (episode, title_without_episode) = extract_episode_from_title(&normalized_title);
+    parse_release(trimmed)
+}
 
-    let mut cleaned_title = leading_group_regex()
-        .replace_all(&title_without_episode, " ")
-        .to_string();
-    cleaned_title = bracket_group_regex()
-        .replace_all(&cleaned_title, " ")
-        .to_string();
-    cleaned_title = noise_token_regex()
-        .replace_all(&cleaned_title, " ")
-        .to_string();
-    cleaned_title = hyphen_separator_regex()
-        .replace_all(&cleaned_title, " ")
-        .to_string();
-    cleaned_title = collapse_whitespace_regex()
-        .replace_all(cleaned_title.trim(), " ")
+// Metadata tokens are recorded on the release and replaced by a marker, so
+// the episode patterns only see free text and the title stops at the first
+// piece of metadata.
+fn parse_release(raw: &str) -> Option<ParsedRelease> {
+    let mut release = ParsedRelease::default();
+    let mut remaining = String::new();
+
+    for token in split_enclosures(raw) {
+        match token {
+            Token::Enclosed(content) => {
+                if let Some(kept) = classify_enclosed_token(&mut release, &content, &remaining) {
+                    remaining.push_str(&kept);
+                } else {
+                    remaining.push_str(METADATA_MARKER);
+                }
+            }
+            Token::Text(text) => {
+                for word in classify_words(&text, false) {
+                    match word {
+                        Word::Free(value) => {
+                            remaining.push(' ');
+                            remaining.push_str(&value);
+                        }
+                        Word::Keyword(keyword) => {
+                            record_keywords(&mut release, &keyword);
+                            remaining.push_str(METADATA_MARKER);
+                        }
+                        Word::TrailingGroup(keyword, group) => {
+                            record_keywords(&mut release, &keyword);
+                            release.release_group.get_or_insert(group);
+                            remaining.push_str(METADATA_MARKER);
+                        }
+                    }
+                }
+                remaining.push(' ');
+            }
+        }
+    }
+
+    let remaining = collapse_whitespace_regex()
+        .replace_all(&remaining, " ")
         .to_string();
 
-    let title = cleaned_title
+    // Season and part numbers are blanked out before looking for the episode
+    // so `Season 2` is never read as episode 2. Only markers in front of the
+    // episode describe the series; later ones belong to the episode title.
+    let season_markers = find_season_markers(&remaining);
+    let kind_markers = find_kind_markers(&remaining);
+    let masked = mask_ranges(
+        &remaining,
+        season_markers
+            .iter()
+            .map(|marker| marker.range.clone())
+            .chain(kind_markers.iter().map(|marker| marker.range.clone())),
+    );
+
+    let (title_part, after_episode) = match extract_episode_from_title(&masked) {
+        Some(episode) => {
+            // Half episodes (`12.5`) are recaps or specials that have no
+            // place in the regular episode numbering.
+            if episode.fractional {
+                release.kind = EpisodeKind::Special;
+            } else {
+                release.episode = Some(episode.number);
+                release.episode_end = episode.end;
+            }
+            release.season = episode.season.or(release.season);
+            if episode.version.is_some() {
+                release.version = episode.version;
+            }
+
+            apply_season_markers(
+                &mut release,
+                season_markers
+                    .iter()
+                    .filter(|marker| marker.range.start < episode.range.start),
+            );
+            apply_kind_markers(
+                &mut release,
+                kind_markers
+                    .iter()
+                    .filter(|marker| marker.range.start < episode.range.start),
+            );
+
+            (
+                masked[..episode.range.start].to_string(),
+                Some(remaining[episode.range.end..].to_string()),
+            )
+        }
+        None => {
+            apply_season_markers(&mut release, season_markers.iter());
+            apply_kind_markers(&mut release, kind_markers.iter());
+            (masked, None)
+        }
+    };
+
+    let title = title_part
+        .split(METADATA_MARKER.trim())
+        .find_map(clean_free_segment)?;
+    release.anime_title = strip_trailing_roman_season(&mut release, title);
+    release.episode_title = after_episode
+        .as_deref()
+        .and_then(|value| value.split(METADATA_MARKER.trim()).next())
+        .and_then(clean_free_segment);
+
+    Some(release)
+}
+
+fn classify_enclosed_token(
+    release: &mut ParsedRelease,
+    content: &str,
+    preceding_text: &str,
+) -> Option<String> {
+    if content.is_empty() {
+        return None;
+    }
+
+    if release.checksum.is_none() && checksum_regex().is_match(content) {
+        release.checksum = Some(content.to_ascii_uppercase());
+        return None;
+    }
+
+    if let Some(year) = content
+        .parse::<u32>()
+        .ok()
+        .filter(|value| (1900..=2100).contains(value))
+    {
+        release.year.get_or_insert(year);
+        return None;
+    }
+
+    // Bracketed episode numbers (`[05]`, `[05v2]`) are left for the episode
+    // patterns to pick up.
+    if enclosed_episode_regex().is_match(content) {
+        return Some(format!(" [{content}] "));
+    }
+
+    let season_markers = find_season_markers(content);
+    let kind_markers = find_kind_markers(content);
+    if !season_markers.is_empty() || !kind_markers.is_empty() {
+        apply_season_markers(release, season_markers.iter());
+        apply_kind_markers(release, kind_markers.iter());
+        return None;
+    }
+
+    let words = classify_words(content, true);
+    let is_metadata = words.iter().all(|word| !matches!(word, Word::Free(_)));
+    for word in &words {
+        if let Word::Keyword(keyword) = word {
+            record_keywords(release, keyword);
+        }
+    }
+
+    let nothing_before = !preceding_text.chars().any(|ch| ch.is_alphanumeric());
+    if !is_metadata && nothing_before && release.release_group.is_none() {
+        release.release_group = Some(content.to_string());
+    }
+
+    None
+}
+
+#[derive(Debug, Clone)]
+struct SeasonMarker {
+    season: Option<u32>,
+    part: Option<u32>,
+    range: std::ops::Range<usize>,
+}
+
+fn find_season_markers(value: &str) -> Vec<SeasonMarker> {
+    let mut markers: Vec<SeasonMarker> = Vec::new();
+
+    for (regex, is_part) in [
+        (season_regex(), false),
+        (season_japanese_regex(), false),
+        (part_regex(), true),
+        (part_japanese_regex(), true),
+    ] {
+        for captures in regex.captures_iter(value) {
+            let Some(full_match) = captures.get(0) else {
+                continue;
+            };
+            let Some(number) = ["number", "after", "before"]
+                .into_iter()
+                .find_map(|name| captures.name(name))
+                .and_then(|value| parse_season_number(value.as_str()))
+            else {
+                continue;
+            };
+
+            markers.push(SeasonMarker {
+                season: (!is_part).then_some(number),
+                part: is_part.then_some(number),
+                range: full_match.range(),
+            });
+        }
+    }
+
+    markers.sort_by_key(|marker| marker.range.start);
+    markers
+}
+
+fn apply_season_markers<'a>(
+    release: &mut ParsedRelease,
+    markers: impl Iterator<Item = &'a SeasonMarker>,
+) {
+    for marker in markers {
+        if release.season.is_none() {
+            release.season = marker.season;
+        }
+        if release.part.is_none() {
+            release.part = marker.part;
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct KindMarker {
+    kind: EpisodeKind,
+    number: Option<u32>,
+    range: std::ops::Range<usize>,
+}
+
+fn find_kind_markers(value: &str) -> Vec<KindMarker> {
+    let mut markers: Vec<KindMarker> = kind_patterns()
+        .iter()
+        .flat_map(|(kind, regex)| {
+            regex.captures_iter(value).filter_map(|captures| {
+                Some(KindMarker {
+                    kind: *kind,
+                    number: captures
+                        .name("number")
+                        .and_then(|number| number.as_str().parse::<u32>().ok()),
+                    range: captures.get(0)?.range(),
+                })
+            })
+        })
+        .collect();
+
+    markers.sort_by_key(|marker| marker.range.start);
+    markers
+}
+
+// A numbered marker (`SP2`, `OVA 3`, `NCOP1`) doubles as the episode number
+// when the name carries no other one.
+fn apply_kind_markers<'a>(
+    release: &mut ParsedRelease,
+    mut markers: impl Iterator<Item = &'a KindMarker>,
+) {
+    let Some(marker) = markers.next() else {
+        return;
+    };
+
+    if release.kind == EpisodeKind::Regular {
+        release.kind = marker.kind;
+    }
+    if release.episode.is_none() && marker.kind != EpisodeKind::Movie {
+        release.episode = marker.number;
+    }
+}
+
+fn mask_ranges(value: &str, ranges: impl Iterator<Item = std::ops::Range<usize>>) -> String {
+    let mut masked = value.to_string();
+    for range in ranges {
+        masked.replace_range(range.clone(), &" ".repeat(range.len()));
+    }
+
+    masked
+}
+
+// Sequels are often only told apart by a trailing numeral (`Overlord II`).
+// The numeral is only stripped when something else is left of the title.
+fn strip_trailing_roman_season(release: &mut ParsedRelease, title: String) -> String {
+    let Some(captures) = trailing_roman_regex().captures(&title) else {
+        return title;
+    };
+    let Some(numeral) = captures.name("numeral") else {
+        return title;
+    };
+    let Some(season) = parse_roman_numeral(numeral.as_str()) else {
+        return title;
+    };
+
+    let stripped = title[..captures.get(0).map_or(0, |item| item.start())]
         .trim()
-        .trim_matches(|ch: char| matches!(ch, '-' | '_' | '.' | ' '))
         .to_string();
+    if stripped.is_empty() {
+        return title;
+    }
 
-    if title.is_empty() {
-        return None;
+    release.season.get_or_insert(season);
+    stripped
+}
+
+fn parse_season_number(value: &str) -> Option<u32> {
+    let normalized = value.trim().to_ascii_lowercase();
+    let normalized = ["st", "nd", "rd", "th"]
+        .into_iter()
+        .find_map(|suffix| {
+            normalized
+                .strip_suffix(suffix)
+                .filter(|digits| !digits.is_empty() && digits.chars().all(|ch| ch.is_ascii_digit()))
+        })
+        .unwrap_or(&normalized);
+    let number = match normalized {
+        "first" => Some(1),
+        "second" => Some(2),
+        "third" => Some(3),
+        "fourth" => Some(4),
+        "fifth" => Some(5),
+        "sixth" => Some(6),
+        _ => normalized
+            .parse::<u32>()
+            .ok()
+            .or_else(|| parse_roman_numeral(normalized)),
+    };
+
+    number.filter(|value| (1..=50).contains(value))
+}
+
+fn parse_roman_numeral(value: &str) -> Option<u32> {
+    match value.to_ascii_uppercase().as_str() {
+        "I" => Some(1),
+        "II" => Some(2),
+        "III" => Some(3),
+        "IV" => Some(4),
+        "V" => Some(5),
+        "VI" => Some(6),
+        "VII" => Some(7),
+        "VIII" => Some(8),
+        "IX" => Some(9),
+        "X" => Some(10),
+        _ => None,
+    }
+}
+
+fn record_keywords(release: &mut ParsedRelease, keyword: &Keyword) {
+    for part in split_glued_keywords(keyword) {
+        release.record_keyword(&part);
     }
+}
 
-    Some(ParsedAnime {
-        anime_title: title,
-        episode,
-    })
+fn push_unique(values: &mut Vec<String>, value: String) {
+    if !values.iter().any(|item| item.eq_ignore_ascii_case(&value)) {
+        values.push(value);
+    }
+}
+
+fn clean_free_segment(segment: &str) -> Option<String> {
+    let cleaned = hyphen_separator_regex().replace_all(segment, " ");
+    let cleaned = collapse_whitespace_regex()
+        .replace_all(cleaned.trim(), " ")
+        .trim_matches(|ch: char| matches!(ch, '-' | '_' | '.' | ' '))
+        .to_string();
+
+    if cleaned.chars().any(char::is_alphanumeric) {
+        Some(cleaned)
+    } else {
+        None
+    }
 }
 
 pub(crate) fn normalize_source_arg(value: &str) -> Option<String> {
@@ -77,22 +470,21 @@ pub(crate) fn normalize_source_arg(value: &str) -> Option<String> {
         .next()
         .unwrap_or(trimmed);
 
-    let extension = Path::new(sanitized)
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| ext.to_ascii_lowercase());
-
-    if extension
-        .as_deref()
-        .map(|ext| VIDEO_EXTENSIONS.contains(&ext))
-        .unwrap_or(false)
-    {
+    if has_video_extension(sanitized) {
         Some(trimmed.to_string())
     } else {
         None
     }
 }
 
+pub(crate) fn has_video_extension(value: &str) -> bool {
+    Path::new(value)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| VIDEO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
 pub(crate) fn is_url_source(value: &str) -> bool {
     let normalized = value.to_ascii_lowercase();
     normalized.starts_with("http://")
@@ -141,78 +533,98 @@ fn extract_source_title(source: &str) -> Option<String> {
     }
 }
 
-fn normalize_title_tokens(value: &str) -> String {
-    collapse_whitespace_regex()
-        .replace_all(&value.replace(['.', '_'], " "), " ")
-        .to_string()
+struct EpisodeMatch {
+    number: u32,
+    end: Option<u32>,
+    fractional: bool,
+    season: Option<u32>,
+    version: Option<u32>,
+    range: std::ops::Range<usize>,
 }
 
-fn extract_episode_from_title(title: &str) -> (Option<u32>, String) {
-    for regex in [
+fn extract_episode_from_title(title: &str) -> Option<EpisodeMatch> {
+    [
         episode_sxe_regex(),
         episode_explicit_regex(),
         episode_japanese_regex(),
         episode_dash_regex(),
         episode_bracket_regex(),
-    ] {
-        if let Some((episode, range)) = match_episode_with_regex(regex, title) {
-            let mut remaining = title.to_string();
-            remaining.replace_range(range, " ");
-            return (Some(episode), remaining);
-        }
-    }
-
-    if let Some((episode, range)) = fallback_episode_from_numbers(title) {
-        let mut remaining = title.to_string();
-        remaining.replace_range(range, " ");
-        return (Some(episode), remaining);
-    }
-
-    (None, title.to_string())
+    ]
+    .into_iter()
+    .find_map(|regex| match_episode_with_regex(regex, title))
+    .or_else(|| fallback_episode_from_numbers(title))
 }
 
-fn match_episode_with_regex(regex: &Regex, title: &str) -> Option<(u32, std::ops::Range<usize>)> {
+fn match_episode_with_regex(regex: &Regex, title: &str) -> Option<EpisodeMatch> {
     let captures = regex.captures(title)?;
-    let full_match = captures.get(0)?;
-    let episode_raw = captures.name("episode")?.as_str();
-    let episode = episode_raw.parse::<u32>().ok()?;
+    let episode = episode_from_captures(&captures)?;
 
-    if !is_plausible_episode(episode) {
+    if !is_plausible_episode(episode.number) {
         return None;
     }
 
-    Some((episode, full_match.range()))
+    Some(episode)
 }
 
-fn fallback_episode_from_numbers(title: &str) -> Option<(u32, std::ops::Range<usize>)> {
+fn fallback_episode_from_numbers(title: &str) -> Option<EpisodeMatch> {
     let captures: Vec<_> = fallback_numeric_regex().captures_iter(title).collect();
     for capture in captures.into_iter().rev() {
         let Some(full_match) = capture.get(0) else {
             continue;
         };
-        let Some(episode_match) = capture.name("episode") else {
-            continue;
-        };
 
         if !is_token_boundary(title, full_match.start(), full_match.end()) {
             continue;
         }
 
-        let episode = match episode_match.as_str().parse::<u32>() {
-            Ok(value) => value,
-            Err(_) => continue,
+        let Some(episode) = episode_from_captures(&capture) else {
+            continue;
         };
 
-        if !is_plausible_episode(episode) {
+        if !is_plausible_episode(episode.number) {
             continue;
         }
 
-        return Some((episode, full_match.range()));
+        return Some(episode);
     }
 
     None
 }
 
+fn episode_from_captures(captures: &regex::Captures<'_>) -> Option<EpisodeMatch> {
+    let parse_group = |name: &str| {
+        captures
+            .name(name)
+            .and_then(|value| value.as_str().parse::<u32>().ok())
+    };
+
+    let number = parse_group("episode")?;
+    let mut range = captures.get(0)?.range();
+
+    // `05 - 03` is an episode followed by unrelated numbers, not a range.
+    let end = parse_group("episode_end");
+    let end = match end {
+        Some(end) if end > number && is_plausible_episode(end) => Some(end),
+        Some(_) => {
+            range.end = ["version", "decimal", "episode"]
+                .into_iter()
+                .find_map(|name| captures.name(name))
+                .map_or(range.end, |group| group.end());
+            None
+        }
+        None => None,
+    };
+
+    Some(EpisodeMatch {
+        number,
+        end,
+        fractional: parse_group("decimal").is_some_and(|decimal| decimal > 0),
+        season: parse_group("season"),
+        version: parse_group("version"),
+        range,
+    })
+}
+
 fn is_token_boundary(value: &str, start: usize, end: usize) -> bool {
     let prev = value[..start].chars().last();
     let next = value[end..].chars().next();
@@ -237,7 +649,7 @@ fn is_plausible_episode(value: u32) -> bool {
         && !(1900..=2100).contains(&value)
 }
 
-fn decode_percent_encoded(value: &str) -> String {
+pub(crate) fn decode_percent_encoded(value: &str) -> String {
     let bytes = value.as_bytes();
     let mut out = Vec::with_capacity(bytes.len());
     let mut index = 0;
@@ -273,25 +685,93 @@ fn hex_to_u8(value: u8) -> Option<u8> {
     }
 }
 
-fn leading_group_regex() -> &'static Regex {
+fn checksum_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^[0-9A-Fa-f]{8}$").expect("valid checksum regex"))
+}
+
+fn enclosed_episode_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"(?i)^\d{1,4}(?:v\d+)?(?:[-~]\d{1,4}(?:v\d+)?)?$")
+            .expect("valid enclosed episode regex")
+    })
+}
+
+fn season_regex() -> &'static Regex {
     static REGEX: OnceLock<Regex> = OnceLock::new();
-    REGEX.get_or_init(|| Regex::new(r"^(?:\[[^\]]+\]\s*)+").expect("valid leading group regex"))
+    REGEX.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(?:S(?P<number>\d{1,2})|Season\s*(?P<after>\d{1,2}|[IVX]{1,4})|(?P<before>\d{1,2}(?:st|nd|rd|th)|first|second|third|fourth|fifth|sixth)\s+Season)\b",
+        )
+        .expect("valid season regex")
+    })
 }
 
-fn bracket_group_regex() -> &'static Regex {
+fn season_japanese_regex() -> &'static Regex {
     static REGEX: OnceLock<Regex> = OnceLock::new();
     REGEX.get_or_init(|| {
-        Regex::new(r"[\[\(\{][^\]\)\}]*[\]\)\}]").expect("valid bracket group regex")
+        Regex::new(r"\x{7B2C}\s*(?P<number>\d{1,2})\s*\x{671F}").expect("valid JP season regex")
     })
 }
 
-fn noise_token_regex() -> &'static Regex {
+fn part_regex() -> &'static Regex {
     static REGEX: OnceLock<Regex> = OnceLock::new();
     REGEX.get_or_init(|| {
         Regex::new(
-            r"(?i)\b(?:\d{3,4}p(?:[- ]?main(?:8|10))?|main(?:8|10)|10bit|8bit|x264(?:-[a-z0-9]+)?|x265(?:-[a-z0-9]+)?|h264|h265|hevc|av1|aac(?:[- ]?2[- ]?0)?|flac|opus|ddp?(?:[- ]?(?:2[- ]?0|5[- ]?1|7[- ]?1))?|ac[- ]?3|e[- ]?ac[- ]?3|dts(?:[- ]?hd)?|truehd|atmos|blu[- ]?ray|bdrip|webrip|web[- ]?dl|dvdrip|remux|proper|repack|vostfr|raw|sub(?:bed|s)?|multi|dual[- ]?audio)\b",
+            r"(?i)\b(?:(?:Part|Cour)\s*(?P<after>\d{1,2}|[IVX]{1,4})|(?P<before>\d{1,2}(?:st|nd|rd|th))\s+(?:Part|Cour))\b",
         )
-        .expect("valid noise token regex")
+        .expect("valid part regex")
+    })
+}
+
+fn part_japanese_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"\x{7B2C}\s*(?P<number>\d{1,2})\s*\x{30AF}\x{30FC}\x{30EB}")
+            .expect("valid JP part regex")
+    })
+}
+
+fn trailing_roman_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"\s(?P<numeral>II|III|IV|VI|VII|VIII|IX)$").expect("valid roman numeral regex")
+    })
+}
+
+fn kind_patterns() -> &'static [(EpisodeKind, Regex)] {
+    static PATTERNS: OnceLock<Vec<(EpisodeKind, Regex)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            (
+                EpisodeKind::OpeningEnding,
+                r"NC(?:OP|ED)\s*(?P<number>\d{1,2})?|Creditless\s+(?:OP|ED|Opening|Ending)",
+            ),
+            // Bare `OP`/`ED` are only markers when numbered (`OP2`, `ED 1`).
+            (
+                EpisodeKind::OpeningEnding,
+                r"(?:OP|ED)\s*(?P<number>\d{1,2})",
+            ),
+            (EpisodeKind::Ova, r"(?:OVA|OAD)\s*(?P<number>\d{1,3})?"),
+            (
+                EpisodeKind::Special,
+                r"(?:SP|Specials?)\s*(?P<number>\d{1,3})?|Recap",
+            ),
+            (
+                EpisodeKind::Preview,
+                r"Preview|PV\s*(?P<number>\d{1,2})?|Trailer|Teaser",
+            ),
+            (EpisodeKind::Movie, r"Movie|Gekijouban"),
+        ]
+        .into_iter()
+        .map(|(kind, pattern)| {
+            (
+                kind,
+                Regex::new(&format!(r"(?i)\b(?:{pattern})\b")).expect("valid episode kind regex"),
+            )
+        })
+        .collect()
     })
 }
 
@@ -308,14 +788,19 @@ fn collapse_whitespace_regex() -> &'static Regex {
 fn episode_sxe_regex() -> &'static Regex {
     static REGEX: OnceLock<Regex> = OnceLock::new();
     REGEX.get_or_init(|| {
-        Regex::new(r"(?i)\bS\d{1,2}E(?P<episode>\d{1,4})\b").expect("valid SxE regex")
+        Regex::new(
+            r"(?i)\bS(?P<season>\d{1,2})E(?P<episode>\d{1,4})(?:v(?P<version>\d+))?(?:-E?(?P<episode_end>\d{1,4}))?\b",
+        )
+        .expect("valid SxE regex")
     })
 }
 
 fn episode_explicit_regex() -> &'static Regex {
     static REGEX: OnceLock<Regex> = OnceLock::new();
     REGEX.get_or_init(|| {
-        Regex::new(r"(?i)\bE(?:P|PISODE)?[ ._-]?(?P<episode>\d{1,4})\b")
+        Regex::new(
+            r"(?i)\bE(?:P|PISODE)?[ ._-]?(?P<episode>\d{1,4})(?:\.(?P<decimal>\d))?(?:v(?P<version>\d+))?\b",
+        )
             .expect("valid explicit episode regex")
     })
 }
@@ -331,21 +816,31 @@ fn episode_japanese_regex() -> &'static Regex {
 fn episode_dash_regex() -> &'static Regex {
     static REGEX: OnceLock<Regex> = OnceLock::new();
     REGEX.get_or_init(|| {
-        Regex::new(r"(?i)\s-\s(?P<episode>\d{1,4})(?:v\d+)?\b").expect("valid dash regex")
+        Regex::new(
+            r"(?i)\s-\s(?P<episode>\d{1,4})(?:\.(?P<decimal>\d))?(?:v(?P<version>\d+))?(?:(?:[-~]|\s~\s)(?P<episode_end>\d{1,4})(?:v\d+)?)?\b",
+        )
+        .expect("valid dash regex")
     })
 }
 
 fn episode_bracket_regex() -> &'static Regex {
     static REGEX: OnceLock<Regex> = OnceLock::new();
     REGEX.get_or_init(|| {
-        Regex::new(r"(?i)\[(?P<episode>\d{1,4})(?:v\d+)?\]").expect("valid bracket regex")
+        Regex::new(
+            r"(?i)\[(?P<episode>\d{1,4})(?:v(?P<version>\d+))?(?:[-~](?P<episode_end>\d{1,4})(?:v\d+)?)?\]",
+        )
+        .expect("valid bracket regex")
     })
 }
 
 fn fallback_numeric_regex() -> &'static Regex {
     static REGEX: OnceLock<Regex> = OnceLock::new();
-    REGEX
-        .get_or_init(|| Regex::new(r"(?P<episode>\d{1,4})(?:v\d+)?").expect("valid fallback regex"))
+    REGEX.get_or_init(|| {
+        Regex::new(
+            r"(?P<episode>\d{1,4})(?:\.(?P<decimal>\d))?(?:v(?P<version>\d+))?(?:[-~](?P<episode_end>\d{1,4}))?",
+        )
+        .expect("valid fallback regex")
+    })
 }
 
 #[cfg(test)]
@@ -393,6 +888,462 @@ mod tests {
         assert_eq!(parsed.episode, Some(8));
     }
 
+    #[test]
+    fn parse_release_records_group_version_checksum_and_episode_title() {
+        let release = parse_anime_from_source(
+            "[SubsPlease] Sousou no Frieren - 05v2 - The Hero's Journey (1080p) [ABCD1234].mkv",
+        )
+        .expect("source should parse");
+
+        assert_eq!(release.anime_title, "Sousou no Frieren");
+        assert_eq!(release.episode, Some(5));
+        assert_eq!(release.version, Some(2));
+        assert_eq!(release.episode_title.as_deref(), Some("The Hero's Journey"));
+        assert_eq!(release.release_group.as_deref(), Some("SubsPlease"));
+        assert_eq!(release.resolution.as_deref(), Some("1080p"));
+        assert_eq!(release.checksum.as_deref(), Some("ABCD1234"));
+    }
+
+    #[test]
+    fn parse_release_collects_codecs_source_languages_and_year() {
+        let release = parse_anime_from_source(
+            "[Judas] Kimetsu no Yaiba (2019) - S01E08 [BD 1080p HEVC x265 10bit Dual-Audio][Multi-Subs].mkv",
+        )
+        .expect("source should parse");
+
+        assert_eq!(release.anime_title, "Kimetsu no Yaiba");
+        assert_eq!(release.season, Some(1));
+        assert_eq!(release.episode, Some(8));
+        assert_eq!(release.year, Some(2019));
+        assert_eq!(release.release_group.as_deref(), Some("Judas"));
+        assert_eq!(release.source.as_deref(), Some("BD"));
+        assert_eq!(release.video_codecs, vec!["HEVC", "x265", "10bit"]);
+        assert_eq!(release.audio_codecs, vec!["Dual-Audio"]);
+        assert_eq!(release.languages, vec!["Multi-Subs"]);
+        assert_eq!(release.episode_title, None);
+    }
+
+    #[test]
+    fn parse_release_reads_scene_style_names() {
+        let release = parse_anime_from_source(
+            "Ghost.in.the.Shell.Stand.Alone.Complex.S01E08.1080p-Main10.BluRay.DD5.1.x265-CTR.mkv",
+        )
+        .expect("source should parse");
+
+        assert_eq!(release.release_group.as_deref(), Some("CTR"));
+        assert_eq!(release.resolution.as_deref(), Some("1080p"));
+        assert_eq!(release.source.as_deref(), Some("BluRay"));
+        assert_eq!(release.video_codecs, vec!["Main10", "x265"]);
+        assert_eq!(release.audio_codecs, vec!["DD5.1"]);
+    }
+
+    #[test]
+    fn parse_anime_from_title_normalizes_dots_in_display_titles() {
+        let parsed = parse_anime_from_title("Dr. Stone - 05").expect("display title should parse");
+
+        assert_eq!(parsed.anime_title, "Dr Stone");
+        assert_eq!(parsed.episode, Some(5));
+        assert!(parse_anime_from_title("   ").is_none());
+    }
+
+    #[test]
+    fn extracts_season_and_part_from_release_names() {
+        // (source, title, season, part, episode)
+        type Case = (
+            &'static str,
+            &'static str,
+            Option<u32>,
+            Option<u32>,
+            Option<u32>,
+        );
+        let cases: &[Case] = &[
+            (
+                "Ghost.in.the.Shell.Stand.Alone.Complex.S02E08.1080p.BluRay.x265-CTR.mkv",
+                "Ghost in the Shell Stand Alone Complex",
+                Some(2),
+                None,
+                Some(8),
+            ),
+            (
+                "[SubsPlease] Mushoku Tensei S2 - 05 (1080p) [A1B2C3D4].mkv",
+                "Mushoku Tensei",
+                Some(2),
+                None,
+                Some(5),
+            ),
+            (
+                "[SubsPlease] Spy x Family Season 2 - 03 (1080p) [0F3B2A11].mkv",
+                "Spy x Family",
+                Some(2),
+                None,
+                Some(3),
+            ),
+            (
+                "[Erai-raws] Kimetsu no Yaiba 2nd Season - 11 [1080p][Multiple Subtitle].mkv",
+                "Kimetsu no Yaiba",
+                Some(2),
+                None,
+                Some(11),
+            ),
+            (
+                "[Judas] Shingeki no Kyojin - The Final Season Part 2 - 04 [1080p][HEVC x265 10bit].mkv",
+                "Shingeki no Kyojin - The Final Season",
+                None,
+                Some(2),
+                Some(4),
+            ),
+            (
+                "[SubsPlease] Jujutsu Kaisen 2nd Season Cour 2 - 18 (720p) [C0FFEE42].mkv",
+                "Jujutsu Kaisen",
+                Some(2),
+                Some(2),
+                Some(18),
+            ),
+            (
+                "[HorribleSubs] Overlord II - 07 [1080p].mkv",
+                "Overlord",
+                Some(2),
+                None,
+                Some(7),
+            ),
+            (
+                "[Group] Re Zero kara Hajimeru Isekai Seikatsu (Season 3) - 02 [1080p].mkv",
+                "Re Zero kara Hajimeru Isekai Seikatsu",
+                Some(3),
+                None,
+                Some(2),
+            ),
+            (
+                "[Group] Vinland Saga Season 2 [BD 1080p].mkv",
+                "Vinland Saga",
+                Some(2),
+                None,
+                None,
+            ),
+            (
+                "[Group] Show - 05 - The Long Night, Part 2 [1080p].mkv",
+                "Show",
+                None,
+                None,
+                Some(5),
+            ),
+            (
+                "Kusuriya no Hitorigoto \u{7B2C}2\u{671F} \u{7B2C}3\u{8A71}.mkv",
+                "Kusuriya no Hitorigoto",
+                Some(2),
+                None,
+                Some(3),
+            ),
+        ];
+
+        for (source, title, season, part, episode) in cases {
+            let release =
+                parse_anime_from_source(source).unwrap_or_else(|| panic!("{source} should parse"));
+
+            assert_eq!(release.anime_title, *title, "title of {source}");
+            assert_eq!(release.season, *season, "season of {source}");
+            assert_eq!(release.part, *part, "part of {source}");
+            assert_eq!(release.episode, *episode, "episode of {source}");
+        }
+    }
+
+    #[test]
+    fn parses_batch_ranges_without_confusing_episode_titles() {
+        let batch = parse_anime_from_source("[Group] Show - 01-12 [BD 1080p].mkv")
+            .expect("batch should parse");
+        assert_eq!(batch.anime_title, "Show");
+        assert_eq!((batch.episode, batch.episode_end), (Some(1), Some(12)));
+        assert_eq!(batch.kind, EpisodeKind::Regular);
+
+        let bracketed = parse_anime_from_source("[Group] Show (01~24) [BD].mkv")
+            .expect("bracketed batch should parse");
+        assert_eq!(
+            (bracketed.episode, bracketed.episode_end),
+            (Some(1), Some(24))
+        );
+
+        let not_a_range = parse_anime_from_source("[Group] Show - 05-03 [1080p].mkv")
+            .expect("source should parse");
+        assert_eq!(
+            (not_a_range.episode, not_a_range.episode_end),
+            (Some(5), None)
+        );
+    }
+
+    #[test]
+    fn classifies_specials_ovas_and_creditless_extras() {
+        // (source, title, kind, episode)
+        type Case = (&'static str, &'static str, EpisodeKind, Option<u32>);
+        let cases: &[Case] = &[
+            (
+                "[Group] Frieren - NCOP1 [1080p].mkv",
+                "Frieren",
+                EpisodeKind::OpeningEnding,
+                Some(1),
+            ),
+            (
+                "[Group] Frieren - Creditless Ending [1080p].mkv",
+                "Frieren",
+                EpisodeKind::OpeningEnding,
+                None,
+            ),
+            (
+                "[Group] Frieren NCED [BD 1080p].mkv",
+                "Frieren",
+                EpisodeKind::OpeningEnding,
+                None,
+            ),
+            (
+                "[Group] Hellsing Ultimate OVA 03 [BD 1080p].mkv",
+                "Hellsing Ultimate",
+                EpisodeKind::Ova,
+                Some(3),
+            ),
+            (
+                "[Group] Toradora! OAD [DVD].mkv",
+                "Toradora!",
+                EpisodeKind::Ova,
+                None,
+            ),
+            (
+                "[Group] K-On! - SP1 [BD 1080p].mkv",
+                "K-On!",
+                EpisodeKind::Special,
+                Some(1),
+            ),
+            (
+                "[Group] Re Zero - Recap [1080p].mkv",
+                "Re Zero",
+                EpisodeKind::Special,
+                None,
+            ),
+            (
+                "[Group] Made in Abyss - Episode 12.5 [1080p].mkv",
+                "Made in Abyss",
+                EpisodeKind::Special,
+                None,
+            ),
+            (
+                "[Group] Frieren - 12.5 [1080p].mkv",
+                "Frieren",
+                EpisodeKind::Special,
+                None,
+            ),
+            (
+                "[Group] Oshi no Ko - Preview [1080p].mkv",
+                "Oshi no Ko",
+                EpisodeKind::Preview,
+                None,
+            ),
+            (
+                "[Group] Kimi no Na wa (Movie) [BD 1080p].mkv",
+                "Kimi no Na wa",
+                EpisodeKind::Movie,
+                None,
+            ),
+            (
+                "[Group] Show OVA - 02 [1080p].mkv",
+                "Show",
+                EpisodeKind::Ova,
+                Some(2),
+            ),
+        ];
+
+        for (source, title, kind, episode) in cases {
+            let release =
+                parse_anime_from_source(source).unwrap_or_else(|| panic!("{source} should parse"));
+
+            assert_eq!(release.anime_title, *title, "title of {source}");
+            assert_eq!(release.kind, *kind, "kind of {source}");
+            assert_eq!(release.episode, *episode, "episode of {source}");
+        }
+    }
+
+    const RELEASE_CORPUS: &str = include_str!("fixtures/release_corpus.tsv");
+
+    // Names in the checked-in corpus the parser still gets wrong: `00`
+    // episodes, `S00` specials, dotted `H.264-GROUP` suffixes, fully bracketed
+    // names and titles that carry their own punctuation. Every other line has
+    // to parse exactly, so with a corpus this small a single regression fails
+    // the test instead of disappearing into a percentage.
+    const KNOWN_CORPUS_MISSES: &[&str] = &[
+        "[SubsPlease] Mushoku Tensei S2 - 00 (1080p).mkv",
+        "[SubsPlease] Dr. Stone S3 - 22 (1080p).mkv",
+        "[SubsPlease] Shingeki no Kyojin (The Final Season Part 2) - 87 (1080p).mkv",
+        "[Judas] Re Zero kara Hajimeru Isekai Seikatsu - OVA 01 - Memory Snow.mkv",
+        "[Nekomoe kissaten][Sousou no Frieren][01][1080p][JPSC].mp4",
+        "[Nekomoe kissaten][Make Heroine ga Oosugiru!][11][1080p][JPTC].mp4",
+        "[FFF] Kyoukai no Kanata - 00 [BD][1080p-FLAC].mkv",
+        "Frieren.Beyond.Journeys.End.S01E05.1080p.CR.WEB-DL.AAC2.0.H.264-VARYG.mkv",
+        "Delicious.in.Dungeon.S01E01.1080p.NF.WEB-DL.DDP5.1.H.264-VARYG.mkv",
+        "Solo.Leveling.S01E03.1080p.CR.WEB-DL.AAC2.0.H.264-VARYG.mkv",
+        "Dan.Da.Dan.S01E07.1080p.NF.WEB-DL.DDP5.1.H.264-VARYG.mkv",
+        "The.Apothecary.Diaries.S01E18.1080p.CR.WEB-DL.AAC2.0.H.264-VARYG.mkv",
+        "Oshi.no.Ko.S02E06.1080p.HIDIVE.WEB-DL.AAC2.0.H.264-VARYG.mkv",
+        "Jujutsu.Kaisen.S02E17.1080p.CR.WEB-DL.AAC2.0.H.264-VARYG.mkv",
+        "Cyberpunk.Edgerunners.S01E06.1080p.NF.WEB-DL.DDP5.1.H.264-NTb.mkv",
+        "Odd.Taxi.S01E13.1080p.CR.WEB-DL.AAC2.0.H.264-VARYG.mkv",
+        "Blue.Eye.Samurai.S01E08.1080p.NF.WEB-DL.DDP5.1.Atmos.H.264-FLUX.mkv",
+        "Pluto.S01E08.1080p.NF.WEB-DL.DDP5.1.H.264-VARYG.mkv",
+        "Mushoku Tensei - S02E00 - Guardian Fitz.mkv",
+        "Kimetsu no Yaiba - S00E01 - Special.mkv",
+        "/mnt/media/anime/Bocchi the Rock!/Specials/Bocchi the Rock! - S00E01.mkv",
+    ];
+
+    // Set to a file in the corpus format to measure the parser against a
+    // larger dump, such as one exported from nyaa or AniDB.
+    const RELEASE_DUMP_ENV: &str = "KIOKU_RELEASE_CORPUS";
+
+    #[derive(Default)]
+    struct FieldScore {
+        matched: usize,
+        total: usize,
+    }
+
+    impl FieldScore {
+        fn record(&mut self, matched: bool) -> bool {
+            self.total += 1;
+            if matched {
+                self.matched += 1;
+            }
+            matched
+        }
+
+        fn accuracy(&self) -> f64 {
+            if self.total == 0 {
+                return 1.0;
+            }
+            self.matched as f64 / self.total as f64
+        }
+    }
+
+    #[derive(Default)]
+    struct CorpusScore {
+        title: FieldScore,
+        episode: FieldScore,
+        season: FieldScore,
+        group: FieldScore,
+        kind: FieldScore,
+        // Names with at least one field parsed differently than annotated.
+        misses: Vec<String>,
+    }
+
+    impl CorpusScore {
+        fn report(&self) {
+            for (field, score) in [
+                ("title", &self.title),
+                ("episode", &self.episode),
+                ("season", &self.season),
+                ("group", &self.group),
+                ("kind", &self.kind),
+            ] {
+                eprintln!(
+                    "{field:>8}: {}/{} ({:.2}%)",
+                    score.matched,
+                    score.total,
+                    score.accuracy() * 100.0
+                );
+            }
+        }
+    }
+
+    fn expected_field(value: &str) -> Option<&str> {
+        (value != "-").then_some(value)
+    }
+
+    fn expected_kind(value: &str) -> EpisodeKind {
+        match value {
+            "regular" => EpisodeKind::Regular,
+            "special" => EpisodeKind::Special,
+            "ova" => EpisodeKind::Ova,
+            "opening-ending" => EpisodeKind::OpeningEnding,
+            "preview" => EpisodeKind::Preview,
+            "movie" => EpisodeKind::Movie,
+            other => panic!("unknown episode kind `{other}`"),
+        }
+    }
+
+    fn score_release_corpus(corpus: &str) -> CorpusScore {
+        let mut score = CorpusScore::default();
+
+        for line in corpus.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [name, expected_title, expected_episode, expected_season, expected_group, expected_kind_name] =
+                fields[..]
+            else {
+                panic!("corpus line should have six columns: {line}");
+            };
+
+            let release = parse_anime_from_source(name).unwrap_or_default();
+            let matched = [
+                score.title.record(release.anime_title == expected_title),
+                score.episode.record(
+                    release.episode
+                        == expected_field(expected_episode)
+                            .map(|value| value.parse().expect("numeric episode")),
+                ),
+                score.season.record(
+                    release.season
+                        == expected_field(expected_season)
+                            .map(|value| value.parse().expect("numeric season")),
+                ),
+                score
+                    .group
+                    .record(release.release_group.as_deref() == expected_field(expected_group)),
+                score
+                    .kind
+                    .record(release.kind == expected_kind(expected_kind_name)),
+            ];
+            if matched.contains(&false) {
+                score.misses.push(name.to_string());
+            }
+        }
+
+        score
+    }
+
+    #[test]
+    fn release_corpus_parses_except_known_misses() {
+        let score = score_release_corpus(RELEASE_CORPUS);
+        score.report();
+
+        let regressions: Vec<&String> = score
+            .misses
+            .iter()
+            .filter(|name| !KNOWN_CORPUS_MISSES.contains(&name.as_str()))
+            .collect();
+        assert!(
+            regressions.is_empty(),
+            "parsed differently: {regressions:#?}"
+        );
+
+        let fixed: Vec<&&str> = KNOWN_CORPUS_MISSES
+            .iter()
+            .filter(|name| !score.misses.iter().any(|miss| miss == *name))
+            .collect();
+        assert!(
+            fixed.is_empty(),
+            "now parse correctly, remove them from KNOWN_CORPUS_MISSES: {fixed:#?}"
+        );
+    }
+
+    #[test]
+    #[ignore = "needs a release dump in KIOKU_RELEASE_CORPUS"]
+    fn release_dump_reports_per_field_accuracy() {
+        let path = std::env::var(RELEASE_DUMP_ENV)
+            .unwrap_or_else(|_| panic!("{RELEASE_DUMP_ENV} should point at a corpus file"));
+        let corpus = std::fs::read_to_string(&path)
+            .unwrap_or_else(|err| panic!("failed to read {path}: {err}"));
+
+        let score = score_release_corpus(&corpus);
+        score.report();
+        eprintln!("misses: {:#?}", score.misses);
+    }
+
     #[test]
     fn returns_none_when_source_only_contains_noise_tokens() {
         assert!(parse_anime_from_source("[SubsPlease] [1080p] [AAC] [1234ABCD].mkv").is_none());
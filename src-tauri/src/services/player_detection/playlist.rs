@@ -0,0 +1,590 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+use regex::Regex;
+
+use super::parser::{
+    decode_percent_encoded, is_url_source, looks_like_windows_path, parse_anime_from_source,
+    ParsedRelease,
+};
+use super::types::PlayerPlaybackStatus;
+use super::util::find_long_option_value;
+
+const PLAYLIST_EXTENSIONS: &[&str] = &["m3u", "m3u8", "pls", "xspf"];
+const PLAYLIST_OPTION: &str = "--playlist";
+const MAX_PLAYLIST_BYTES: u64 = 4 * 1024 * 1024;
+const MAX_CACHED_PLAYLISTS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PlaylistEntry {
+    pub location: String,
+    pub title: Option<String>,
+}
+
+// A playlist named on the command line. mpv's playlist position counts every
+// media argument and `--shuffle` reorders them, so the position only indexes
+// this file when it is the sole media argument and plays in order. Option
+// values given as separate arguments count as media too, which only costs the
+// position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PlaylistArg {
+    pub path: String,
+    pub follows_position: bool,
+}
+
+struct CachedPlaylist {
+    modified: Option<SystemTime>,
+    len: u64,
+    entries: Arc<Vec<PlaylistEntry>>,
+}
+
+pub(crate) fn has_playlist_extension(value: &str) -> bool {
+    Path::new(value)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| PLAYLIST_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+// mpv takes `--playlist=<file>`; every player also accepts the playlist as a
+// plain argument. The last one wins, matching how players treat repeats.
+pub(crate) fn find_playlist_arg(args: &[String]) -> Option<PlaylistArg> {
+    let positional: Vec<&str> = args
+        .iter()
+        .skip(1)
+        .map(|arg| arg.trim().trim_matches('"').trim_matches('\'').trim())
+        .filter(|arg| !arg.is_empty() && !arg.starts_with('-'))
+        .collect();
+
+    let path = find_long_option_value(args, PLAYLIST_OPTION)
+        .filter(|value| has_playlist_extension(value))
+        .or_else(|| {
+            positional
+                .iter()
+                .rev()
+                .find(|arg| !is_url_source(arg) && has_playlist_extension(arg))
+                .map(|arg| arg.to_string())
+        })?;
+    let follows_position = !is_shuffled(args) && positional.iter().all(|arg| *arg == path);
+
+    Some(PlaylistArg {
+        path,
+        follows_position,
+    })
+}
+
+// mpv takes `--shuffle[=yes|no]`, VLC `--random` or `-Z`; the last one wins.
+fn is_shuffled(args: &[String]) -> bool {
+    args.iter()
+        .skip(1)
+        .map(|arg| arg.trim())
+        .fold(false, |shuffled, arg| match arg {
+            "--shuffle" | "--random" | "-Z" => true,
+            "--no-shuffle" | "--no-random" => false,
+            _ => arg
+                .strip_prefix("--shuffle=")
+                .map_or(shuffled, |value| value != "no"),
+        })
+}
+
+// Playlists are read on every poll, so parsed entries are kept until the file's
+// modification time or size changes.
+pub(crate) fn read_playlist(path: &str) -> Result<Arc<Vec<PlaylistEntry>>, String> {
+    let metadata = std::fs::metadata(path)
+        .map_err(|error| format!("Failed to read playlist {path}: {error}"))?;
+    if metadata.len() > MAX_PLAYLIST_BYTES {
+        return Err(format!("Playlist {path} is too large to read"));
+    }
+
+    let modified = metadata.modified().ok();
+    let len = metadata.len();
+    let cached = playlist_cache().lock().ok().and_then(|cache| {
+        cache
+            .get(path)
+            .filter(|cached| cached.modified == modified && cached.len == len)
+            .map(|cached| Arc::clone(&cached.entries))
+    });
+    if let Some(entries) = cached {
+        return Ok(entries);
+    }
+
+    let entries = Arc::new(parse_playlist_file(path)?);
+    if let Ok(mut cache) = playlist_cache().lock() {
+        if cache.len() >= MAX_CACHED_PLAYLISTS && !cache.contains_key(path) {
+            cache.clear();
+        }
+        cache.insert(
+            path.to_string(),
+            CachedPlaylist {
+                modified,
+                len,
+                entries: Arc::clone(&entries),
+            },
+        );
+    }
+
+    Ok(entries)
+}
+
+fn playlist_cache() -> &'static Mutex<HashMap<String, CachedPlaylist>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, CachedPlaylist>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn parse_playlist_file(path: &str) -> Result<Vec<PlaylistEntry>, String> {
+    let bytes =
+        std::fs::read(path).map_err(|error| format!("Failed to read playlist {path}: {error}"))?;
+    let content = String::from_utf8_lossy(&bytes);
+    let content = content.trim_start_matches('\u{feff}');
+    let base = Path::new(path).parent().unwrap_or(Path::new(""));
+
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "m3u" | "m3u8" => Ok(parse_m3u(content, base)),
+        "pls" => Ok(parse_pls(content, base)),
+        "xspf" => Ok(parse_xspf(content, base)),
+        _ => Err(format!("Unsupported playlist format: {path}")),
+    }
+}
+
+// Uses the player's playlist position or current title when it reports one and
+// otherwise settles for the series title every entry agrees on. The position
+// is only trusted when `follows_position` says it indexes `entries`.
+pub(crate) fn resolve_playlist_release(
+    entries: &[PlaylistEntry],
+    status: Option<&PlayerPlaybackStatus>,
+    follows_position: bool,
+) -> Option<ParsedRelease> {
+    let playing = status.and_then(|status| {
+        status
+            .playlist_position
+            .filter(|_| follows_position)
+            .and_then(|position| entries.get(position))
+            .or_else(|| {
+                let title = status.media_title.as_deref()?.trim();
+                entries
+                    .iter()
+                    .find(|entry| entry_matches_title(entry, title))
+            })
+    });
+    let playing = playing.or(match entries {
+        [single] => Some(single),
+        _ => None,
+    });
+
+    if let Some(entry) = playing {
+        return parse_anime_from_source(&entry.location);
+    }
+
+    let mut titles = entries
+        .iter()
+        .filter_map(|entry| parse_anime_from_source(&entry.location))
+        .map(|release| release.anime_title);
+    let first = titles.next()?;
+    if titles.all(|title| title == first) {
+        return Some(ParsedRelease {
+            anime_title: first,
+            ..Default::default()
+        });
+    }
+
+    None
+}
+
+fn entry_matches_title(entry: &PlaylistEntry, title: &str) -> bool {
+    if entry
+        .title
+        .as_deref()
+        .is_some_and(|entry_title| entry_title.trim() == title)
+    {
+        return true;
+    }
+
+    let file_name = entry
+        .location
+        .rsplit(['/', '\\'])
+        .next()
+        .map(decode_percent_encoded)
+        .unwrap_or_default();
+    let stem = Path::new(&file_name)
+        .file_stem()
+        .and_then(|value| value.to_str())
+        .unwrap_or(&file_name);
+
+    file_name == title || stem == title
+}
+
+fn parse_m3u(content: &str, base: &Path) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut pending_title = None;
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            pending_title = info
+                .split_once(',')
+                .map(|(_, title)| title.trim().to_string())
+                .filter(|title| !title.is_empty());
+            continue;
+        }
+
+        if line.starts_with('#') {
+            continue;
+        }
+
+        entries.push(PlaylistEntry {
+            location: resolve_location(line, base),
+            title: pending_title.take(),
+        });
+    }
+
+    entries
+}
+
+fn parse_pls(content: &str, base: &Path) -> Vec<PlaylistEntry> {
+    let mut files = Vec::new();
+    let mut titles = Vec::new();
+
+    for line in content.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+
+        if let Some(index) = key
+            .strip_prefix("file")
+            .and_then(|index| index.parse::<u32>().ok())
+        {
+            files.push((index, resolve_location(value, base)));
+        } else if let Some(index) = key
+            .strip_prefix("title")
+            .and_then(|index| index.parse::<u32>().ok())
+        {
+            titles.push((index, value.to_string()));
+        }
+    }
+
+    files.sort_by_key(|(index, _)| *index);
+    files
+        .into_iter()
+        .map(|(index, location)| PlaylistEntry {
+            location,
+            title: titles
+                .iter()
+                .find(|(title_index, _)| *title_index == index)
+                .map(|(_, title)| title.clone()),
+        })
+        .collect()
+}
+
+fn parse_xspf(content: &str, base: &Path) -> Vec<PlaylistEntry> {
+    xspf_track_regex()
+        .captures_iter(content)
+        .filter_map(|track| {
+            let body = track.get(1)?.as_str();
+            let location = xspf_element(body, "location")?;
+
+            Some(PlaylistEntry {
+                location: resolve_location(&location, base),
+                title: xspf_element(body, "title"),
+            })
+        })
+        .collect()
+}
+
+fn xspf_element(body: &str, name: &str) -> Option<String> {
+    let start_tag = format!("<{name}>");
+    let end_tag = format!("</{name}>");
+    let start = body.find(&start_tag)? + start_tag.len();
+    let end = start + body[start..].find(&end_tag)?;
+
+    let value = decode_xml_entities(body[start..end].trim());
+    (!value.is_empty()).then_some(value)
+}
+
+fn decode_xml_entities(value: &str) -> String {
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest
+            .find(';')
+            .map(|end| (&rest[1..end], end))
+            .and_then(|(name, end)| decode_xml_entity(name).map(|ch| (ch, end)));
+        match entity {
+            Some((ch, end)) => {
+                decoded.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_xml_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => name.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+// Entries are relative to the playlist file unless they are absolute paths
+// or URLs; URLs are kept as-is since the parser decodes them itself.
+fn resolve_location(location: &str, base: &Path) -> String {
+    let trimmed = location.trim();
+    if is_url_source(trimmed)
+        || trimmed.contains("://")
+        || Path::new(trimmed).is_absolute()
+        || looks_like_windows_path(trimmed)
+        || trimmed.starts_with('\\')
+    {
+        return trimmed.to_string();
+    }
+
+    base.join(trimmed).to_string_lossy().to_string()
+}
+
+fn xspf_track_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"(?s)<track>(.*?)</track>").expect("valid xspf track regex"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(location: &str, title: Option<&str>) -> PlaylistEntry {
+        PlaylistEntry {
+            location: location.to_string(),
+            title: title.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parse_m3u_reads_extended_titles_and_resolves_relative_entries() {
+        let entries = parse_m3u(
+            "#EXTM3U\n#EXTINF:1420,Frieren - 01\n[SubsPlease] Sousou no Frieren - 01 (1080p).mkv\n\n# comment\n/anime/Frieren - 02.mkv\r\nhttps://cdn.example.com/Frieren%20-%2003.mkv\n",
+            Path::new("/anime/Frieren"),
+        );
+
+        assert_eq!(
+            entries,
+            vec![
+                entry(
+                    "/anime/Frieren/[SubsPlease] Sousou no Frieren - 01 (1080p).mkv",
+                    Some("Frieren - 01")
+                ),
+                entry("/anime/Frieren - 02.mkv", None),
+                entry("https://cdn.example.com/Frieren%20-%2003.mkv", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_pls_orders_numbered_entries_and_pairs_titles() {
+        let entries = parse_pls(
+            "[playlist]\nNumberOfEntries=2\nFile2=Frieren - 02.mkv\nTitle2=Episode 2\nfile1=/anime/Frieren - 01.mkv\nLength1=-1\nVersion=2\n",
+            Path::new("/anime"),
+        );
+
+        assert_eq!(
+            entries,
+            vec![
+                entry("/anime/Frieren - 01.mkv", None),
+                entry("/anime/Frieren - 02.mkv", Some("Episode 2")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_xspf_reads_track_locations_and_decodes_entities() {
+        let entries = parse_xspf(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<playlist xmlns="http://xspf.org/ns/0/" version="1">
+  <trackList>
+    <track>
+      <location>file:///anime/Spy%20x%20Family%20-%2001.mkv</location>
+      <title>Spy &amp; Family &#x2014; 01</title>
+    </track>
+    <track><location>Spy x Family - 02.mkv</location></track>
+    <track><title>No location</title></track>
+  </trackList>
+</playlist>"#,
+            Path::new("/anime"),
+        );
+
+        assert_eq!(
+            entries,
+            vec![
+                entry(
+                    "file:///anime/Spy%20x%20Family%20-%2001.mkv",
+                    Some("Spy & Family \u{2014} 01")
+                ),
+                entry("/anime/Spy x Family - 02.mkv", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_playlist_release_prefers_position_then_title_then_series() {
+        let entries = vec![
+            entry(
+                "/anime/[SubsPlease] Sousou no Frieren - 01 (1080p).mkv",
+                None,
+            ),
+            entry(
+                "/anime/[SubsPlease] Sousou no Frieren - 02 (1080p).mkv",
+                None,
+            ),
+        ];
+
+        let by_position = resolve_playlist_release(
+            &entries,
+            Some(&PlayerPlaybackStatus {
+                playlist_position: Some(1),
+                ..Default::default()
+            }),
+            true,
+        )
+        .expect("entry at the position should parse");
+        assert_eq!(by_position.episode, Some(2));
+
+        // The position belongs to a longer player playlist; only the title is
+        // trusted.
+        let untrusted = resolve_playlist_release(
+            &entries,
+            Some(&PlayerPlaybackStatus {
+                playlist_position: Some(1),
+                media_title: Some("[SubsPlease] Sousou no Frieren - 01 (1080p).mkv".to_string()),
+                ..Default::default()
+            }),
+            false,
+        )
+        .expect("entry matching the title should parse");
+        assert_eq!(untrusted.episode, Some(1));
+
+        let by_title = resolve_playlist_release(
+            &entries,
+            Some(&PlayerPlaybackStatus {
+                media_title: Some("[SubsPlease] Sousou no Frieren - 01 (1080p).mkv".to_string()),
+                ..Default::default()
+            }),
+            true,
+        )
+        .expect("entry matching the title should parse");
+        assert_eq!(by_title.episode, Some(1));
+
+        let series =
+            resolve_playlist_release(&entries, None, true).expect("series should be shared");
+        assert_eq!(series.anime_title, "Sousou no Frieren");
+        assert_eq!(series.episode, None);
+
+        let mixed = vec![
+            entries[0].clone(),
+            entry("/anime/[SubsPlease] Dandadan - 01 (1080p).mkv", None),
+        ];
+        assert!(resolve_playlist_release(&mixed, None, true).is_none());
+    }
+
+    #[test]
+    fn read_playlist_reuses_entries_until_the_file_changes() {
+        let path = std::env::temp_dir().join(format!("kioku-playlist-{}.m3u", std::process::id()));
+        let path_str = path.to_string_lossy().to_string();
+        std::fs::write(&path, "Frieren - 01.mkv\n").expect("playlist should be written");
+
+        let first = read_playlist(&path_str).expect("playlist should be read");
+        let second = read_playlist(&path_str).expect("playlist should be read");
+        assert!(Arc::ptr_eq(&first, &second));
+
+        std::fs::write(&path, "Frieren - 01.mkv\nFrieren - 02.mkv\n")
+            .expect("playlist should be rewritten");
+        let changed = read_playlist(&path_str).expect("playlist should be read");
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(changed.len(), 2);
+        assert!(changed[1].location.ends_with("Frieren - 02.mkv"));
+    }
+
+    #[test]
+    fn find_playlist_arg_reads_option_and_positional_playlists() {
+        let args = |values: &[&str]| {
+            values
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let playlist = |path: &str, follows_position| {
+            Some(PlaylistArg {
+                path: path.to_string(),
+                follows_position,
+            })
+        };
+
+        assert_eq!(
+            find_playlist_arg(&args(&["mpv", "--playlist=/anime/frieren.m3u8"])),
+            playlist("/anime/frieren.m3u8", true)
+        );
+        assert_eq!(
+            find_playlist_arg(&args(&["vlc", "--fullscreen", "\"/anime/Frieren.XSPF\""])),
+            playlist("/anime/Frieren.XSPF", true)
+        );
+        assert_eq!(
+            find_playlist_arg(&args(&["mpv", "https://example.com/live.m3u8"])),
+            None
+        );
+        // mpv numbers its whole playlist, so other media or shuffling move the
+        // position away from the file's own order.
+        assert_eq!(
+            find_playlist_arg(&args(&["mpv", "/anime/op.mkv", "/anime/frieren.m3u"])),
+            playlist("/anime/frieren.m3u", false)
+        );
+        assert_eq!(
+            find_playlist_arg(&args(&["mpv", "--shuffle", "/anime/frieren.m3u"])),
+            playlist("/anime/frieren.m3u", false)
+        );
+        assert_eq!(
+            find_playlist_arg(&args(&[
+                "mpv",
+                "--shuffle=yes",
+                "--no-shuffle",
+                "--playlist",
+                "/anime/frieren.m3u"
+            ])),
+            playlist("/anime/frieren.m3u", true)
+        );
+    }
+}
@@ -0,0 +1,467 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::mem;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use super::processes::{read_linux_process, scan_linux_processes, ProcessSnapshot};
+
+const CN_IDX_PROC: u32 = 1;
+const CN_VAL_PROC: u32 = 1;
+const PROC_CN_MCAST_LISTEN: u32 = 1;
+const NLMSG_HDRLEN: usize = 16;
+const NLMSG_DONE: u16 = 3;
+const CN_MSG_LEN: usize = 20;
+// `what`, `cpu` and `timestamp_ns` precede the event data in `struct proc_event`.
+const PROC_EVENT_DATA_OFFSET: usize = 16;
+const PROC_EVENT_NONE: u32 = 0x0000_0000;
+const PROC_EVENT_FORK: u32 = 0x0000_0001;
+const PROC_EVENT_EXEC: u32 = 0x0000_0002;
+const PROC_EVENT_COMM: u32 = 0x0000_0200;
+const PROC_EVENT_EXIT: u32 = 0x8000_0000;
+const RECEIVE_BUFFER_BYTES: libc::c_int = 1 << 20;
+const SUBSCRIBE_ACK_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProcEvent {
+    // Fork, exec and comm changes all mean the process has to be read again.
+    Changed(u32),
+    Exited(u32),
+    Acknowledged { ack: u32, error: u32 },
+}
+
+struct ProcessTracker {
+    processes: Mutex<BTreeMap<u32, ProcessSnapshot>>,
+    needs_rescan: AtomicBool,
+    connected: AtomicBool,
+}
+
+impl ProcessTracker {
+    fn new(processes: Vec<ProcessSnapshot>) -> Self {
+        Self {
+            processes: Mutex::new(into_process_map(processes)),
+            needs_rescan: AtomicBool::new(false),
+            connected: AtomicBool::new(true),
+        }
+    }
+
+    fn apply(&self, event: ProcEvent) {
+        let Ok(mut processes) = self.processes.lock() else {
+            return;
+        };
+
+        match event {
+            ProcEvent::Changed(pid) => match read_linux_process(pid) {
+                Some(process) => {
+                    processes.insert(pid, process);
+                }
+                None => {
+                    processes.remove(&pid);
+                }
+            },
+            ProcEvent::Exited(pid) => {
+                processes.remove(&pid);
+            }
+            ProcEvent::Acknowledged { .. } => {}
+        }
+    }
+
+    fn snapshot(&self) -> Option<Vec<ProcessSnapshot>> {
+        if !self.connected.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let mut processes = self.processes.lock().ok()?;
+        if self.needs_rescan.swap(false, Ordering::AcqRel) {
+            match scan_linux_processes() {
+                Ok(scanned) => *processes = into_process_map(scanned),
+                Err(_) => {
+                    self.needs_rescan.store(true, Ordering::Release);
+                    return None;
+                }
+            }
+        }
+
+        Some(processes.values().cloned().collect())
+    }
+}
+
+// Returns `None` when the proc connector is unavailable (usually missing
+// CAP_NET_ADMIN) or has stopped, so callers can fall back to scanning /proc.
+// There is no pidfd or inotify fallback: inotify does not report changes under
+// /proc, and a pidfd can only be opened for a process that is already known, so
+// neither can announce new processes without the full scan.
+pub(crate) fn tracked_processes() -> Option<Vec<ProcessSnapshot>> {
+    static TRACKER: OnceLock<Option<Arc<ProcessTracker>>> = OnceLock::new();
+    TRACKER
+        .get_or_init(|| match start_tracker() {
+            Ok(tracker) => Some(tracker),
+            Err(error) => {
+                eprintln!("process event connector unavailable, polling /proc instead: {error}");
+                None
+            }
+        })
+        .as_ref()?
+        .snapshot()
+}
+
+fn start_tracker() -> Result<Arc<ProcessTracker>, String> {
+    // Subscribe before the initial scan so processes started in between are
+    // queued on the socket instead of being missed.
+    let socket = subscribe_to_proc_events()?;
+    let tracker = Arc::new(ProcessTracker::new(scan_linux_processes()?));
+
+    let thread_tracker = Arc::clone(&tracker);
+    std::thread::Builder::new()
+        .name("proc-connector".to_string())
+        .spawn(move || run_event_loop(socket, thread_tracker))
+        .map_err(|error| format!("Failed to start process event thread: {error}"))?;
+
+    Ok(tracker)
+}
+
+fn run_event_loop(socket: OwnedFd, tracker: Arc<ProcessTracker>) {
+    let mut buffer = vec![0_u8; 8192];
+
+    loop {
+        match receive(&socket, &mut buffer) {
+            Ok(length) => {
+                for event in parse_proc_events(&buffer[..length]) {
+                    tracker.apply(event);
+                }
+            }
+            // The socket overflowed and events were dropped, so the cache can
+            // no longer be trusted until the next full scan.
+            Err(error) if error.raw_os_error() == Some(libc::ENOBUFS) => {
+                tracker.needs_rescan.store(true, Ordering::Release);
+            }
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock
+                ) => {}
+            Err(error) => {
+                eprintln!("process event connector stopped, polling /proc instead: {error}");
+                tracker.connected.store(false, Ordering::Release);
+                return;
+            }
+        }
+    }
+}
+
+fn subscribe_to_proc_events() -> Result<OwnedFd, String> {
+    // SAFETY: `socket` takes no pointers; the result is checked before use.
+    let raw = unsafe {
+        libc::socket(
+            libc::AF_NETLINK,
+            libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+            libc::NETLINK_CONNECTOR,
+        )
+    };
+    if raw < 0 {
+        return Err(format!(
+            "Failed to open proc connector socket: {}",
+            io::Error::last_os_error()
+        ));
+    }
+    // SAFETY: `raw` is a freshly opened descriptor that nothing else owns.
+    let socket = unsafe { OwnedFd::from_raw_fd(raw) };
+
+    // SAFETY: `sockaddr_nl` is a plain C struct for which all zeroes is valid.
+    let mut address: libc::sockaddr_nl = unsafe { mem::zeroed() };
+    address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    address.nl_groups = CN_IDX_PROC;
+    // SAFETY: `address` outlives the call and the length passed is its size.
+    let bound = unsafe {
+        libc::bind(
+            socket.as_raw_fd(),
+            &address as *const libc::sockaddr_nl as *const libc::sockaddr,
+            mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+        )
+    };
+    if bound < 0 {
+        return Err(format!(
+            "Failed to bind proc connector socket: {}",
+            io::Error::last_os_error()
+        ));
+    }
+
+    // A larger buffer makes overflows (and the rescans they trigger) rare on
+    // busy machines; the kernel default still works if this is refused.
+    let _ = set_socket_option(&socket, libc::SO_RCVBUF, &RECEIVE_BUFFER_BYTES);
+    set_receive_timeout(&socket, Some(SUBSCRIBE_ACK_TIMEOUT))?;
+
+    let ack = std::process::id();
+    let message = listen_message(ack);
+    // SAFETY: the pointer and length describe `message`, which outlives the call.
+    let sent = unsafe {
+        libc::send(
+            socket.as_raw_fd(),
+            message.as_ptr() as *const libc::c_void,
+            message.len(),
+            0,
+        )
+    };
+    if sent < 0 {
+        return Err(format!(
+            "Failed to subscribe to process events: {}",
+            io::Error::last_os_error()
+        ));
+    }
+
+    wait_for_subscription_ack(&socket, ack)?;
+    set_receive_timeout(&socket, None)?;
+
+    Ok(socket)
+}
+
+// The kernel acknowledges the listen request with `ack + 1` and an errno,
+// and stays silent when we are not allowed to listen at all.
+fn wait_for_subscription_ack(socket: &OwnedFd, ack: u32) -> Result<(), String> {
+    let deadline = Instant::now() + SUBSCRIBE_ACK_TIMEOUT;
+    let mut buffer = vec![0_u8; 8192];
+
+    while Instant::now() < deadline {
+        let length = match receive(socket, &mut buffer) {
+            Ok(length) => length,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+            Err(error) => {
+                return Err(format!(
+                    "Failed to read process event acknowledgement: {error}"
+                ))
+            }
+        };
+
+        for event in parse_proc_events(&buffer[..length]) {
+            match event {
+                ProcEvent::Acknowledged {
+                    ack: received,
+                    error: 0,
+                } if received == ack.wrapping_add(1) => {
+                    return Ok(());
+                }
+                ProcEvent::Acknowledged {
+                    ack: received,
+                    error,
+                } if received == ack.wrapping_add(1) => {
+                    return Err(format!(
+                        "Process event subscription was refused: {}",
+                        io::Error::from_raw_os_error(error as i32)
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Err("Process event subscription was not acknowledged".to_string())
+}
+
+fn receive(socket: &OwnedFd, buffer: &mut [u8]) -> io::Result<usize> {
+    // SAFETY: the kernel writes at most `buffer.len()` bytes into `buffer`.
+    let received = unsafe {
+        libc::recv(
+            socket.as_raw_fd(),
+            buffer.as_mut_ptr() as *mut libc::c_void,
+            buffer.len(),
+            0,
+        )
+    };
+    if received < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(received as usize)
+}
+
+fn set_receive_timeout(socket: &OwnedFd, timeout: Option<Duration>) -> Result<(), String> {
+    let timeout = timeout.unwrap_or_default();
+    let value = libc::timeval {
+        tv_sec: timeout.as_secs() as libc::time_t,
+        tv_usec: timeout.subsec_micros() as libc::suseconds_t,
+    };
+
+    set_socket_option(socket, libc::SO_RCVTIMEO, &value)
+        .map_err(|error| format!("Failed to configure proc connector socket: {error}"))
+}
+
+fn set_socket_option<T>(socket: &OwnedFd, option: libc::c_int, value: &T) -> io::Result<()> {
+    // SAFETY: `value` is borrowed for the call and the length passed is its size;
+    // callers pass the type each option expects.
+    let result = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            option,
+            value as *const T as *const libc::c_void,
+            mem::size_of::<T>() as libc::socklen_t,
+        )
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+fn listen_message(ack: u32) -> Vec<u8> {
+    let payload = PROC_CN_MCAST_LISTEN.to_ne_bytes();
+    let total_length = NLMSG_HDRLEN + CN_MSG_LEN + payload.len();
+    let mut message = Vec::with_capacity(total_length);
+
+    message.extend_from_slice(&(total_length as u32).to_ne_bytes());
+    message.extend_from_slice(&NLMSG_DONE.to_ne_bytes());
+    message.extend_from_slice(&0_u16.to_ne_bytes());
+    message.extend_from_slice(&0_u32.to_ne_bytes());
+    message.extend_from_slice(&std::process::id().to_ne_bytes());
+
+    message.extend_from_slice(&CN_IDX_PROC.to_ne_bytes());
+    message.extend_from_slice(&CN_VAL_PROC.to_ne_bytes());
+    message.extend_from_slice(&0_u32.to_ne_bytes());
+    message.extend_from_slice(&ack.to_ne_bytes());
+    message.extend_from_slice(&(payload.len() as u16).to_ne_bytes());
+    message.extend_from_slice(&0_u16.to_ne_bytes());
+
+    message.extend_from_slice(&payload);
+    message
+}
+
+fn parse_proc_events(buffer: &[u8]) -> Vec<ProcEvent> {
+    let mut events = Vec::new();
+    let mut offset = 0;
+
+    while let Some(length) = read_u32(buffer, offset) {
+        let length = length as usize;
+        if length < NLMSG_HDRLEN || offset + length > buffer.len() {
+            break;
+        }
+
+        if let Some(event) =
+            parse_connector_message(&buffer[offset + NLMSG_HDRLEN..offset + length])
+        {
+            events.push(event);
+        }
+
+        offset += (length + 3) & !3;
+    }
+
+    events
+}
+
+fn parse_connector_message(message: &[u8]) -> Option<ProcEvent> {
+    if read_u32(message, 0)? != CN_IDX_PROC || read_u32(message, 4)? != CN_VAL_PROC {
+        return None;
+    }
+
+    let event = message.get(CN_MSG_LEN..)?;
+    let data = event.get(PROC_EVENT_DATA_OFFSET..)?;
+    // Thread events report a `pid` that differs from the `tgid`; only whole
+    // processes are tracked.
+    let process_id = |pid_offset: usize| {
+        let pid = read_u32(data, pid_offset)?;
+        let tgid = read_u32(data, pid_offset + 4)?;
+        (pid == tgid).then_some(tgid)
+    };
+
+    match read_u32(event, 0)? {
+        PROC_EVENT_NONE => Some(ProcEvent::Acknowledged {
+            ack: read_u32(message, 12)?,
+            error: read_u32(data, 0)?,
+        }),
+        PROC_EVENT_FORK => process_id(8).map(ProcEvent::Changed),
+        PROC_EVENT_EXEC | PROC_EVENT_COMM => process_id(0).map(ProcEvent::Changed),
+        PROC_EVENT_EXIT => process_id(0).map(ProcEvent::Exited),
+        _ => None,
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let chunk = bytes.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_ne_bytes(chunk.try_into().ok()?))
+}
+
+fn into_process_map(processes: Vec<ProcessSnapshot>) -> BTreeMap<u32, ProcessSnapshot> {
+    processes
+        .into_iter()
+        .map(|process| (process.pid, process))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proc_event_message(what: u32, ack: u32, data: &[u32]) -> Vec<u8> {
+        let data_bytes: Vec<u8> = data.iter().flat_map(|value| value.to_ne_bytes()).collect();
+        let event_length = PROC_EVENT_DATA_OFFSET + data_bytes.len();
+        let total_length = NLMSG_HDRLEN + CN_MSG_LEN + event_length;
+
+        let mut message = Vec::new();
+        message.extend_from_slice(&(total_length as u32).to_ne_bytes());
+        message.extend_from_slice(&NLMSG_DONE.to_ne_bytes());
+        message.extend_from_slice(&[0; 10]);
+        for value in [CN_IDX_PROC, CN_VAL_PROC, 0, ack] {
+            message.extend_from_slice(&value.to_ne_bytes());
+        }
+        message.extend_from_slice(&(event_length as u16).to_ne_bytes());
+        message.extend_from_slice(&0_u16.to_ne_bytes());
+        message.extend_from_slice(&what.to_ne_bytes());
+        message.extend_from_slice(&[0; PROC_EVENT_DATA_OFFSET - 4]);
+        message.extend_from_slice(&data_bytes);
+        message
+    }
+
+    #[test]
+    fn parse_proc_events_tracks_processes_and_skips_thread_events() {
+        let mut buffer = proc_event_message(PROC_EVENT_FORK, 0, &[1, 1, 42, 42]);
+        buffer.extend(proc_event_message(PROC_EVENT_FORK, 0, &[42, 42, 43, 42]));
+        buffer.extend(proc_event_message(PROC_EVENT_EXEC, 0, &[42, 42]));
+        buffer.extend(proc_event_message(
+            PROC_EVENT_COMM,
+            0,
+            &[43, 42, 0, 0, 0, 0],
+        ));
+        buffer.extend(proc_event_message(
+            PROC_EVENT_EXIT,
+            0,
+            &[42, 42, 0, 0, 1, 1],
+        ));
+        buffer.extend(proc_event_message(PROC_EVENT_NONE, 8, &[1]));
+        // A truncated trailing message is ignored rather than misread.
+        buffer.extend(&proc_event_message(PROC_EVENT_EXIT, 0, &[7, 7])[..20]);
+
+        assert_eq!(
+            parse_proc_events(&buffer),
+            vec![
+                ProcEvent::Changed(42),
+                ProcEvent::Changed(42),
+                ProcEvent::Exited(42),
+                ProcEvent::Acknowledged { ack: 8, error: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn tracker_reads_changed_processes_and_drops_exited_ones() {
+        let pid = std::process::id();
+        let tracker = ProcessTracker::new(Vec::new());
+
+        tracker.apply(ProcEvent::Changed(pid));
+        let processes = tracker.snapshot().expect("tracker should be connected");
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].pid, pid);
+        assert!(!processes[0].name.is_empty());
+
+        tracker.apply(ProcEvent::Exited(pid));
+        assert!(tracker
+            .snapshot()
+            .expect("tracker should be connected")
+            .is_empty());
+
+        tracker.connected.store(false, Ordering::Release);
+        assert!(tracker.snapshot().is_none());
+    }
+}
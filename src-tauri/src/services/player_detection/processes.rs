@@ -1,9 +1,13 @@
 use serde::Deserialize;
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
-#[cfg(any(windows, target_os = "macos"))]
+#[cfg(any(windows, target_os = "linux", target_os = "macos"))]
 use std::process::Command;
 
+#[cfg(target_os = "linux")]
+use super::parser::has_video_extension;
+#[cfg(target_os = "linux")]
+use super::proc_connector;
 use super::util::split_command_line;
 
 #[derive(Debug, Clone)]
@@ -26,13 +30,13 @@ struct WindowsProcess {
 #[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x0800_0000;
 
+// Every process is listed, as on the other platforms, so custom player
+// aliases are matched by the detector like the built-in ones.
 #[cfg(windows)]
 pub(crate) fn list_processes() -> Result<Vec<ProcessSnapshot>, String> {
     let script = r#"
     [Console]::OutputEncoding = [System.Text.Encoding]::UTF8
-    $targets = @('mpv.exe','mpvnet.exe','mpc-hc.exe','mpc-hc64.exe','mpc-be.exe','mpc-be64.exe')
-    $nameFilter = ($targets | ForEach-Object { "Name='$_'" }) -join ' OR '
-    $query = "SELECT ProcessId, Name, CommandLine FROM Win32_Process WHERE $nameFilter"
+    $query = "SELECT ProcessId, Name, CommandLine FROM Win32_Process WHERE ProcessId <> 0"
     $processes = Get-CimInstance -Query $query
 
     if ($null -eq $processes) {
@@ -117,8 +121,19 @@ fn parse_windows_process_snapshots(stdout: &str) -> Result<Vec<ProcessSnapshot>,
     Ok(windows_processes_to_snapshots(parsed))
 }
 
+// Uses the proc connector's incremental view when the kernel lets us subscribe
+// to process events and falls back to scanning /proc on every call otherwise.
 #[cfg(target_os = "linux")]
 pub(crate) fn list_processes() -> Result<Vec<ProcessSnapshot>, String> {
+    if let Some(processes) = proc_connector::tracked_processes() {
+        return Ok(processes);
+    }
+
+    scan_linux_processes()
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn scan_linux_processes() -> Result<Vec<ProcessSnapshot>, String> {
     let entries = std::fs::read_dir("/proc")
         .map_err(|error| format!("Failed to list /proc entries: {error}"))?;
 
@@ -135,31 +150,57 @@ pub(crate) fn list_processes() -> Result<Vec<ProcessSnapshot>, String> {
             continue;
         };
 
-        let name = match std::fs::read_to_string(format!("/proc/{pid}/comm")) {
-            Ok(value) => value.trim().to_string(),
-            Err(_) => continue,
-        };
+        if let Some(process) = read_linux_process(pid) {
+            processes.push(process);
+        }
+    }
 
-        let args = match std::fs::read(format!("/proc/{pid}/cmdline")) {
-            Ok(bytes) => parse_linux_cmdline(&bytes),
-            Err(_) => continue,
-        };
+    Ok(processes)
+}
 
-        let command_line = if args.is_empty() {
-            name.clone()
-        } else {
-            args.join(" ")
-        };
+#[cfg(target_os = "linux")]
+pub(crate) fn read_linux_process(pid: u32) -> Option<ProcessSnapshot> {
+    let name = std::fs::read_to_string(format!("/proc/{pid}/comm"))
+        .ok()?
+        .trim()
+        .to_string();
+    let args = parse_linux_cmdline(&std::fs::read(format!("/proc/{pid}/cmdline")).ok()?);
+
+    let command_line = if args.is_empty() {
+        name.clone()
+    } else {
+        args.join(" ")
+    };
+
+    Some(ProcessSnapshot {
+        pid,
+        name,
+        command_line,
+        args,
+    })
+}
 
-        processes.push(ProcessSnapshot {
-            pid,
-            name,
-            command_line,
-            args,
-        });
-    }
+// Players launched on a folder or playlist carry no video argument, so the file
+// they are playing is looked up among their open descriptors instead. The
+// kernel reuses the lowest free descriptor and `/proc` keeps no open time, so
+// when several videos are open there is no telling which one is playing and
+// none is reported.
+#[cfg(target_os = "linux")]
+pub(crate) fn find_open_media_file(pid: u32) -> Option<String> {
+    let entries = std::fs::read_dir(format!("/proc/{pid}/fd")).ok()?;
+
+    let mut open_media = entries.filter_map(Result::ok).filter_map(|entry| {
+        let target = std::fs::read_link(entry.path()).ok()?;
+        let target = target.to_str()?;
+        (target.starts_with('/') && has_video_extension(target)).then(|| target.to_string())
+    });
+    let media = open_media.next()?;
+    open_media.all(|other| other == media).then_some(media)
+}
 
-    Ok(processes)
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn find_open_media_file(_pid: u32) -> Option<String> {
+    None
 }
 
 #[cfg(target_os = "linux")]
@@ -234,6 +275,83 @@ pub(crate) fn list_processes() -> Result<Vec<ProcessSnapshot>, String> {
     Ok(Vec::new())
 }
 
+#[cfg(windows)]
+pub(crate) fn focused_process_id() -> Option<u32> {
+    // SAFETY: GetForegroundWindow takes no arguments and may return null, which
+    // GetWindowThreadProcessId accepts; `process_id` outlives the call.
+    let process_id = unsafe {
+        let window = GetForegroundWindow();
+        if window.is_null() {
+            return None;
+        }
+
+        let mut process_id = 0_u32;
+        GetWindowThreadProcessId(window, &mut process_id);
+        process_id
+    };
+
+    (process_id != 0).then_some(process_id)
+}
+
+#[cfg(windows)]
+#[link(name = "user32")]
+extern "system" {
+    fn GetForegroundWindow() -> *mut std::ffi::c_void;
+    fn GetWindowThreadProcessId(window: *mut std::ffi::c_void, process_id: *mut u32) -> u32;
+}
+
+// Needs an X11 (or XWayland) window manager that publishes EWMH hints; on
+// other sessions there is simply no focused player.
+#[cfg(target_os = "linux")]
+pub(crate) fn focused_process_id() -> Option<u32> {
+    let xprop = |args: &[&str]| {
+        let output = Command::new("xprop").args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    };
+
+    let window_id = parse_xprop_active_window(&xprop(&["-root", "_NET_ACTIVE_WINDOW"])?)?;
+    parse_xprop_window_pid(&xprop(&["-id", &window_id, "_NET_WM_PID"])?)
+}
+
+#[cfg(target_os = "linux")]
+fn parse_xprop_active_window(output: &str) -> Option<String> {
+    let window_id = output.split_whitespace().last()?;
+    (window_id.starts_with("0x") && window_id != "0x0").then(|| window_id.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn parse_xprop_window_pid(output: &str) -> Option<u32> {
+    output.split('=').nth(1)?.trim().parse().ok()
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn focused_process_id() -> Option<u32> {
+    let output = Command::new("lsappinfo").arg("front").output().ok()?;
+    let application = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if application.is_empty() {
+        return None;
+    }
+
+    let output = Command::new("lsappinfo")
+        .args(["info", "-only", "pid", &application])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .split('=')
+        .nth(1)?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+pub(crate) fn focused_process_id() -> Option<u32> {
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -313,6 +431,79 @@ mod tests {
         assert!(snapshots[1].args.is_empty());
     }
 
+    // Holds `stdin` and `stdout` open in a child process so the scan does not
+    // depend on what other tests open in this one.
+    #[cfg(target_os = "linux")]
+    fn spawn_holding_open(
+        stdin: &std::path::Path,
+        stdout: &std::path::Path,
+    ) -> std::process::Child {
+        std::process::Command::new("sleep")
+            .arg("30")
+            .stdin(std::fs::File::open(stdin).expect("file should open"))
+            .stdout(std::fs::File::create(stdout).expect("file should open"))
+            .spawn()
+            .expect("child should spawn")
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn find_open_media_file_reads_the_single_video_a_process_holds_open() {
+        let nonce = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        let directory =
+            std::env::temp_dir().join(format!("kioku-fd-{}-{nonce}", std::process::id()));
+        std::fs::create_dir_all(&directory).expect("temp dir should be created");
+        let subtitles = directory.join("Frieren - 01.ass");
+        let video = directory.join("Frieren - 01.mkv");
+        let other_video = directory.join("Frieren - 02.mkv");
+        for path in [&subtitles, &video, &other_video] {
+            std::fs::write(path, b"").expect("fixture should be written");
+        }
+
+        let cases = [
+            (&subtitles, &subtitles, None),
+            (
+                &video,
+                &subtitles,
+                Some(video.to_string_lossy().to_string()),
+            ),
+            (&video, &video, Some(video.to_string_lossy().to_string())),
+            (&video, &other_video, None),
+        ];
+        for (stdin, stdout, expected) in cases {
+            let mut child = spawn_holding_open(stdin, stdout);
+            let found = find_open_media_file(child.id());
+            let _ = child.kill();
+            let _ = child.wait();
+
+            assert_eq!(found, expected, "{stdin:?} and {stdout:?}");
+        }
+
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parse_xprop_output_reads_active_window_and_its_pid() {
+        assert_eq!(
+            parse_xprop_active_window("_NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007\n")
+                .as_deref(),
+            Some("0x3a00007")
+        );
+        assert_eq!(
+            parse_xprop_active_window("_NET_ACTIVE_WINDOW(WINDOW): window id # 0x0"),
+            None
+        );
+        assert_eq!(
+            parse_xprop_window_pid("_NET_WM_PID(CARDINAL) = 48213\n"),
+            Some(48213)
+        );
+        assert_eq!(parse_xprop_window_pid("_NET_WM_PID:  not found."), None);
+    }
+
     #[cfg(target_os = "linux")]
     #[test]
     fn parse_linux_cmdline_splits_null_delimited_arguments() {
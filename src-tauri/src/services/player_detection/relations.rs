@@ -0,0 +1,417 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use tokio::sync::Mutex;
+
+use crate::services::anilist::{fetch_anilist_media_relations, AniListMediaRelations};
+
+use super::matcher::StoredAnimeListItem;
+use super::types::ListEntryMatch;
+
+const RELATIONS_FILE_NAME: &str = "anilist-relations.json";
+// Airing shows gain episodes and sequels, so cached nodes are refetched
+// after a week.
+const RELATION_CACHE_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+// Bounds the requests a single lookup may make for very long franchises.
+const MAX_RELATION_FETCHES: usize = 24;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedRelations {
+    id: u64,
+    title: String,
+    episodes: Option<u32>,
+    prequel_id: Option<u64>,
+    sequel_id: Option<u64>,
+    fetched_at: u64,
+}
+
+impl CachedRelations {
+    fn from_relations(relations: AniListMediaRelations, fetched_at: u64) -> Self {
+        Self {
+            id: relations.id,
+            title: relations.title,
+            episodes: relations.episodes,
+            prequel_id: relations.prequel_id,
+            sequel_id: relations.sequel_id,
+            fetched_at,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ChainLookup {
+    Missing(u64),
+    Unmapped,
+    Mapped { media_id: u64, episode: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SeasonalEpisode {
+    pub media_id: u64,
+    pub title: String,
+    pub total_episodes: u32,
+    pub episode: u32,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn fresh_node(
+    nodes: &HashMap<u64, CachedRelations>,
+    media_id: u64,
+    now: u64,
+) -> Result<&CachedRelations, ChainLookup> {
+    nodes
+        .get(&media_id)
+        .filter(|node| now.saturating_sub(node.fetched_at) < RELATION_CACHE_TTL_SECS)
+        .ok_or(ChainLookup::Missing(media_id))
+}
+
+// Treats `episode` as counted from the first season of the matched media's
+// franchise and walks the sequel chain until the number fits a season. A
+// season with an unknown episode count is still airing and takes whatever is
+// left.
+fn map_absolute_episode(
+    nodes: &HashMap<u64, CachedRelations>,
+    media_id: u64,
+    episode: u32,
+    now: u64,
+) -> ChainLookup {
+    let lookup = || -> Result<ChainLookup, ChainLookup> {
+        let matched = fresh_node(nodes, media_id, now)?;
+        match matched.episodes {
+            Some(total) if episode > total => {}
+            _ => return Ok(ChainLookup::Unmapped),
+        }
+
+        let mut visited = HashSet::from([matched.id]);
+        let mut current = matched;
+        while let Some(prequel_id) = current.prequel_id {
+            if !visited.insert(prequel_id) {
+                return Ok(ChainLookup::Unmapped);
+            }
+            current = fresh_node(nodes, prequel_id, now)?;
+        }
+
+        visited.clear();
+        let mut remaining = episode;
+        loop {
+            if !visited.insert(current.id) {
+                return Ok(ChainLookup::Unmapped);
+            }
+
+            match current.episodes {
+                Some(total) if remaining > total => remaining -= total,
+                _ => {
+                    return Ok(ChainLookup::Mapped {
+                        media_id: current.id,
+                        episode: remaining,
+                    })
+                }
+            }
+
+            let Some(sequel_id) = current.sequel_id else {
+                return Ok(ChainLookup::Unmapped);
+            };
+            current = fresh_node(nodes, sequel_id, now)?;
+        }
+    };
+
+    lookup().unwrap_or_else(|missing| missing)
+}
+
+fn read_relations_file(path: &Path) -> Result<HashMap<u64, CachedRelations>, String> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(error) => return Err(format!("Failed to read AniList relations cache: {error}")),
+    };
+    let nodes: Vec<CachedRelations> = serde_json::from_str(&contents)
+        .map_err(|error| format!("Failed to parse AniList relations cache: {error}"))?;
+
+    Ok(nodes.into_iter().map(|node| (node.id, node)).collect())
+}
+
+fn write_relations_file(path: &Path, nodes: &HashMap<u64, CachedRelations>) -> Result<(), String> {
+    let mut nodes: Vec<&CachedRelations> = nodes.values().collect();
+    nodes.sort_by_key(|node| node.id);
+    let contents = serde_json::to_string(&nodes)
+        .map_err(|error| format!("Failed to serialize AniList relations cache: {error}"))?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed to save AniList relations cache: {error}"))?;
+    }
+    let temporary_path = path.with_extension("json.tmp");
+    std::fs::write(&temporary_path, contents)
+        .and_then(|_| std::fs::rename(&temporary_path, path))
+        .map_err(|error| format!("Failed to save AniList relations cache: {error}"))
+}
+
+pub(crate) fn seasonal_list_match(
+    list_match: &ListEntryMatch,
+    seasonal: SeasonalEpisode,
+    items: &[StoredAnimeListItem],
+) -> ListEntryMatch {
+    let item = items.iter().find(|item| item.id == seasonal.media_id);
+
+    ListEntryMatch {
+        media_id: seasonal.media_id,
+        entry_id: item.and_then(|item| item.entry_id),
+        title: item
+            .map(|item| item.title.clone())
+            .unwrap_or(seasonal.title),
+        total_episodes: item
+            .map(|item| item.total_episodes)
+            .filter(|total| *total > 0)
+            .unwrap_or(seasonal.total_episodes),
+        episode: Some(seasonal.episode),
+        ..list_match.clone()
+    }
+}
+
+// Local copy of the AniList sequel/prequel graph, filled on demand.
+pub struct AnimeRelationsState {
+    path: Option<PathBuf>,
+    nodes: Mutex<HashMap<u64, CachedRelations>>,
+}
+
+impl AnimeRelationsState {
+    fn from_path(path: Option<PathBuf>) -> Self {
+        let nodes = match path.as_deref().map(read_relations_file) {
+            Some(Ok(nodes)) => nodes,
+            Some(Err(error)) => {
+                eprintln!("{error}");
+                HashMap::new()
+            }
+            None => HashMap::new(),
+        };
+
+        Self {
+            path,
+            nodes: Mutex::new(nodes),
+        }
+    }
+
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = match app.path().app_local_data_dir() {
+            Ok(dir) => Some(dir.join(RELATIONS_FILE_NAME)),
+            Err(error) => {
+                eprintln!("failed to resolve AniList relations cache location: {error}");
+                None
+            }
+        };
+
+        Self::from_path(path)
+    }
+
+    async fn lookup(&self, media_id: u64, episode: u32) -> ChainLookup {
+        map_absolute_episode(&*self.nodes.lock().await, media_id, episode, unix_now())
+    }
+
+    async fn seasonal_episode(&self, media_id: u64, episode: u32) -> Option<SeasonalEpisode> {
+        let nodes = self.nodes.lock().await;
+        let node = nodes.get(&media_id)?;
+
+        Some(SeasonalEpisode {
+            media_id,
+            title: node.title.clone(),
+            total_episodes: node.episodes.unwrap_or_default(),
+            episode,
+        })
+    }
+
+    fn persist(&self, nodes: &HashMap<u64, CachedRelations>) {
+        let Some(path) = self.path.as_deref() else {
+            return;
+        };
+
+        if let Err(error) = write_relations_file(path, nodes) {
+            eprintln!("{error}");
+        }
+    }
+
+    // Translates an absolute episode number of an AniList media into the
+    // season and relative episode it belongs to. `None` means the number
+    // already fits the media or the relation chain cannot place it.
+    pub(crate) async fn to_seasonal_episode(
+        &self,
+        app: &tauri::AppHandle,
+        media_id: u64,
+        episode: u32,
+    ) -> Result<Option<SeasonalEpisode>, String> {
+        let mut fetched = false;
+        let mut result = Ok(None);
+
+        for _ in 0..=MAX_RELATION_FETCHES {
+            match self.lookup(media_id, episode).await {
+                ChainLookup::Unmapped => break,
+                ChainLookup::Mapped { media_id, episode } => {
+                    result = Ok(self.seasonal_episode(media_id, episode).await);
+                    break;
+                }
+                ChainLookup::Missing(missing_id) => {
+                    match fetch_anilist_media_relations(app, missing_id).await {
+                        Ok(relations) => {
+                            self.nodes.lock().await.insert(
+                                missing_id,
+                                CachedRelations::from_relations(relations, unix_now()),
+                            );
+                            fetched = true;
+                        }
+                        Err(error) => {
+                            result = Err(error);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        if fetched {
+            self.persist(&*self.nodes.lock().await);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::types::ListMatchSource;
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn node(
+        id: u64,
+        episodes: Option<u32>,
+        prequel_id: Option<u64>,
+        sequel_id: Option<u64>,
+    ) -> CachedRelations {
+        CachedRelations {
+            id,
+            title: format!("Season {id}"),
+            episodes,
+            prequel_id,
+            sequel_id,
+            fetched_at: NOW,
+        }
+    }
+
+    fn graph(nodes: impl IntoIterator<Item = CachedRelations>) -> HashMap<u64, CachedRelations> {
+        nodes.into_iter().map(|node| (node.id, node)).collect()
+    }
+
+    #[test]
+    fn map_absolute_episode_walks_the_sequel_chain_from_the_first_season() {
+        let nodes = graph([
+            node(1, Some(25), None, Some(2)),
+            node(2, Some(12), Some(1), Some(3)),
+            node(3, None, Some(2), None),
+        ]);
+
+        assert_eq!(
+            map_absolute_episode(&nodes, 1, 12, NOW),
+            ChainLookup::Unmapped
+        );
+        assert_eq!(
+            map_absolute_episode(&nodes, 1, 26, NOW),
+            ChainLookup::Mapped {
+                media_id: 2,
+                episode: 1
+            }
+        );
+        assert_eq!(
+            map_absolute_episode(&nodes, 2, 40, NOW),
+            ChainLookup::Mapped {
+                media_id: 3,
+                episode: 3
+            }
+        );
+        // Still airing, so any number fits.
+        assert_eq!(
+            map_absolute_episode(&nodes, 3, 1089, NOW),
+            ChainLookup::Unmapped
+        );
+
+        let finished = graph([
+            node(1, Some(25), None, Some(2)),
+            node(2, Some(12), Some(1), None),
+        ]);
+        assert_eq!(
+            map_absolute_episode(&finished, 1, 38, NOW),
+            ChainLookup::Unmapped
+        );
+    }
+
+    #[test]
+    fn map_absolute_episode_requests_missing_or_stale_nodes_and_stops_on_cycles() {
+        let mut nodes = graph([node(2, Some(12), Some(1), None)]);
+        assert_eq!(
+            map_absolute_episode(&nodes, 2, 30, NOW),
+            ChainLookup::Missing(1)
+        );
+
+        nodes.insert(1, node(1, Some(12), None, Some(2)));
+        assert_eq!(
+            map_absolute_episode(&nodes, 2, 30, NOW + RELATION_CACHE_TTL_SECS),
+            ChainLookup::Missing(2)
+        );
+
+        let cyclic = graph([
+            node(1, Some(12), Some(2), Some(2)),
+            node(2, Some(12), Some(1), Some(1)),
+        ]);
+        assert_eq!(
+            map_absolute_episode(&cyclic, 1, 30, NOW),
+            ChainLookup::Unmapped
+        );
+    }
+
+    #[test]
+    fn seasonal_list_match_points_at_the_sequel_entry() {
+        let list_match = ListEntryMatch {
+            provider_id: "anilist".to_string(),
+            media_id: 1,
+            entry_id: Some(100),
+            title: "Season 1".to_string(),
+            total_episodes: 25,
+            episode: Some(26),
+            confidence: 0.9,
+            source: ListMatchSource::List,
+        };
+        let seasonal = SeasonalEpisode {
+            media_id: 2,
+            title: "Season 2".to_string(),
+            total_episodes: 12,
+            episode: 1,
+        };
+
+        let mapped = seasonal_list_match(&list_match, seasonal.clone(), &[]);
+        assert_eq!(mapped.media_id, 2);
+        assert_eq!(mapped.entry_id, None);
+        assert_eq!(mapped.title, "Season 2");
+        assert_eq!(mapped.episode, Some(1));
+        assert_eq!(mapped.confidence, 0.9);
+
+        let items = [StoredAnimeListItem {
+            id: 2,
+            entry_id: Some(200),
+            title: "Shingeki no Kyojin Season 2".to_string(),
+            ..Default::default()
+        }];
+        let mapped = seasonal_list_match(&list_match, seasonal, &items);
+        assert_eq!(mapped.entry_id, Some(200));
+        assert_eq!(mapped.title, "Shingeki no Kyojin Season 2");
+        assert_eq!(mapped.total_episodes, 12);
+    }
+}
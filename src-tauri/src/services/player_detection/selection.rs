@@ -0,0 +1,209 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use super::types::{DetectionCandidate, DetectionSelectionConfig, SelectionPolicy};
+
+// Process start times are not available on every platform, so "most recent"
+// means first seen by the observer. Processes found in the same poll, such as
+// the first one after launch, fall back to the higher pid.
+fn recency(candidate: &DetectionCandidate, first_seen: &HashMap<u32, u64>) -> (u64, u32) {
+    (
+        first_seen
+            .get(&candidate.process_id)
+            .copied()
+            .unwrap_or_default(),
+        candidate.process_id,
+    )
+}
+
+// Picks the candidate the observer follows. Focus and pins only say which
+// player to prefer; when they point at nothing playing, the observer stays on
+// `current_process_id` rather than jumping between players.
+pub(crate) fn select_candidate<'a>(
+    candidates: &[&'a DetectionCandidate],
+    selection: &DetectionSelectionConfig,
+    current_process_id: Option<u32>,
+    focused_process_id: Option<u32>,
+    first_seen: &HashMap<u32, u64>,
+) -> Option<&'a DetectionCandidate> {
+    let by_process_id = |process_id: Option<u32>| {
+        process_id.and_then(|process_id| {
+            candidates
+                .iter()
+                .copied()
+                .find(|candidate| candidate.process_id == process_id)
+        })
+    };
+    let most_recent = || {
+        candidates
+            .iter()
+            .copied()
+            .max_by_key(|candidate| recency(candidate, first_seen))
+    };
+
+    match selection.policy {
+        // Candidates arrive sorted by pid.
+        SelectionPolicy::StickToCurrent => {
+            by_process_id(current_process_id).or_else(|| candidates.first().copied())
+        }
+        SelectionPolicy::MostRecent => most_recent(),
+        SelectionPolicy::FocusedWindow => by_process_id(focused_process_id)
+            .or_else(|| by_process_id(current_process_id))
+            .or_else(most_recent),
+        SelectionPolicy::Pinned => by_process_id(selection.pinned_process_id)
+            .or_else(|| by_process_id(current_process_id))
+            .or_else(most_recent),
+        SelectionPolicy::PlayerPriority => candidates.iter().copied().min_by_key(|candidate| {
+            let rank = selection
+                .player_priority
+                .iter()
+                .position(|entry| entry.matches(&candidate.detection))
+                .unwrap_or(selection.player_priority.len());
+            (rank, Reverse(recency(candidate, first_seen)))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::types::{
+        AnimePlaybackDetection, MediaSourceKind, PlayerPriorityEntry, SupportedPlayer,
+    };
+    use super::*;
+
+    fn candidate(process_id: u32, player: SupportedPlayer) -> DetectionCandidate {
+        DetectionCandidate {
+            process_id,
+            source: MediaSourceKind::CommandLine,
+            detection: AnimePlaybackDetection::new(player, "Frieren".to_string(), Some(1)),
+        }
+    }
+
+    fn priority_entry(
+        player: SupportedPlayer,
+        custom_player_id: Option<&str>,
+    ) -> PlayerPriorityEntry {
+        PlayerPriorityEntry {
+            player,
+            custom_player_id: custom_player_id.map(str::to_string),
+        }
+    }
+
+    fn selection(policy: SelectionPolicy) -> DetectionSelectionConfig {
+        DetectionSelectionConfig {
+            policy,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn select_candidate_applies_each_policy() {
+        let background_mpv = candidate(300, SupportedPlayer::Mpv);
+        let new_mpv = candidate(120, SupportedPlayer::Mpv);
+        let vlc = candidate(200, SupportedPlayer::Vlc);
+        let candidates = [&background_mpv, &new_mpv, &vlc];
+        let first_seen = HashMap::from([(300, 1), (120, 5), (200, 5)]);
+        let selected =
+            |selection: &DetectionSelectionConfig, current: Option<u32>, focused: Option<u32>| {
+                select_candidate(&candidates, selection, current, focused, &first_seen)
+                    .map(|candidate| candidate.process_id)
+            };
+
+        let sticky = DetectionSelectionConfig::default();
+        assert_eq!(sticky.policy, SelectionPolicy::StickToCurrent);
+        assert_eq!(selected(&sticky, Some(300), None), Some(300));
+        assert_eq!(selected(&sticky, Some(999), None), Some(300));
+
+        // Seen in the same poll, the higher pid counts as newer.
+        assert_eq!(
+            selected(&selection(SelectionPolicy::MostRecent), Some(300), None),
+            Some(200)
+        );
+
+        let focused = selection(SelectionPolicy::FocusedWindow);
+        assert_eq!(selected(&focused, Some(300), Some(120)), Some(120));
+        // Focus on a browser or the desktop keeps the current player.
+        assert_eq!(selected(&focused, Some(300), Some(999)), Some(300));
+        assert_eq!(selected(&focused, None, None), Some(200));
+
+        let pinned = DetectionSelectionConfig {
+            policy: SelectionPolicy::Pinned,
+            pinned_process_id: Some(300),
+            ..Default::default()
+        };
+        assert_eq!(selected(&pinned, Some(120), None), Some(300));
+        let missing_pin = DetectionSelectionConfig {
+            pinned_process_id: Some(999),
+            ..pinned
+        };
+        assert_eq!(selected(&missing_pin, Some(120), None), Some(120));
+
+        let priority = DetectionSelectionConfig {
+            policy: SelectionPolicy::PlayerPriority,
+            player_priority: vec![
+                priority_entry(SupportedPlayer::Vlc, None),
+                priority_entry(SupportedPlayer::Mpv, None),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(selected(&priority, Some(300), None), Some(200));
+        let unlisted = DetectionSelectionConfig {
+            player_priority: vec![priority_entry(SupportedPlayer::MpcHc, None)],
+            ..priority
+        };
+        assert_eq!(selected(&unlisted, None, None), Some(200));
+
+        assert_eq!(
+            select_candidate(&[], &focused, Some(300), Some(300), &first_seen),
+            None
+        );
+    }
+
+    #[test]
+    fn player_priority_ranks_custom_players_by_definition_id() {
+        let custom = |process_id: u32, id: &str| DetectionCandidate {
+            process_id,
+            source: MediaSourceKind::CommandLine,
+            detection: AnimePlaybackDetection::for_custom_player(
+                id.to_string(),
+                "Frieren".to_string(),
+                Some(1),
+            ),
+        };
+        let iina = custom(10, "iina");
+        let potplayer = custom(20, "potplayer");
+        let mpv = candidate(30, SupportedPlayer::Mpv);
+        let candidates = [&iina, &potplayer, &mpv];
+        let first_seen = HashMap::new();
+        let selected = |player_priority: Vec<PlayerPriorityEntry>| {
+            let selection = DetectionSelectionConfig {
+                policy: SelectionPolicy::PlayerPriority,
+                player_priority,
+                ..Default::default()
+            };
+            select_candidate(&candidates, &selection, None, None, &first_seen)
+                .map(|candidate| candidate.process_id)
+        };
+
+        assert_eq!(
+            selected(vec![
+                priority_entry(SupportedPlayer::Custom, Some("potplayer")),
+                priority_entry(SupportedPlayer::Mpv, None),
+                priority_entry(SupportedPlayer::Custom, Some("iina")),
+            ]),
+            Some(20)
+        );
+        assert_eq!(
+            selected(vec![
+                priority_entry(SupportedPlayer::Mpv, None),
+                priority_entry(SupportedPlayer::Custom, Some("potplayer")),
+            ]),
+            Some(30)
+        );
+        // An entry without an id ranks every custom player; the newest wins.
+        assert_eq!(
+            selected(vec![priority_entry(SupportedPlayer::Custom, None)]),
+            Some(20)
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use super::detector::query_player_playback_status;
+use super::processes::{self, ProcessSnapshot};
+use super::types::{DetectionSourcesConfig, PlayerPlaybackStatus, SupportedPlayer};
+
+// Where the detector reads running processes from. The system implementation
+// wraps the platform backends; tests swap in a scripted timeline.
+pub(crate) trait ProcessSource: Send + Sync {
+    fn list_processes(&self) -> Result<Vec<ProcessSnapshot>, String>;
+
+    fn find_open_media_file(&self, _pid: u32) -> Option<String> {
+        None
+    }
+
+    // Owner of the window with keyboard focus, when the platform exposes it.
+    fn focused_process_id(&self) -> Option<u32> {
+        None
+    }
+}
+
+// Where the detector asks a player what it is playing (mpv IPC, VLC HTTP,
+// MPC web interface).
+pub(crate) trait PlaybackSource: Send + Sync {
+    fn query_playback_status(
+        &self,
+        player: SupportedPlayer,
+        process: &ProcessSnapshot,
+        sources: &DetectionSourcesConfig,
+    ) -> Option<PlayerPlaybackStatus>;
+}
+
+pub(crate) struct SystemProcessSource;
+
+impl ProcessSource for SystemProcessSource {
+    fn list_processes(&self) -> Result<Vec<ProcessSnapshot>, String> {
+        processes::list_processes()
+    }
+
+    fn find_open_media_file(&self, pid: u32) -> Option<String> {
+        processes::find_open_media_file(pid)
+    }
+
+    fn focused_process_id(&self) -> Option<u32> {
+        processes::focused_process_id()
+    }
+}
+
+pub(crate) struct SystemPlaybackSource;
+
+impl PlaybackSource for SystemPlaybackSource {
+    fn query_playback_status(
+        &self,
+        player: SupportedPlayer,
+        process: &ProcessSnapshot,
+        sources: &DetectionSourcesConfig,
+    ) -> Option<PlayerPlaybackStatus> {
+        query_player_playback_status(player, process, sources)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fakes {
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::Mutex;
+
+    use super::*;
+
+    // Replays one process list per call and keeps returning the last one once
+    // the timeline runs out, like a machine that stopped changing.
+    pub(crate) struct ScriptedProcessSource {
+        timeline: Mutex<VecDeque<Result<Vec<ProcessSnapshot>, String>>>,
+        last: Mutex<Vec<ProcessSnapshot>>,
+        focused_process_id: Option<u32>,
+    }
+
+    impl ScriptedProcessSource {
+        pub(crate) fn new(timeline: Vec<Result<Vec<ProcessSnapshot>, String>>) -> Self {
+            Self {
+                timeline: Mutex::new(timeline.into()),
+                last: Mutex::new(Vec::new()),
+                focused_process_id: None,
+            }
+        }
+
+        pub(crate) fn with_focused_process(mut self, pid: u32) -> Self {
+            self.focused_process_id = Some(pid);
+            self
+        }
+    }
+
+    impl ProcessSource for ScriptedProcessSource {
+        fn list_processes(&self) -> Result<Vec<ProcessSnapshot>, String> {
+            let next = self.timeline.lock().expect("timeline lock").pop_front();
+            let mut last = self.last.lock().expect("last snapshot lock");
+
+            match next {
+                Some(Ok(processes)) => {
+                    *last = processes.clone();
+                    Ok(processes)
+                }
+                Some(Err(error)) => Err(error),
+                None => Ok(last.clone()),
+            }
+        }
+
+        fn focused_process_id(&self) -> Option<u32> {
+            self.focused_process_id
+        }
+    }
+
+    #[derive(Default)]
+    pub(crate) struct ScriptedPlaybackSource {
+        statuses: HashMap<u32, PlayerPlaybackStatus>,
+    }
+
+    impl ScriptedPlaybackSource {
+        pub(crate) fn with_status(mut self, pid: u32, status: PlayerPlaybackStatus) -> Self {
+            self.statuses.insert(pid, status);
+            self
+        }
+    }
+
+    impl PlaybackSource for ScriptedPlaybackSource {
+        fn query_playback_status(
+            &self,
+            _player: SupportedPlayer,
+            process: &ProcessSnapshot,
+            _sources: &DetectionSourcesConfig,
+        ) -> Option<PlayerPlaybackStatus> {
+            self.statuses.get(&process.pid).cloned()
+        }
+    }
+
+    pub(crate) fn process(pid: u32, args: &[&str]) -> ProcessSnapshot {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+
+        ProcessSnapshot {
+            pid,
+            name: args.first().cloned().unwrap_or_default(),
+            command_line: args.join(" "),
+            args,
+        }
+    }
+}
@@ -0,0 +1,370 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+const WORD_DELIMITERS: &[char] = &[' ', '.', '_'];
+const MAX_COMPOUND_WORDS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token {
+    Text(String),
+    Enclosed(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeywordKind {
+    Resolution,
+    VideoCodec,
+    AudioCodec,
+    Source,
+    Language,
+    Version,
+    ReleaseInfo,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Keyword {
+    pub kind: KeywordKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Word {
+    Free(String),
+    Keyword(Keyword),
+    // Scene releases append the group to the last technical token (`x265-CTR`).
+    TrailingGroup(Keyword, String),
+}
+
+struct KeywordPattern {
+    kind: KeywordKind,
+    // Ambiguous keywords are common words in titles too (`TV`, `Web`, `Dub`),
+    // so bare text only treats them as keywords next to other release metadata.
+    ambiguous: bool,
+    regex: Regex,
+}
+
+pub(crate) fn split_enclosures(value: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut chars = value.char_indices().peekable();
+
+    while let Some((index, ch)) = chars.next() {
+        let Some(closer) = closing_bracket(ch) else {
+            text.push(ch);
+            continue;
+        };
+
+        let rest = &value[index + ch.len_utf8()..];
+        let Some(end) = rest.find(closer) else {
+            text.push(ch);
+            continue;
+        };
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(std::mem::take(&mut text)));
+        }
+        tokens.push(Token::Enclosed(rest[..end].trim().to_string()));
+
+        let consumed_until = index + ch.len_utf8() + end + closer.len_utf8();
+        while chars
+            .peek()
+            .is_some_and(|(next_index, _)| *next_index < consumed_until)
+        {
+            chars.next();
+        }
+    }
+
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+
+    tokens
+}
+
+// Splits free text on word delimiters and folds runs that form a single
+// keyword (`DD5.1`, `H.264`, `Blu Ray`) using the delimiters they were written with.
+pub(crate) fn classify_words(value: &str, enclosed: bool) -> Vec<Word> {
+    let parts = split_words_with_delimiters(value);
+    let mut words = Vec::with_capacity(parts.len());
+    let mut previous_is_metadata = false;
+    let mut index = 0;
+
+    while index < parts.len() {
+        let allow_ambiguous = enclosed || previous_is_metadata;
+        let is_last = index + 1 == parts.len();
+
+        let compound = (2..=MAX_COMPOUND_WORDS.min(parts.len() - index))
+            .rev()
+            .find_map(|count| {
+                let joined = join_parts(&parts[index..index + count]);
+                classify_keyword(&joined, allow_ambiguous).map(|kind| (count, kind, joined))
+            });
+        if let Some((count, kind, text)) = compound {
+            words.push(Word::Keyword(Keyword { kind, text }));
+            previous_is_metadata = true;
+            index += count;
+            continue;
+        }
+
+        let word = parts[index].0.clone();
+        let classified = classify_word(&word, allow_ambiguous, is_last && !enclosed);
+        previous_is_metadata = match &classified {
+            Word::Free(text) => looks_like_episode_token(text),
+            Word::Keyword(_) | Word::TrailingGroup(_, _) => true,
+        };
+        words.push(classified);
+        index += 1;
+    }
+
+    words
+}
+
+fn classify_word(word: &str, allow_ambiguous: bool, allow_trailing_group: bool) -> Word {
+    if let Some(kind) = classify_keyword(word, allow_ambiguous) {
+        return Word::Keyword(Keyword {
+            kind,
+            text: word.to_string(),
+        });
+    }
+
+    let segments: Vec<&str> = word.split('-').collect();
+    if segments.len() < 2 || segments.iter().any(|segment| segment.is_empty()) {
+        return Word::Free(word.to_string());
+    }
+
+    // `1080p-Main10` glues several keywords together; callers split it back
+    // apart with `split_glued_keywords`.
+    let classified: Vec<Option<KeywordKind>> = segments
+        .iter()
+        .map(|segment| classify_keyword(segment, true))
+        .collect();
+
+    if classified.iter().all(Option::is_some) {
+        return Word::Keyword(Keyword {
+            kind: classified[0].expect("checked above"),
+            text: word.to_string(),
+        });
+    }
+
+    let (last, head) = classified.split_last().expect("at least two segments");
+    if allow_trailing_group && last.is_none() && head.iter().all(Option::is_some) {
+        let keyword_text = segments[..segments.len() - 1].join("-");
+        return Word::TrailingGroup(
+            Keyword {
+                kind: head[0].expect("checked above"),
+                text: keyword_text,
+            },
+            segments[segments.len() - 1].to_string(),
+        );
+    }
+
+    Word::Free(word.to_string())
+}
+
+pub(crate) fn split_glued_keywords(keyword: &Keyword) -> Vec<Keyword> {
+    if classify_keyword(&keyword.text, true).is_some() {
+        return vec![keyword.clone()];
+    }
+
+    keyword
+        .text
+        .split('-')
+        .filter_map(|segment| {
+            classify_keyword(segment, true).map(|kind| Keyword {
+                kind,
+                text: segment.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn classify_keyword(value: &str, allow_ambiguous: bool) -> Option<KeywordKind> {
+    keyword_patterns()
+        .iter()
+        .find(|pattern| (allow_ambiguous || !pattern.ambiguous) && pattern.regex.is_match(value))
+        .map(|pattern| pattern.kind)
+}
+
+fn looks_like_episode_token(value: &str) -> bool {
+    episode_like_regex().is_match(value)
+}
+
+// When spaces or underscores separate the words, a dot between two digits is a
+// decimal (`12.5`, `DD5.1`) rather than a delimiter.
+fn split_words_with_delimiters(value: &str) -> Vec<(String, Option<char>)> {
+    let mut parts: Vec<(String, Option<char>)> = Vec::new();
+    let mut current = String::new();
+    let dots_are_delimiters = !value.trim().contains([' ', '_']);
+    let chars: Vec<char> = value.chars().collect();
+
+    for (index, &ch) in chars.iter().enumerate() {
+        let is_decimal_point = ch == '.'
+            && !dots_are_delimiters
+            && current.ends_with(|previous: char| previous.is_ascii_digit())
+            && chars
+                .get(index + 1)
+                .is_some_and(|next| next.is_ascii_digit());
+        if WORD_DELIMITERS.contains(&ch) && !is_decimal_point {
+            if !current.is_empty() {
+                parts.push((std::mem::take(&mut current), Some(ch)));
+            }
+            continue;
+        }
+
+        current.push(ch);
+    }
+
+    if !current.is_empty() {
+        parts.push((current, None));
+    }
+
+    parts
+}
+
+fn join_parts(parts: &[(String, Option<char>)]) -> String {
+    let mut joined = String::new();
+    for (position, (word, delimiter)) in parts.iter().enumerate() {
+        joined.push_str(word);
+        if position + 1 < parts.len() {
+            joined.push(delimiter.unwrap_or(' '));
+        }
+    }
+
+    joined
+}
+
+fn closing_bracket(ch: char) -> Option<char> {
+    match ch {
+        '[' => Some(']'),
+        '(' => Some(')'),
+        '{' => Some('}'),
+        '\u{3010}' => Some('\u{3011}'),
+        _ => None,
+    }
+}
+
+fn keyword_patterns() -> &'static [KeywordPattern] {
+    static PATTERNS: OnceLock<Vec<KeywordPattern>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            (
+                KeywordKind::Resolution,
+                false,
+                r"\d{3,4}[pi]|\d{3,4}x\d{3,4}|4k|uhd",
+            ),
+            (
+                KeywordKind::VideoCodec,
+                false,
+                r"[xh][ .]?26[45]|hevc|avc|av1|vp9|xvid|divx|10[ .-]?bits?|8[ .-]?bits?|hi10p?|main[ .-]?(?:8|10)",
+            ),
+            (
+                KeywordKind::AudioCodec,
+                false,
+                r"aac(?:[ .-]?(?:2|5)(?:[ .-]?[01])?)?|flac|opus|mp3|lpcm|e?-?ac-?3|ddp?(?:[ .+-]?[257][ .-]?[01])?|ddp?\+|dts(?:[ .-]?hd(?:[ .-]?ma)?)?|truehd|atmos|dual[ .-]?audio",
+            ),
+            (
+                KeywordKind::Source,
+                false,
+                r"blu[ .-]?ray|bdrip|bdremux|bdmv|webrip|web[ .-]?dl|web[ .-]?rip|dvdrip|hdtv|tvrip|remux",
+            ),
+            (KeywordKind::Source, true, r"bd|web|dvd|tv"),
+            (
+                KeywordKind::Language,
+                false,
+                r"vostfr|raw|multi(?:[ .-]?subs?)?|sub(?:bed|s)?",
+            ),
+            (
+                KeywordKind::Language,
+                true,
+                r"eng(?:lish)?|jpn|jap(?:anese)?|ger|fre|spa|esp|ita|rus|por|pt[ .-]?br|ara|chs|cht|chi|big5|gb|dub(?:bed)?",
+            ),
+            (KeywordKind::Version, false, r"v\d{1,2}"),
+            (KeywordKind::ReleaseInfo, false, r"proper|repack"),
+        ]
+        .into_iter()
+        .map(|(kind, ambiguous, pattern)| KeywordPattern {
+            kind,
+            ambiguous,
+            regex: Regex::new(&format!("(?i)^(?:{pattern})$")).expect("valid keyword regex"),
+        })
+        .collect()
+    })
+}
+
+fn episode_like_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"(?i)^(?:S\d{1,2}E\d{1,4}|E?P?\d{1,4}(?:v\d+)?)$")
+            .expect("valid episode token regex")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_enclosures_separates_bracketed_tokens_from_text() {
+        assert_eq!(
+            split_enclosures("[SubsPlease] Frieren - 03 (1080p) [ABCD1234"),
+            vec![
+                Token::Enclosed("SubsPlease".to_string()),
+                Token::Text(" Frieren - 03 ".to_string()),
+                Token::Enclosed("1080p".to_string()),
+                Token::Text(" [ABCD1234".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn classify_words_folds_compound_keywords_and_scene_groups() {
+        let words = classify_words("Show.S01E08.1080p-Main10.BluRay.DD5.1.x265-CTR", false);
+
+        assert_eq!(words[0], Word::Free("Show".to_string()));
+        assert_eq!(words[1], Word::Free("S01E08".to_string()));
+        assert!(matches!(
+            &words[2],
+            Word::Keyword(Keyword { kind: KeywordKind::Resolution, text }) if text == "1080p-Main10"
+        ));
+        assert!(matches!(
+            &words[4],
+            Word::Keyword(Keyword { kind: KeywordKind::AudioCodec, text }) if text == "DD5.1"
+        ));
+        assert_eq!(
+            words[5],
+            Word::TrailingGroup(
+                Keyword {
+                    kind: KeywordKind::VideoCodec,
+                    text: "x265".to_string(),
+                },
+                "CTR".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn ambiguous_keywords_need_surrounding_metadata_in_bare_text() {
+        assert_eq!(
+            classify_words("Web Ghost", false),
+            vec![
+                Word::Free("Web".to_string()),
+                Word::Free("Ghost".to_string())
+            ]
+        );
+        assert!(matches!(
+            classify_words("Show 05 WEB", false).last(),
+            Some(Word::Keyword(Keyword {
+                kind: KeywordKind::Source,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            classify_words("TV", true).first(),
+            Some(Word::Keyword(Keyword {
+                kind: KeywordKind::Source,
+                ..
+            }))
+        ));
+    }
+}
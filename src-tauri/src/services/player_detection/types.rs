@@ -1,3 +1,4 @@
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 use super::util::normalize_process_name;
@@ -8,11 +9,16 @@ pub enum SupportedPlayer {
     Mpv,
     MpcHc,
     MpcBe,
+    Vlc,
+    Mpris,
+    Custom,
 }
 
 impl SupportedPlayer {
+    // MPRIS reports every player on the session bus, including browsers, so it
+    // only runs when explicitly selected.
     pub(crate) fn all() -> Vec<Self> {
-        vec![Self::Mpv, Self::MpcHc, Self::MpcBe]
+        vec![Self::Mpv, Self::MpcHc, Self::MpcBe, Self::Vlc]
     }
 
     fn process_aliases(self) -> &'static [&'static str] {
@@ -20,6 +26,15 @@ impl SupportedPlayer {
             Self::Mpv => &["mpv", "mpv.exe", "mpvnet", "mpvnet.exe", "io.mpv.mpv"],
             Self::MpcHc => &["mpc-hc", "mpc-hc.exe", "mpc-hc64", "mpc-hc64.exe"],
             Self::MpcBe => &["mpc-be", "mpc-be.exe", "mpc-be64", "mpc-be64.exe"],
+            Self::Vlc => &["vlc", "vlc.exe", "cvlc"],
+            Self::Mpris | Self::Custom => &[],
+        }
+    }
+
+    pub(crate) fn option_style(self) -> PlayerOptionStyle {
+        match self {
+            Self::MpcHc | Self::MpcBe => PlayerOptionStyle::Slash,
+            Self::Mpv | Self::Vlc | Self::Mpris | Self::Custom => PlayerOptionStyle::Dash,
         }
     }
 
@@ -31,18 +46,386 @@ impl SupportedPlayer {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub enum PlayerOptionStyle {
+    #[default]
+    #[serde(rename = "-")]
+    Dash,
+    #[serde(rename = "--")]
+    DoubleDash,
+    #[serde(rename = "/")]
+    Slash,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomPlayerDefinition {
+    pub id: String,
+    #[serde(default)]
+    pub process_aliases: Vec<String>,
+    #[serde(default)]
+    pub option_style: PlayerOptionStyle,
+    #[serde(default)]
+    pub media_arg_index: Option<usize>,
+    #[serde(default)]
+    pub media_arg_pattern: Option<String>,
+    // Compiled from `media_arg_pattern` by `normalize_custom_player`.
+    #[serde(skip)]
+    pub(crate) media_arg_regex: Option<MediaArgRegex>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MediaArgRegex(pub(crate) Regex);
+
+// Definitions are compared when the observer checks whether its configuration
+// changed mid-cycle; the pattern source is all that matters there.
+impl PartialEq for MediaArgRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for MediaArgRegex {}
+
+impl CustomPlayerDefinition {
+    pub(crate) fn matches_process_name(&self, value: &str) -> bool {
+        let normalized = normalize_process_name(value);
+        self.process_aliases
+            .iter()
+            .any(|alias| normalized == normalize_process_name(alias))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MpvIpcConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub socket_path: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VlcHttpConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MpcWebConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub port: Option<u16>,
+    // MPC-BE defaults to the same port as MPC-HC; falls back to `port`.
+    #[serde(default)]
+    pub mpc_be_port: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionSourcesConfig {
+    #[serde(default)]
+    pub mpv_ipc: MpvIpcConfig,
+    #[serde(default)]
+    pub vlc_http: VlcHttpConfig,
+    #[serde(default)]
+    pub mpc_web: MpcWebConfig,
+}
+
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct DetectPlayingAnimeRequest {
     pub players: Option<Vec<SupportedPlayer>>,
+    pub sources: Option<DetectionSourcesConfig>,
+    pub custom_players: Option<Vec<CustomPlayerDefinition>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EpisodeKind {
+    #[default]
+    Regular,
+    Special,
+    Ova,
+    OpeningEnding,
+    Preview,
+    Movie,
+}
+
+impl EpisodeKind {
+    // Creditless openings and previews are extras, not something anyone
+    // tracks as watched.
+    pub(crate) fn is_trackable(self) -> bool {
+        !matches!(self, Self::OpeningEnding | Self::Preview)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct PlayerPlaybackStatus {
+    pub media_path: Option<String>,
+    pub media_title: Option<String>,
+    pub position_seconds: Option<f64>,
+    pub duration_seconds: Option<f64>,
+    pub paused: Option<bool>,
+    pub playlist_position: Option<usize>,
+    // The player said it is stopped, so whatever its command line or open
+    // files name is not playing either.
+    pub stopped: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ListMatchSource {
+    List,
+    Search,
+    Alias,
+}
+
+// The list entry a detection was matched to. `confidence` runs from 0 to 1;
+// exact title matches score 1. `episode` is the detected episode as numbered
+// on the matched entry.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListEntryMatch {
+    pub provider_id: String,
+    pub media_id: u64,
+    pub entry_id: Option<u64>,
+    pub title: String,
+    pub total_episodes: u32,
+    pub episode: Option<u32>,
+    pub confidence: f64,
+    pub source: ListMatchSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AnimePlaybackDetection {
     pub player: SupportedPlayer,
+    pub custom_player_id: Option<String>,
     pub anime_title: String,
     pub episode: Option<u32>,
+    pub season: Option<u32>,
+    pub kind: EpisodeKind,
+    pub media_path: Option<String>,
+    pub media_title: Option<String>,
+    pub position_seconds: Option<f64>,
+    pub duration_seconds: Option<f64>,
+    pub paused: Option<bool>,
+    pub list_match: Option<ListEntryMatch>,
+}
+
+impl AnimePlaybackDetection {
+    pub(crate) fn new(player: SupportedPlayer, anime_title: String, episode: Option<u32>) -> Self {
+        Self {
+            player,
+            custom_player_id: None,
+            anime_title,
+            episode,
+            season: None,
+            kind: EpisodeKind::Regular,
+            media_path: None,
+            media_title: None,
+            position_seconds: None,
+            duration_seconds: None,
+            paused: None,
+            list_match: None,
+        }
+    }
+
+    pub(crate) fn for_custom_player(
+        custom_player_id: String,
+        anime_title: String,
+        episode: Option<u32>,
+    ) -> Self {
+        let mut detection = Self::new(SupportedPlayer::Custom, anime_title, episode);
+        detection.custom_player_id = Some(custom_player_id);
+        detection
+    }
+
+    pub(crate) fn with_kind(mut self, kind: EpisodeKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub(crate) fn with_season(mut self, season: Option<u32>) -> Self {
+        self.season = season;
+        self
+    }
+
+    pub(crate) fn with_playback_status(mut self, status: PlayerPlaybackStatus) -> Self {
+        self.media_path = status.media_path;
+        self.media_title = status.media_title;
+        self.position_seconds = status.position_seconds;
+        self.duration_seconds = status.duration_seconds;
+        self.paused = status.paused;
+        self
+    }
+
+    pub(crate) fn progress_percent(&self) -> Option<f64> {
+        match (self.position_seconds, self.duration_seconds) {
+            (Some(position), Some(duration)) if duration > 0.0 => {
+                Some((position / duration * 100.0).clamp(0.0, 100.0))
+            }
+            _ => None,
+        }
+    }
+
+    // Playback position and pause state change on every poll, so observer
+    // transitions only compare what identifies the episode being watched.
+    pub(crate) fn is_same_episode(&self, other: &Self) -> bool {
+        self.player == other.player
+            && self.custom_player_id == other.custom_player_id
+            && self.anime_title == other.anime_title
+            && self.episode == other.episode
+            && self.season == other.season
+            && self.kind == other.kind
+    }
+}
+
+// Where playback is, for players whose backend reports a position.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackProgress {
+    pub position_seconds: f64,
+    pub duration_seconds: Option<f64>,
+    pub percent: Option<f64>,
+    pub paused: bool,
+}
+
+impl PlaybackProgress {
+    pub(crate) fn from_detection(detection: &AnimePlaybackDetection) -> Option<Self> {
+        Some(Self {
+            position_seconds: detection.position_seconds?,
+            duration_seconds: detection.duration_seconds,
+            percent: detection.progress_percent(),
+            paused: detection.paused.unwrap_or(false),
+        })
+    }
+}
+
+// Where a candidate's media path came from, most reliable first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MediaSourceKind {
+    PlayerInterface,
+    CommandLine,
+    OpenFile,
+    Playlist,
+    Mpris,
+}
+
+// One player currently showing something parseable.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionCandidate {
+    pub process_id: u32,
+    pub source: MediaSourceKind,
+    pub detection: AnimePlaybackDetection,
+}
+
+// `StickToCurrent` follows a player until it closes and then takes the lowest
+// pid, which is how the observer behaved before policies existed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SelectionPolicy {
+    #[default]
+    StickToCurrent,
+    MostRecent,
+    FocusedWindow,
+    Pinned,
+    PlayerPriority,
+}
+
+// One step of the player priority order. Custom players all report
+// `SupportedPlayer::Custom`, so they are told apart by their definition id;
+// without one the entry ranks every custom player.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerPriorityEntry {
+    pub player: SupportedPlayer,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_player_id: Option<String>,
+}
+
+impl PlayerPriorityEntry {
+    pub(crate) fn matches(&self, detection: &AnimePlaybackDetection) -> bool {
+        self.player == detection.player
+            && (self.player != SupportedPlayer::Custom
+                || self.custom_player_id.is_none()
+                || self.custom_player_id == detection.custom_player_id)
+    }
+}
+
+// Decides which candidate becomes `active` when several players are open.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionSelectionConfig {
+    #[serde(default)]
+    pub policy: SelectionPolicy,
+    #[serde(default)]
+    pub pinned_process_id: Option<u32>,
+    #[serde(default)]
+    pub player_priority: Vec<PlayerPriorityEntry>,
+}
+
+pub(crate) const DEFAULT_CONFIRM_CYCLES: u32 = 1;
+const MAX_STABILITY_WINDOW_MS: u64 = 5 * 60 * 1000;
+
+// How long a change has to hold before the observer reports it. The defaults
+// report a change on the first poll that sees it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionStabilityConfig {
+    // Consecutive polls a new episode must be seen in.
+    #[serde(default = "default_confirm_cycles")]
+    pub confirm_cycles: u32,
+    // Time a new episode must be seen for, on top of `confirm_cycles`.
+    #[serde(default)]
+    pub confirm_ms: u64,
+    // Time the episode must be gone for before it counts as closed.
+    #[serde(default)]
+    pub close_grace_ms: u64,
+}
+
+impl Default for DetectionStabilityConfig {
+    fn default() -> Self {
+        Self {
+            confirm_cycles: DEFAULT_CONFIRM_CYCLES,
+            confirm_ms: 0,
+            close_grace_ms: 0,
+        }
+    }
+}
+
+impl DetectionStabilityConfig {
+    pub(crate) fn normalized(mut self) -> Self {
+        self.confirm_cycles = self.confirm_cycles.max(1);
+        self.confirm_ms = self.confirm_ms.min(MAX_STABILITY_WINDOW_MS);
+        self.close_grace_ms = self.close_grace_ms.min(MAX_STABILITY_WINDOW_MS);
+        self
+    }
+}
+
+fn default_confirm_cycles() -> u32 {
+    DEFAULT_CONFIRM_CYCLES
+}
+
+// Why the active episode changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransitionReason {
+    Started,
+    EpisodeChanged,
+    PlayerSwitched,
+    PlayerClosed,
+    ObserverDisabled,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -52,7 +435,14 @@ pub struct PlaybackObserverSnapshot {
     pub last_observed: Option<AnimePlaybackDetection>,
     pub observed_process_id: Option<u32>,
     pub observed_player: Option<SupportedPlayer>,
+    pub candidates: Vec<DetectionCandidate>,
+    pub selection: DetectionSelectionConfig,
+    pub stability: DetectionStabilityConfig,
+    pub last_transition: Option<TransitionReason>,
+    pub progress: Option<PlaybackProgress>,
     pub selected_players: Vec<SupportedPlayer>,
+    pub sources: DetectionSourcesConfig,
+    pub custom_players: Vec<CustomPlayerDefinition>,
     pub enabled: bool,
     pub poll_interval_ms: u64,
     pub last_error: Option<String>,
@@ -64,4 +454,8 @@ pub struct ConfigurePlaybackObserverRequest {
     pub enabled: Option<bool>,
     pub players: Option<Vec<SupportedPlayer>>,
     pub poll_interval_ms: Option<u64>,
+    pub sources: Option<DetectionSourcesConfig>,
+    pub custom_players: Option<Vec<CustomPlayerDefinition>>,
+    pub selection: Option<DetectionSelectionConfig>,
+    pub stability: Option<DetectionStabilityConfig>,
 }
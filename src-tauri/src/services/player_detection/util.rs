@@ -1,6 +1,8 @@
 use std::collections::HashSet;
 
-use super::types::SupportedPlayer;
+use regex::Regex;
+
+use super::types::{CustomPlayerDefinition, MediaArgRegex, SupportedPlayer};
 
 pub(crate) const DEFAULT_OBSERVER_POLL_INTERVAL_MS: u64 = 2_000;
 pub(crate) const MIN_OBSERVER_POLL_INTERVAL_MS: u64 = 500;
@@ -28,6 +30,97 @@ pub(crate) fn normalize_process_name(value: &str) -> String {
         .to_ascii_lowercase()
 }
 
+pub(crate) fn normalize_custom_player(
+    definition: CustomPlayerDefinition,
+) -> Result<CustomPlayerDefinition, String> {
+    let id = definition.id.trim().to_string();
+    if id.is_empty() {
+        return Err("Custom player id must not be empty".to_string());
+    }
+
+    let mut seen = HashSet::new();
+    let process_aliases: Vec<String> = definition
+        .process_aliases
+        .iter()
+        .map(|alias| normalize_process_name(alias))
+        .filter(|alias| !alias.is_empty() && seen.insert(alias.clone()))
+        .collect();
+    if process_aliases.is_empty() {
+        return Err(format!(
+            "Custom player `{id}` must define at least one process alias"
+        ));
+    }
+
+    let media_arg_pattern = definition
+        .media_arg_pattern
+        .map(|pattern| pattern.trim().to_string())
+        .filter(|pattern| !pattern.is_empty());
+    let media_arg_regex = media_arg_pattern
+        .as_deref()
+        .map(|pattern| {
+            Regex::new(pattern).map(MediaArgRegex).map_err(|error| {
+                format!("Invalid media argument pattern for custom player `{id}`: {error}")
+            })
+        })
+        .transpose()?;
+
+    Ok(CustomPlayerDefinition {
+        id,
+        process_aliases,
+        option_style: definition.option_style,
+        media_arg_index: definition.media_arg_index,
+        media_arg_pattern,
+        media_arg_regex,
+    })
+}
+
+pub(crate) fn normalize_custom_players(
+    definitions: Vec<CustomPlayerDefinition>,
+) -> Result<Vec<CustomPlayerDefinition>, String> {
+    let mut seen = HashSet::new();
+    let mut normalized = Vec::with_capacity(definitions.len());
+
+    for definition in definitions {
+        let definition = normalize_custom_player(definition)?;
+        if !seen.insert(definition.id.clone()) {
+            return Err(format!("Duplicate custom player id `{}`", definition.id));
+        }
+
+        normalized.push(definition);
+    }
+
+    Ok(normalized)
+}
+
+// Accepts both `--option=value` and `--option value`; the last occurrence wins,
+// matching how players resolve repeated options.
+pub(crate) fn find_long_option_value(args: &[String], option: &str) -> Option<String> {
+    let mut candidate = None;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let value = arg.trim();
+        if let Some(inline) = value
+            .strip_prefix(option)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            candidate = Some(inline.to_string());
+        } else if value == option {
+            candidate = iter.next().map(|next| next.trim().to_string());
+        }
+    }
+
+    candidate
+        .map(|value| {
+            value
+                .trim_matches('"')
+                .trim_matches('\'')
+                .trim()
+                .to_string()
+        })
+        .filter(|value| !value.is_empty())
+}
+
 pub(crate) fn split_command_line(value: &str) -> Vec<String> {
     let mut parts = Vec::new();
     let mut current = String::new();
@@ -61,3 +154,44 @@ pub(crate) fn split_command_line(value: &str) -> Vec<String> {
 
     parts
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_custom_players_trims_aliases_and_rejects_invalid_definitions() {
+        let normalized = normalize_custom_players(vec![CustomPlayerDefinition {
+            id: " iina ".to_string(),
+            process_aliases: vec![
+                "/Applications/IINA.app/Contents/MacOS/IINA".to_string(),
+                "iina".to_string(),
+                " ".to_string(),
+            ],
+            ..Default::default()
+        }])
+        .expect("definition should be valid");
+        assert_eq!(normalized[0].id, "iina");
+        assert_eq!(normalized[0].process_aliases, vec!["iina".to_string()]);
+
+        let without_alias = CustomPlayerDefinition {
+            id: "empty".to_string(),
+            ..Default::default()
+        };
+        assert!(normalize_custom_players(vec![without_alias]).is_err());
+
+        let bad_pattern = CustomPlayerDefinition {
+            id: "broken".to_string(),
+            process_aliases: vec!["broken".to_string()],
+            media_arg_pattern: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert!(normalize_custom_players(vec![bad_pattern]).is_err());
+
+        let duplicate = normalized[0].clone();
+        assert_eq!(
+            normalize_custom_players(vec![duplicate.clone(), duplicate]).unwrap_err(),
+            "Duplicate custom player id `iina`"
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use serde::Deserialize;
+
+use super::local_http::get_local;
+use super::types::{PlayerPlaybackStatus, VlcHttpConfig};
+use super::util::find_long_option_value;
+
+const VLC_STATUS_PATH: &str = "/requests/status.json";
+const VLC_DEFAULT_HTTP_PORT: u16 = 8080;
+const VLC_HTTP_PORT_OPTION: &str = "--http-port";
+const VLC_HTTP_PASSWORD_OPTION: &str = "--http-password";
+
+#[derive(Debug, Deserialize)]
+struct VlcStatusResponse {
+    #[serde(default)]
+    state: Option<String>,
+    #[serde(default)]
+    time: Option<f64>,
+    #[serde(default)]
+    length: Option<f64>,
+    #[serde(default)]
+    information: Option<VlcInformation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VlcInformation {
+    #[serde(default)]
+    category: Option<VlcCategory>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VlcCategory {
+    #[serde(default)]
+    meta: Option<VlcMeta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VlcMeta {
+    #[serde(default)]
+    filename: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct VlcHttpEndpoint {
+    pub port: u16,
+    pub password: Option<String>,
+}
+
+pub(crate) fn resolve_endpoint(config: &VlcHttpConfig, args: &[String]) -> Option<VlcHttpEndpoint> {
+    if !config.enabled {
+        return None;
+    }
+
+    let port = find_long_option_value(args, VLC_HTTP_PORT_OPTION)
+        .and_then(|value| value.parse::<u16>().ok())
+        .or(config.port)
+        .unwrap_or(VLC_DEFAULT_HTTP_PORT);
+    let password = find_long_option_value(args, VLC_HTTP_PASSWORD_OPTION).or_else(|| {
+        config
+            .password
+            .as_deref()
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    });
+
+    Some(VlcHttpEndpoint { port, password })
+}
+
+pub(crate) fn query_playback_status(
+    endpoint: &VlcHttpEndpoint,
+) -> Result<PlayerPlaybackStatus, String> {
+    let response = get_local(endpoint.port, VLC_STATUS_PATH, endpoint.password.as_deref())
+        .map_err(|error| format!("Failed to query VLC HTTP interface: {error}"))?;
+
+    match response.status {
+        200 => parse_status_response(&response.body),
+        401 | 403 => Err("VLC HTTP interface rejected the configured password".to_string()),
+        status => Err(format!("VLC HTTP interface returned status {status}")),
+    }
+}
+
+fn parse_status_response(body: &str) -> Result<PlayerPlaybackStatus, String> {
+    let status: VlcStatusResponse = serde_json::from_str(body)
+        .map_err(|error| format!("Invalid VLC status response: {error}"))?;
+
+    let state = status.state.as_deref().unwrap_or_default();
+    if state.eq_ignore_ascii_case("stopped") {
+        return Ok(PlayerPlaybackStatus {
+            stopped: true,
+            ..Default::default()
+        });
+    }
+
+    let meta = status
+        .information
+        .and_then(|information| information.category)
+        .and_then(|category| category.meta);
+    let (media_path, media_title) = match meta {
+        Some(meta) => (normalize_meta(meta.filename), normalize_meta(meta.title)),
+        None => (None, None),
+    };
+
+    Ok(PlayerPlaybackStatus {
+        media_path,
+        media_title,
+        position_seconds: status.time.filter(|value| *value >= 0.0),
+        duration_seconds: status.length.filter(|value| *value > 0.0),
+        paused: Some(state.eq_ignore_ascii_case("paused")),
+        playlist_position: None,
+        stopped: false,
+    })
+}
+
+fn normalize_meta(value: Option<String>) -> Option<String> {
+    value
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::local_http::stub_server;
+    use super::*;
+
+    const PLAYING_STATUS: &str = r#"{
+        "fullscreen": false,
+        "time": 312,
+        "length": 1420,
+        "state": "paused",
+        "position": 0.2197,
+        "information": {
+            "category": {
+                "meta": {
+                    "filename": "[SubsPlease] Sousou no Frieren - 05 (1080p) [ABCD1234].mkv",
+                    "title": "Sousou no Frieren - 05"
+                },
+                "Stream 0": { "Type": "Video" }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn parse_status_response_maps_filename_position_and_state() {
+        let status = parse_status_response(PLAYING_STATUS).expect("status should parse");
+
+        assert_eq!(
+            status.media_path.as_deref(),
+            Some("[SubsPlease] Sousou no Frieren - 05 (1080p) [ABCD1234].mkv")
+        );
+        assert_eq!(
+            status.media_title.as_deref(),
+            Some("Sousou no Frieren - 05")
+        );
+        assert_eq!(status.position_seconds, Some(312.0));
+        assert_eq!(status.duration_seconds, Some(1420.0));
+        assert_eq!(status.paused, Some(true));
+    }
+
+    #[test]
+    fn parse_status_response_reports_stopped_players_and_rejects_invalid_json() {
+        assert_eq!(
+            parse_status_response(r#"{"state":"stopped","time":0,"length":0}"#)
+                .expect("stopped status should parse"),
+            PlayerPlaybackStatus {
+                stopped: true,
+                ..Default::default()
+            }
+        );
+        assert!(parse_status_response("<html>").is_err());
+    }
+
+    #[test]
+    fn resolve_endpoint_prefers_process_args_over_configuration() {
+        let config = VlcHttpConfig {
+            enabled: true,
+            port: Some(9090),
+            password: Some("configured".to_string()),
+        };
+
+        assert_eq!(
+            resolve_endpoint(
+                &config,
+                &[
+                    "vlc".to_string(),
+                    "--extraintf=http".to_string(),
+                    "--http-port".to_string(),
+                    "8181".to_string(),
+                    "--http-password=from-args".to_string(),
+                ]
+            ),
+            Some(VlcHttpEndpoint {
+                port: 8181,
+                password: Some("from-args".to_string()),
+            })
+        );
+        assert_eq!(
+            resolve_endpoint(&config, &["vlc".to_string()]),
+            Some(VlcHttpEndpoint {
+                port: 9090,
+                password: Some("configured".to_string()),
+            })
+        );
+        assert_eq!(
+            resolve_endpoint(
+                &VlcHttpConfig {
+                    enabled: true,
+                    ..Default::default()
+                },
+                &[]
+            ),
+            Some(VlcHttpEndpoint {
+                port: VLC_DEFAULT_HTTP_PORT,
+                password: None,
+            })
+        );
+        assert_eq!(resolve_endpoint(&VlcHttpConfig::default(), &[]), None);
+    }
+
+    #[test]
+    fn query_playback_status_reads_status_from_stub_server() {
+        let (port, server) = stub_server::spawn("200 OK", PLAYING_STATUS.to_string());
+
+        let status = query_playback_status(&VlcHttpEndpoint {
+            port,
+            password: Some("secret".to_string()),
+        })
+        .expect("status should be read");
+        let request = server.join().expect("server should finish");
+
+        assert!(request.starts_with("GET /requests/status.json HTTP/1.0\r\n"));
+        assert!(request.contains("Authorization: Basic OnNlY3JldA==\r\n"));
+        assert_eq!(status.position_seconds, Some(312.0));
+    }
+
+    #[test]
+    fn query_playback_status_surfaces_authentication_failures() {
+        let (port, server) = stub_server::spawn("401 Unauthorized", String::new());
+
+        let error = query_playback_status(&VlcHttpEndpoint {
+            port,
+            password: None,
+        })
+        .unwrap_err();
+        server.join().expect("server should finish");
+
+        assert_eq!(error, "VLC HTTP interface rejected the configured password");
+    }
+}
@@ -0,0 +1,197 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::auth::anilist::PROVIDER_ID as ANILIST_PROVIDER_ID;
+use crate::services::anime_list_updates::{AnimeListUpdateRequest, ListType};
+use crate::services::player_detection::StoredAnimeListItem;
+
+const STATUS_WATCHING: &str = "watching";
+const STATUS_COMPLETED: &str = "completed";
+
+fn is_inactive_status(status: &str) -> bool {
+    matches!(
+        status,
+        "planToWatch" | "plan_to_watch" | "onHold" | "on_hold" | "dropped"
+    )
+}
+
+fn next_status(item: &StoredAnimeListItem, next_episode: u32) -> &str {
+    if item.total_episodes > 0 && next_episode >= item.total_episodes {
+        return STATUS_COMPLETED;
+    }
+
+    // Entries found through search are not on the list yet.
+    if item.user_status.is_empty() || is_inactive_status(&item.user_status) {
+        return STATUS_WATCHING;
+    }
+
+    &item.user_status
+}
+
+// Mirrors what the list views do when an episode is ticked off by hand:
+// progress only moves forward, the status follows the progress, and dates are
+// filled in when the show is started or finished.
+pub(crate) fn build_list_update(
+    provider_id: &str,
+    item: &StoredAnimeListItem,
+    episode: u32,
+    today: &str,
+) -> Option<AnimeListUpdateRequest> {
+    let mut next_episode = item.user_episodes_watched.max(episode);
+    if item.total_episodes > 0 {
+        next_episode = next_episode.min(item.total_episodes);
+    }
+
+    if next_episode <= item.user_episodes_watched {
+        return None;
+    }
+
+    let mut update = AnimeListUpdateRequest {
+        provider_id: provider_id.to_string(),
+        list_type: Some(ListType::Anime),
+        user_episodes_watched: Some(next_episode),
+        ..Default::default()
+    };
+
+    match item.entry_id {
+        Some(entry_id) if provider_id == ANILIST_PROVIDER_ID => update.entry_id = Some(entry_id),
+        _ if provider_id == ANILIST_PROVIDER_ID => update.media_id = Some(item.id),
+        _ => update.entry_id = Some(item.id),
+    }
+
+    let status = next_status(item, next_episode);
+    if status != item.user_status {
+        update.user_status = Some(status.to_string());
+    }
+
+    if item.user_episodes_watched == 0 && item.user_start_date.is_none() {
+        update.user_start_date = Some(today.to_string());
+    }
+
+    if status == STATUS_COMPLETED
+        && item.user_status != STATUS_COMPLETED
+        && item.user_finish_date.is_none()
+    {
+        update.user_finish_date = Some(today.to_string());
+    }
+
+    Some(update)
+}
+
+// Dates are recorded in UTC; the crate has no timezone database to consult.
+pub(crate) fn today_ymd() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() / 86_400)
+        .unwrap_or_default();
+
+    format_days_since_epoch(days as i64)
+}
+
+fn format_days_since_epoch(days: i64) -> String {
+    // Howard Hinnant's civil-from-days algorithm.
+    let shifted = days + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::auth::mal::PROVIDER_ID as MAL_PROVIDER_ID;
+
+    use super::*;
+
+    fn item(user_status: &str, watched: u32, total: u32) -> StoredAnimeListItem {
+        StoredAnimeListItem {
+            id: 52991,
+            entry_id: Some(900),
+            title: "Sousou no Frieren".to_string(),
+            alternative_titles: "Frieren: Beyond Journey's End, 葬送のフリーレン".to_string(),
+            total_episodes: total,
+            user_status: user_status.to_string(),
+            user_episodes_watched: watched,
+            user_start_date: None,
+            user_finish_date: None,
+        }
+    }
+
+    #[test]
+    fn build_list_update_advances_progress_status_and_dates() {
+        let started = build_list_update(
+            ANILIST_PROVIDER_ID,
+            &item("planToWatch", 0, 28),
+            1,
+            "2024-03-01",
+        )
+        .expect("first episode should update");
+        assert_eq!(started.entry_id, Some(900));
+        assert_eq!(started.user_episodes_watched, Some(1));
+        assert_eq!(started.user_status.as_deref(), Some("watching"));
+        assert_eq!(started.user_start_date.as_deref(), Some("2024-03-01"));
+        assert_eq!(started.user_finish_date, None);
+
+        let finished =
+            build_list_update(MAL_PROVIDER_ID, &item("watching", 27, 28), 30, "2024-04-01")
+                .expect("final episode should update");
+        assert_eq!(finished.entry_id, Some(52991));
+        assert_eq!(finished.media_id, None);
+        assert_eq!(finished.user_episodes_watched, Some(28));
+        assert_eq!(finished.user_status.as_deref(), Some("completed"));
+        assert_eq!(finished.user_start_date, None);
+        assert_eq!(finished.user_finish_date.as_deref(), Some("2024-04-01"));
+
+        let mut without_entry = item("watching", 3, 0);
+        without_entry.entry_id = None;
+        let airing = build_list_update(ANILIST_PROVIDER_ID, &without_entry, 4, "2024-03-08")
+            .expect("airing show should update");
+        assert_eq!(airing.media_id, Some(52991));
+        assert_eq!(airing.user_status, None);
+
+        let searched = StoredAnimeListItem {
+            id: 58514,
+            title: "Dandadan".to_string(),
+            total_episodes: 12,
+            ..Default::default()
+        };
+        let added = build_list_update(ANILIST_PROVIDER_ID, &searched, 1, "2024-10-04")
+            .expect("search result should be added");
+        assert_eq!(added.media_id, Some(58514));
+        assert_eq!(added.user_status.as_deref(), Some("watching"));
+        assert_eq!(added.user_start_date.as_deref(), Some("2024-10-04"));
+
+        assert!(build_list_update(
+            ANILIST_PROVIDER_ID,
+            &item("watching", 5, 28),
+            3,
+            "2024-03-08"
+        )
+        .is_none());
+        assert!(build_list_update(
+            ANILIST_PROVIDER_ID,
+            &item("completed", 28, 28),
+            28,
+            "2024-03-08"
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn format_days_since_epoch_produces_calendar_dates() {
+        assert_eq!(format_days_since_epoch(0), "1970-01-01");
+        assert_eq!(format_days_since_epoch(19_782), "2024-02-29");
+        assert_eq!(format_days_since_epoch(20_743), "2026-10-17");
+        assert_eq!(today_ymd().len(), 10);
+    }
+}
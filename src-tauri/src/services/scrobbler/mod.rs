@@ -0,0 +1,9 @@
+mod list;
+mod state;
+mod tracker;
+mod types;
+
+pub use state::{
+    configure_scrobbler, confirm_scrobble, dismiss_scrobble, get_scrobbler_state, ScrobblerState,
+};
+pub use types::{Scrobble, ScrobblerConfig, ScrobblerSnapshot};
@@ -0,0 +1,333 @@
+use std::time::Instant;
+
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::Mutex;
+
+use crate::services::anime_list_updates::AnimeListUpdateQueue;
+use crate::services::player_detection::{
+    read_active_anime_list, AnimePlaybackDetection, StoredAnimeListItem,
+};
+
+use super::list::{build_list_update, today_ymd};
+use super::tracker::WatchTracker;
+use super::types::{Scrobble, ScrobblerConfig, ScrobblerSnapshot};
+
+pub const SCROBBLE_SUBMITTED_EVENT: &str = "scrobbler:scrobbled";
+pub const SCROBBLE_CONFIRMATION_REQUESTED_EVENT: &str = "scrobbler:confirmation-requested";
+
+// Weaker matches are shown to the user but never written to the list
+// without them picking the entry.
+const MIN_UPDATE_CONFIDENCE: f64 = 0.85;
+
+struct ScrobblerStateData {
+    config: ScrobblerConfig,
+    tracker: WatchTracker,
+    pending_confirmations: Vec<Scrobble>,
+    next_id: u64,
+}
+
+pub struct ScrobblerState {
+    data: Mutex<ScrobblerStateData>,
+}
+
+impl ScrobblerState {
+    pub fn new(config: ScrobblerConfig) -> Self {
+        Self {
+            data: Mutex::new(ScrobblerStateData {
+                config: config.normalized(),
+                tracker: WatchTracker::default(),
+                pending_confirmations: Vec::new(),
+                next_id: 1,
+            }),
+        }
+    }
+
+    fn snapshot_from_guard(guard: &ScrobblerStateData) -> ScrobblerSnapshot {
+        ScrobblerSnapshot {
+            config: guard.config.clone(),
+            pending_confirmations: guard.pending_confirmations.clone(),
+        }
+    }
+
+    pub async fn snapshot(&self) -> ScrobblerSnapshot {
+        Self::snapshot_from_guard(&*self.data.lock().await)
+    }
+
+    pub async fn configure(&self, config: ScrobblerConfig) -> ScrobblerSnapshot {
+        let mut guard = self.data.lock().await;
+        guard.config = config.normalized();
+
+        if !guard.config.enabled {
+            guard.tracker.reset();
+            guard.pending_confirmations.clear();
+        }
+
+        Self::snapshot_from_guard(&guard)
+    }
+
+    // Called by the playback observer after every poll with whatever episode
+    // it currently follows.
+    pub(crate) async fn observe(
+        &self,
+        app: &tauri::AppHandle,
+        active: Option<&AnimePlaybackDetection>,
+    ) {
+        let (detection, confirm_updates) = {
+            let mut guard = self.data.lock().await;
+            if !guard.config.enabled {
+                guard.tracker.reset();
+                return;
+            }
+
+            let config = guard.config.clone();
+            match guard.tracker.observe(active, Instant::now(), &config) {
+                Some(detection) => (detection, config.confirm_updates),
+                None => return,
+            }
+        };
+
+        // The episode only counts as reported once its update is queued or
+        // waiting for confirmation; failures are tried again on the next poll.
+        let reported = match self.prepare_scrobble(app, &detection).await {
+            Ok(Some(scrobble)) if confirm_updates => {
+                self.request_confirmation(app, scrobble).await;
+                true
+            }
+            Ok(Some(scrobble)) => match submit_scrobble(app, scrobble).await {
+                Ok(()) => true,
+                Err(error) => {
+                    eprintln!("failed to enqueue list update for watched episode: {error}");
+                    false
+                }
+            },
+            Ok(None) => true,
+            Err(error) => {
+                eprintln!("failed to prepare list update for watched episode: {error}");
+                false
+            }
+        };
+
+        if reported {
+            self.data.lock().await.tracker.mark_reported(&detection);
+        }
+    }
+
+    async fn prepare_scrobble(
+        &self,
+        app: &tauri::AppHandle,
+        detection: &AnimePlaybackDetection,
+    ) -> Result<Option<Scrobble>, String> {
+        let Some(list_match) = detection
+            .list_match
+            .as_ref()
+            .filter(|list_match| list_match.confidence >= MIN_UPDATE_CONFIDENCE)
+        else {
+            return Ok(None);
+        };
+        let Some(episode) = list_match.episode else {
+            return Ok(None);
+        };
+
+        let list = read_active_anime_list(app)?;
+        if list.provider_id != list_match.provider_id {
+            return Ok(None);
+        }
+
+        let item = list
+            .items
+            .into_iter()
+            .find(|item| item.id == list_match.media_id)
+            .unwrap_or_else(|| StoredAnimeListItem {
+                id: list_match.media_id,
+                entry_id: list_match.entry_id,
+                title: list_match.title.clone(),
+                total_episodes: list_match.total_episodes,
+                ..Default::default()
+            });
+        let Some(update) = build_list_update(&list.provider_id, &item, episode, &today_ymd())
+        else {
+            return Ok(None);
+        };
+
+        let mut guard = self.data.lock().await;
+        let id = guard.next_id;
+        guard.next_id += 1;
+
+        Ok(Some(Scrobble {
+            id,
+            anime_id: item.id,
+            anime_title: item.title,
+            episode,
+            update,
+        }))
+    }
+
+    async fn request_confirmation(&self, app: &tauri::AppHandle, scrobble: Scrobble) {
+        self.data
+            .lock()
+            .await
+            .pending_confirmations
+            .push(scrobble.clone());
+
+        if let Err(error) = app
+            .notification()
+            .builder()
+            .title("Update your anime list?")
+            .body(format!(
+                "Episode {} of {} was watched. Open Kioku to confirm the list update.",
+                scrobble.episode, scrobble.anime_title
+            ))
+            .show()
+        {
+            eprintln!("failed to show list update confirmation: {error}");
+        }
+
+        if let Err(error) = app.emit(SCROBBLE_CONFIRMATION_REQUESTED_EVENT, &scrobble) {
+            eprintln!("failed to emit {SCROBBLE_CONFIRMATION_REQUESTED_EVENT} event: {error}");
+        }
+    }
+
+    async fn take_pending_confirmation(&self, id: u64) -> Result<Scrobble, String> {
+        let mut guard = self.data.lock().await;
+        let index = guard
+            .pending_confirmations
+            .iter()
+            .position(|scrobble| scrobble.id == id)
+            .ok_or_else(|| format!("No pending list update with id {id}"))?;
+
+        Ok(guard.pending_confirmations.remove(index))
+    }
+
+    // Puts back a confirmed update that could not be queued so the user can
+    // confirm it again.
+    async fn restore_pending_confirmation(&self, scrobble: Scrobble) {
+        let mut guard = self.data.lock().await;
+        if guard.config.enabled {
+            guard.pending_confirmations.push(scrobble);
+        }
+    }
+}
+
+async fn submit_scrobble(app: &tauri::AppHandle, scrobble: Scrobble) -> Result<(), String> {
+    app.state::<AnimeListUpdateQueue>()
+        .enqueue(scrobble.update.clone())
+        .await?;
+
+    if let Err(error) = app.emit(SCROBBLE_SUBMITTED_EVENT, &scrobble) {
+        eprintln!("failed to emit {SCROBBLE_SUBMITTED_EVENT} event: {error}");
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_scrobbler_state(app: tauri::AppHandle) -> Result<ScrobblerSnapshot, String> {
+    Ok(app.state::<ScrobblerState>().snapshot().await)
+}
+
+#[tauri::command]
+pub async fn configure_scrobbler(
+    config: ScrobblerConfig,
+    app: tauri::AppHandle,
+) -> Result<ScrobblerSnapshot, String> {
+    Ok(app.state::<ScrobblerState>().configure(config).await)
+}
+
+#[tauri::command]
+pub async fn confirm_scrobble(id: u64, app: tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<ScrobblerState>();
+    let scrobble = state.take_pending_confirmation(id).await?;
+
+    if let Err(error) = submit_scrobble(&app, scrobble.clone()).await {
+        state.restore_pending_confirmation(scrobble).await;
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn dismiss_scrobble(id: u64, app: tauri::AppHandle) -> Result<(), String> {
+    app.state::<ScrobblerState>()
+        .take_pending_confirmation(id)
+        .await
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::runtime::Runtime;
+
+    use crate::services::anime_list_updates::AnimeListUpdateRequest;
+
+    use super::*;
+
+    fn scrobble(id: u64) -> Scrobble {
+        Scrobble {
+            id,
+            anime_id: 52991,
+            anime_title: "Sousou no Frieren".to_string(),
+            episode: 5,
+            update: AnimeListUpdateRequest {
+                provider_id: "anilist".to_string(),
+                user_episodes_watched: Some(5),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn pending_confirmations_are_taken_once_and_cleared_when_disabled() {
+        let runtime = Runtime::new().expect("runtime should build");
+        let state = ScrobblerState::new(ScrobblerConfig {
+            enabled: true,
+            completion_percent: 0,
+            confirm_updates: true,
+            ..Default::default()
+        });
+
+        runtime.block_on(async {
+            assert_eq!(state.snapshot().await.config.completion_percent, 1);
+
+            state
+                .data
+                .lock()
+                .await
+                .pending_confirmations
+                .extend([scrobble(1), scrobble(2)]);
+
+            assert_eq!(
+                state
+                    .take_pending_confirmation(1)
+                    .await
+                    .expect("pending update")
+                    .id,
+                1
+            );
+            assert_eq!(
+                state.take_pending_confirmation(1).await.unwrap_err(),
+                "No pending list update with id 1"
+            );
+
+            state.restore_pending_confirmation(scrobble(1)).await;
+            assert_eq!(
+                state
+                    .snapshot()
+                    .await
+                    .pending_confirmations
+                    .iter()
+                    .map(|scrobble| scrobble.id)
+                    .collect::<Vec<_>>(),
+                vec![2, 1]
+            );
+
+            let snapshot = state.configure(ScrobblerConfig::default()).await;
+            assert!(!snapshot.config.enabled);
+            assert!(snapshot.pending_confirmations.is_empty());
+
+            state.restore_pending_confirmation(scrobble(3)).await;
+            assert!(state.snapshot().await.pending_confirmations.is_empty());
+        });
+    }
+}
@@ -0,0 +1,214 @@
+use std::time::{Duration, Instant};
+
+use crate::services::player_detection::AnimePlaybackDetection;
+
+use super::types::ScrobblerConfig;
+
+// Gaps longer than this mean the observer was stopped or the machine slept,
+// not that the episode kept playing.
+const MAX_OBSERVED_GAP: Duration = Duration::from_secs(30);
+
+struct WatchSession {
+    detection: AnimePlaybackDetection,
+    observed: Duration,
+    last_seen: Instant,
+    reported: bool,
+}
+
+impl WatchSession {
+    fn new(detection: AnimePlaybackDetection, now: Instant) -> Self {
+        Self {
+            detection,
+            observed: Duration::ZERO,
+            last_seen: now,
+            reported: false,
+        }
+    }
+
+    fn has_passed_threshold(&self, config: &ScrobblerConfig) -> bool {
+        match self.detection.progress_percent() {
+            Some(percent) => percent >= f64::from(config.completion_percent),
+            None => self.observed >= Duration::from_secs(u64::from(config.watched_minutes) * 60),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct WatchTracker {
+    session: Option<WatchSession>,
+}
+
+impl WatchTracker {
+    // Feeds the observer's active detection for one poll and returns it on
+    // every poll past the configured threshold until `mark_reported` is called
+    // for it, so an update that could not be queued is tried again.
+    pub(crate) fn observe(
+        &mut self,
+        detection: Option<&AnimePlaybackDetection>,
+        now: Instant,
+        config: &ScrobblerConfig,
+    ) -> Option<AnimePlaybackDetection> {
+        let Some(detection) = detection.filter(|detection| detection.episode.is_some()) else {
+            self.session = None;
+            return None;
+        };
+
+        match self.session.as_mut() {
+            Some(session) if session.detection.is_same_episode(detection) => {
+                if session.detection.paused != Some(true) {
+                    session.observed += now
+                        .saturating_duration_since(session.last_seen)
+                        .min(MAX_OBSERVED_GAP);
+                }
+                session.last_seen = now;
+                session.detection = detection.clone();
+            }
+            _ => self.session = Some(WatchSession::new(detection.clone(), now)),
+        }
+
+        let session = self.session.as_ref()?;
+        (!session.reported && session.has_passed_threshold(config))
+            .then(|| session.detection.clone())
+    }
+
+    pub(crate) fn mark_reported(&mut self, detection: &AnimePlaybackDetection) {
+        if let Some(session) = self
+            .session
+            .as_mut()
+            .filter(|session| session.detection.is_same_episode(detection))
+        {
+            session.reported = true;
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.session = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::player_detection::SupportedPlayer;
+
+    use super::*;
+
+    fn episode(number: u32) -> AnimePlaybackDetection {
+        AnimePlaybackDetection::new(
+            SupportedPlayer::Mpv,
+            "Sousou no Frieren".to_string(),
+            Some(number),
+        )
+    }
+
+    fn config(completion_percent: u8, watched_minutes: u32) -> ScrobblerConfig {
+        ScrobblerConfig {
+            enabled: true,
+            completion_percent,
+            watched_minutes,
+            confirm_updates: false,
+        }
+    }
+
+    #[test]
+    fn observe_counts_unpaused_time_and_reports_each_episode_once() {
+        let config = config(80, 2);
+        let start = Instant::now();
+        let mut tracker = WatchTracker::default();
+        let mut paused = episode(1);
+        paused.paused = Some(true);
+
+        assert_eq!(tracker.observe(Some(&episode(1)), start, &config), None);
+        assert_eq!(
+            tracker.observe(Some(&paused), start + Duration::from_secs(25), &config),
+            None
+        );
+        // Time spent paused does not count towards the threshold.
+        assert_eq!(
+            tracker.observe(Some(&episode(1)), start + Duration::from_secs(95), &config),
+            None
+        );
+        let mut now = start + Duration::from_secs(95);
+        for _ in 0..4 {
+            now += Duration::from_secs(20);
+            assert_eq!(tracker.observe(Some(&episode(1)), now, &config), None);
+        }
+        now += Duration::from_secs(20);
+        assert_eq!(
+            tracker.observe(Some(&episode(1)), now, &config),
+            Some(episode(1))
+        );
+        // Until the update is queued, the episode keeps being returned.
+        now += Duration::from_secs(20);
+        assert_eq!(
+            tracker.observe(Some(&episode(1)), now, &config),
+            Some(episode(1))
+        );
+        tracker.mark_reported(&episode(2));
+        now += Duration::from_secs(20);
+        assert_eq!(
+            tracker.observe(Some(&episode(1)), now, &config),
+            Some(episode(1))
+        );
+        tracker.mark_reported(&episode(1));
+        now += Duration::from_secs(20);
+        assert_eq!(tracker.observe(Some(&episode(1)), now, &config), None);
+
+        // A new episode starts a new session.
+        assert_eq!(tracker.observe(Some(&episode(2)), now, &config), None);
+    }
+
+    #[test]
+    fn observe_prefers_reported_position_and_ignores_long_gaps() {
+        let config = config(80, 1);
+        let start = Instant::now();
+        let mut tracker = WatchTracker::default();
+        let at_position = |position: f64| {
+            let mut detection = episode(5);
+            detection.position_seconds = Some(position);
+            detection.duration_seconds = Some(1420.0);
+            detection
+        };
+
+        assert_eq!(
+            tracker.observe(Some(&at_position(30.0)), start, &config),
+            None
+        );
+        // Two minutes of wall time would pass the time threshold, but the
+        // player says only a fraction of the episode has been watched.
+        assert_eq!(
+            tracker.observe(
+                Some(&at_position(150.0)),
+                start + Duration::from_secs(120),
+                &config
+            ),
+            None
+        );
+        assert!(tracker
+            .observe(
+                Some(&at_position(1200.0)),
+                start + Duration::from_secs(125),
+                &config
+            )
+            .is_some());
+
+        let mut tracker = WatchTracker::default();
+        assert_eq!(tracker.observe(Some(&episode(6)), start, &config), None);
+        assert_eq!(
+            tracker.observe(
+                Some(&episode(6)),
+                start + Duration::from_secs(3600),
+                &config
+            ),
+            None
+        );
+        tracker.observe(None, start + Duration::from_secs(3601), &config);
+        assert_eq!(
+            tracker.observe(
+                Some(&episode(6)),
+                start + Duration::from_secs(3700),
+                &config
+            ),
+            None
+        );
+    }
+}
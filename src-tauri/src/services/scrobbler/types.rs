@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use crate::services::anime_list_updates::AnimeListUpdateRequest;
+
+pub(crate) const DEFAULT_COMPLETION_PERCENT: u8 = 80;
+pub(crate) const DEFAULT_WATCHED_MINUTES: u32 = 18;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrobblerConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    // Used when the player reports position and duration.
+    #[serde(default = "default_completion_percent")]
+    pub completion_percent: u8,
+    // Used when only the process is visible and the episode length is unknown.
+    #[serde(default = "default_watched_minutes")]
+    pub watched_minutes: u32,
+    #[serde(default)]
+    pub confirm_updates: bool,
+}
+
+impl Default for ScrobblerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            completion_percent: DEFAULT_COMPLETION_PERCENT,
+            watched_minutes: DEFAULT_WATCHED_MINUTES,
+            confirm_updates: false,
+        }
+    }
+}
+
+impl ScrobblerConfig {
+    pub(crate) fn normalized(mut self) -> Self {
+        self.completion_percent = self.completion_percent.clamp(1, 100);
+        self.watched_minutes = self.watched_minutes.max(1);
+        self
+    }
+}
+
+fn default_completion_percent() -> u8 {
+    DEFAULT_COMPLETION_PERCENT
+}
+
+fn default_watched_minutes() -> u32 {
+    DEFAULT_WATCHED_MINUTES
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scrobble {
+    pub id: u64,
+    pub anime_id: u64,
+    pub anime_title: String,
+    pub episode: u32,
+    pub update: AnimeListUpdateRequest,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrobblerSnapshot {
+    pub config: ScrobblerConfig,
+    pub pending_confirmations: Vec<Scrobble>,
+}
@@ -5,6 +5,7 @@ import { RouterProvider } from 'react-router';
 import AppUpdateSnackbar from '@/components/AppUpdateSnackbar';
 import useDiscordRichPresence from '@/hooks/detection/useDiscordRichPresence';
 import usePlaybackObserverEvents from '@/hooks/detection/usePlaybackObserverEvents';
+import useNowPlayingAliasesMigration from '@/hooks/migrations/useNowPlayingAliasesMigration';
 import useAppUpdater from '@/hooks/useAppUpdater';
 import AnimeInformations from './components/anime/AnimeInformations';
 import MangaInformations from './components/manga/MangaInformations';
@@ -14,6 +15,7 @@ import './styles/global.css';
 
 function App() {
   useAppUpdater();
+  useNowPlayingAliasesMigration();
   usePlaybackObserverEvents();
   useDiscordRichPresence();
 
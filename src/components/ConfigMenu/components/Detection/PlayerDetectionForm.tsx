@@ -13,7 +13,9 @@ import { useConfigMenuStore } from '@/stores/config/configMenu';
 const PLAYER_OPTIONS: { label: string; value: SupportedPlayer }[] = [
   { label: 'mpv', value: 'mpv' },
   { label: 'MPC-HC', value: 'mpc-hc' },
-  { label: 'MPC-BE', value: 'mpc-be' }
+  { label: 'MPC-BE', value: 'mpc-be' },
+  { label: 'VLC', value: 'vlc' },
+  { label: 'MPRIS (Linux)', value: 'mpris' }
 ];
 
 const PlayerDetectionForm = () => {
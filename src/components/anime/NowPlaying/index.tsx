@@ -7,6 +7,7 @@ import {
   flattenAnimeListData,
   mergeAnimeCandidates
 } from '@/hooks/detection/utils';
+import { NotificationService } from '@/services/Notification';
 import { useNowPlayingAliasesStore } from '@/stores/detection/nowPlayingAliases';
 import { usePlayerDetectionStore } from '@/stores/detection/playerDetection';
 import { useAniListStore } from '@/stores/providers/anilist';
@@ -114,10 +115,27 @@ const NowPlaying = () => {
 
       if (!activeProvider) return;
 
-      addAlias(activeProvider, animeId, animePlaying.animeTitle);
+      addAlias({
+        providerId: activeProvider,
+        title: animePlaying.animeTitle,
+        season: animePlaying.season,
+        mediaId: animeId,
+        mediaTitle: aggregatedData.find((anime) => anime.id === animeId)?.title
+      }).catch((error: unknown) => {
+        new NotificationService().sendNotification({
+          title: 'Alias not saved',
+          body: `${animePlaying.animeTitle} will not be matched automatically next time: ${String(error)}`
+        });
+      });
       resolveActiveAnime(activeProvider, animeId);
     },
-    [activeProvider, addAlias, resolveActiveAnime, animePlaying]
+    [
+      activeProvider,
+      addAlias,
+      aggregatedData,
+      resolveActiveAnime,
+      animePlaying
+    ]
   );
 
   return (
@@ -4,6 +4,7 @@ import {
   SynchronizedAnimeList
 } from '@/services/backend/types';
 import { NotificationService } from '@/services/Notification';
+import { useConfigMenuStore } from '@/stores/config/configMenu';
 import { useNowPlayingAliasesStore } from '@/stores/detection/nowPlayingAliases';
 import { usePlayerDetectionStore } from '@/stores/detection/playerDetection';
 import { useAniListStore } from '@/stores/providers/anilist';
@@ -143,6 +144,15 @@ const usePlaybackObserverEvents = () => {
 
           setEpisodeClosed(detection);
 
+          // The backend scrobbler already updates the list once enough of
+          // the episode has been watched.
+          if (
+            useConfigMenuStore.getState().configuration.detection.scrobbler
+              ?.enabled
+          ) {
+            return;
+          }
+
           if (
             !activeMatchedAnimeId ||
             detection.episode === null ||
@@ -0,0 +1,68 @@
+import { useEffect } from 'react';
+
+import { normalizeTitle } from '@/hooks/detection/utils';
+import { NotificationService } from '@/services/Notification';
+import {
+  legacyTauriHandler,
+  toProviderAliases,
+  useLegacyNowPlayingAliasesStore,
+  useNowPlayingAliasesStore
+} from '@/stores/detection/nowPlayingAliases';
+import { Provider } from '@/types/List';
+
+// Moves aliases saved by the frontend into the backend store, then loads the
+// backend aliases for matching. A title the backend already has keeps its
+// backend mapping, since that one was picked more recently.
+const migrateLegacyAliases = async () => {
+  await legacyTauriHandler.start();
+
+  const legacyAliases = toProviderAliases(
+    useLegacyNowPlayingAliasesStore.getState().aliasesByAnimeId
+  );
+  const { aliases, addAlias } = useNowPlayingAliasesStore.getState();
+  const knownTitles = new Set(
+    aliases.map((alias) => `${alias.providerId}:${normalizeTitle(alias.title)}`)
+  );
+
+  for (const provider of Object.values(Provider)) {
+    const aliasesByAnimeId = legacyAliases[provider] || {};
+
+    for (const [animeId, titles] of Object.entries(aliasesByAnimeId)) {
+      for (const title of titles) {
+        const key = `${provider}:${normalizeTitle(title)}`;
+        if (knownTitles.has(key)) {
+          continue;
+        }
+
+        await addAlias({
+          providerId: provider,
+          title,
+          mediaId: Number(animeId)
+        });
+        knownTitles.add(key);
+      }
+    }
+  }
+
+  useLegacyNowPlayingAliasesStore.getState().clearAliases();
+};
+
+const useNowPlayingAliasesMigration = () => {
+  useEffect(() => {
+    const notification = new NotificationService();
+
+    const loadAliases = async () => {
+      await useNowPlayingAliasesStore.getState().loadAliases();
+      await migrateLegacyAliases();
+    };
+
+    void loadAliases().catch((error: unknown) => {
+      notification.sendNotification({
+        title: 'Title aliases unavailable',
+        body: `Saved title aliases could not be loaded: ${String(error)}`
+      });
+    });
+  }, []);
+};
+
+export default useNowPlayingAliasesMigration;
@@ -1,9 +1,60 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
 
-import { ListUpdateRequest } from '../types';
+import {
+  DeadLetteredListUpdate,
+  ListUpdateEvent,
+  ListUpdateQueueSnapshot,
+  ListUpdateRequest,
+  ListUpdatesConfig
+} from '../types';
+
+export const LIST_UPDATE_QUEUED_EVENT = 'list-update:queued';
+export const LIST_UPDATE_STARTED_EVENT = 'list-update:started';
+export const LIST_UPDATE_SUCCEEDED_EVENT = 'list-update:succeeded';
+export const LIST_UPDATE_FAILED_EVENT = 'list-update:failed';
+export const LIST_UPDATE_DROPPED_EVENT = 'list-update:dropped';
+
+export type ListUpdateEventName =
+  | typeof LIST_UPDATE_QUEUED_EVENT
+  | typeof LIST_UPDATE_STARTED_EVENT
+  | typeof LIST_UPDATE_SUCCEEDED_EVENT
+  | typeof LIST_UPDATE_FAILED_EVENT
+  | typeof LIST_UPDATE_DROPPED_EVENT;
 
 export class AnimeListService {
-  static async enqueueListUpdate(update: ListUpdateRequest): Promise<void> {
-    return invoke('enqueue_anime_list_update', { update });
+  static async enqueueListUpdate(update: ListUpdateRequest): Promise<number> {
+    return invoke<number>('enqueue_anime_list_update', { update });
+  }
+
+  static async configure(config: ListUpdatesConfig): Promise<void> {
+    return invoke('configure_anime_list_updates', { config });
+  }
+
+  static async getQueue(): Promise<ListUpdateQueueSnapshot> {
+    return invoke<ListUpdateQueueSnapshot>('get_anime_list_update_queue');
+  }
+
+  static async listDeadLetters(): Promise<DeadLetteredListUpdate[]> {
+    return invoke<DeadLetteredListUpdate[]>(
+      'list_dead_letter_anime_list_updates'
+    );
+  }
+
+  static async resubmitDeadLetter(id: number): Promise<number> {
+    return invoke<number>('resubmit_dead_letter_anime_list_update', { id });
+  }
+
+  static async discardDeadLetter(id: number): Promise<void> {
+    return invoke('discard_dead_letter_anime_list_update', { id });
+  }
+
+  static async listenListUpdate(
+    event: ListUpdateEventName,
+    onEvent: (update: ListUpdateEvent) => void
+  ): Promise<UnlistenFn> {
+    return listen<ListUpdateEvent>(event, ({ payload }) => {
+      onEvent(payload);
+    });
   }
 }
@@ -5,12 +5,18 @@ import {
   AnimePlaybackDetection,
   ConfigurePlaybackObserverRequest,
   DetectPlayingAnimeRequest,
-  PlaybackObserverSnapshot
+  ParsedRelease,
+  PlaybackObserverEvent,
+  PlaybackObserverSnapshot,
+  PlaybackStatusEvent
 } from '../types';
 
 export const PLAYBACK_EPISODE_DETECTED_EVENT =
   'player-detection:episode-detected';
 export const PLAYBACK_EPISODE_CLOSED_EVENT = 'player-detection:episode-closed';
+export const PLAYBACK_PAUSED_EVENT = 'player-detection:paused';
+export const PLAYBACK_RESUMED_EVENT = 'player-detection:resumed';
+export const PLAYBACK_PROGRESS_EVENT = 'player-detection:progress';
 
 export class PlayerDetectionService {
   static async detectPlayingAnime(
@@ -21,6 +27,10 @@ export class PlayerDetectionService {
     });
   }
 
+  static async parseReleaseName(name: string): Promise<ParsedRelease | null> {
+    return invoke<ParsedRelease | null>('parse_release_name', { name });
+  }
+
   static async getPlaybackObserverState(): Promise<PlaybackObserverSnapshot> {
     return invoke<PlaybackObserverSnapshot>('get_playback_observer_state');
   }
@@ -34,9 +44,9 @@ export class PlayerDetectionService {
   }
 
   static async listenEpisodeDetected(
-    onDetected: (detection: AnimePlaybackDetection) => void
+    onDetected: (event: PlaybackObserverEvent) => void
   ): Promise<UnlistenFn> {
-    return listen<AnimePlaybackDetection>(
+    return listen<PlaybackObserverEvent>(
       PLAYBACK_EPISODE_DETECTED_EVENT,
       ({ payload }) => {
         onDetected(payload);
@@ -45,13 +55,43 @@ export class PlayerDetectionService {
   }
 
   static async listenEpisodeClosed(
-    onClosed: (detection: AnimePlaybackDetection) => void
+    onClosed: (event: PlaybackObserverEvent) => void
   ): Promise<UnlistenFn> {
-    return listen<AnimePlaybackDetection>(
+    return listen<PlaybackObserverEvent>(
       PLAYBACK_EPISODE_CLOSED_EVENT,
       ({ payload }) => {
         onClosed(payload);
       }
     );
   }
+
+  static async listenPaused(
+    onPaused: (event: PlaybackStatusEvent) => void
+  ): Promise<UnlistenFn> {
+    return listen<PlaybackStatusEvent>(PLAYBACK_PAUSED_EVENT, ({ payload }) => {
+      onPaused(payload);
+    });
+  }
+
+  static async listenResumed(
+    onResumed: (event: PlaybackStatusEvent) => void
+  ): Promise<UnlistenFn> {
+    return listen<PlaybackStatusEvent>(
+      PLAYBACK_RESUMED_EVENT,
+      ({ payload }) => {
+        onResumed(payload);
+      }
+    );
+  }
+
+  static async listenProgress(
+    onProgress: (event: PlaybackStatusEvent) => void
+  ): Promise<UnlistenFn> {
+    return listen<PlaybackStatusEvent>(
+      PLAYBACK_PROGRESS_EVENT,
+      ({ payload }) => {
+        onProgress(payload);
+      }
+    );
+  }
 }
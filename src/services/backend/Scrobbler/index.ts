@@ -0,0 +1,45 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
+
+import { Scrobble, ScrobblerConfig, ScrobblerSnapshot } from '../types';
+
+export const SCROBBLE_SUBMITTED_EVENT = 'scrobbler:scrobbled';
+export const SCROBBLE_CONFIRMATION_REQUESTED_EVENT =
+  'scrobbler:confirmation-requested';
+
+export class ScrobblerService {
+  static async getState(): Promise<ScrobblerSnapshot> {
+    return invoke<ScrobblerSnapshot>('get_scrobbler_state');
+  }
+
+  static async configure(config: ScrobblerConfig): Promise<ScrobblerSnapshot> {
+    return invoke<ScrobblerSnapshot>('configure_scrobbler', { config });
+  }
+
+  static async confirm(id: number): Promise<void> {
+    return invoke('confirm_scrobble', { id });
+  }
+
+  static async dismiss(id: number): Promise<void> {
+    return invoke('dismiss_scrobble', { id });
+  }
+
+  static async listenScrobbled(
+    onScrobbled: (scrobble: Scrobble) => void
+  ): Promise<UnlistenFn> {
+    return listen<Scrobble>(SCROBBLE_SUBMITTED_EVENT, ({ payload }) => {
+      onScrobbled(payload);
+    });
+  }
+
+  static async listenConfirmationRequested(
+    onRequested: (scrobble: Scrobble) => void
+  ): Promise<UnlistenFn> {
+    return listen<Scrobble>(
+      SCROBBLE_CONFIRMATION_REQUESTED_EVENT,
+      ({ payload }) => {
+        onRequested(payload);
+      }
+    );
+  }
+}
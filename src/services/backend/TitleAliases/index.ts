@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api/core';
+
+import { Provider } from '@/types/List';
+import { TitleAlias } from '../types';
+
+export class TitleAliasService {
+  static async list(): Promise<TitleAlias[]> {
+    return invoke<TitleAlias[]>('list_title_aliases');
+  }
+
+  static async add(alias: TitleAlias): Promise<TitleAlias[]> {
+    return invoke<TitleAlias[]>('add_title_alias', { alias });
+  }
+
+  static async delete(
+    providerId: Provider,
+    title: string,
+    season?: number | null
+  ): Promise<TitleAlias[]> {
+    return invoke<TitleAlias[]>('delete_title_alias', {
+      providerId,
+      title,
+      season: season ?? null
+    });
+  }
+
+  static async import(contents: string): Promise<TitleAlias[]> {
+    return invoke<TitleAlias[]>('import_title_aliases', { contents });
+  }
+
+  static async export(): Promise<string> {
+    return invoke<string>('export_title_aliases');
+  }
+}
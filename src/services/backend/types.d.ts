@@ -18,6 +18,7 @@ interface BaseListUpdateRequest {
   listType: 'anime' | 'manga';
   entryId?: number;
   mediaId?: number;
+  mirrorFrom?: Provider;
 }
 
 export interface AnimeListUpdateRequest
@@ -32,22 +33,213 @@ export interface MangaListUpdateRequest
 
 export type ListUpdateRequest = AnimeListUpdateRequest | MangaListUpdateRequest;
 
-export type SupportedPlayer = 'mpv' | 'mpc-hc' | 'mpc-be';
+export interface ListUpdatesConfig {
+  mirrorToLinkedProviders: boolean;
+}
+
+export interface DeadLetteredListUpdate {
+  id: number;
+  request: ListUpdateRequest;
+  error: string;
+  attempts: number;
+  failedAt: number;
+}
+
+export interface ListUpdateQueueItem {
+  id: number;
+  request: ListUpdateRequest;
+  attempts: number;
+  lastError: string | null;
+}
+
+export interface ListUpdateQueueSnapshot {
+  pending: ListUpdateQueueItem[];
+  inFlight: ListUpdateQueueItem | null;
+  recentlyFailed: DeadLetteredListUpdate[];
+}
+
+export interface ListUpdateEvent {
+  id: number;
+  request: ListUpdateRequest;
+  attempt: number;
+  error: string | null;
+  retryInMs: number | null;
+}
+
+export type SupportedPlayer = 'mpv' | 'mpc-hc' | 'mpc-be' | 'vlc' | 'mpris' | 'custom';
+
+export type EpisodeKind =
+  | 'regular'
+  | 'special'
+  | 'ova'
+  | 'opening-ending'
+  | 'preview'
+  | 'movie';
+
+export type PlayerOptionStyle = '-' | '--' | '/';
+
+export interface CustomPlayerDefinition {
+  id: string;
+  processAliases: string[];
+  optionStyle?: PlayerOptionStyle;
+  mediaArgIndex?: number | null;
+  mediaArgPattern?: string | null;
+}
+
+export interface MpvIpcConfig {
+  enabled: boolean;
+  socketPath?: string | null;
+}
+
+export interface VlcHttpConfig {
+  enabled: boolean;
+  port?: number | null;
+  password?: string | null;
+}
+
+export interface MpcWebConfig {
+  enabled: boolean;
+  port?: number | null;
+  mpcBePort?: number | null;
+}
+
+export interface DetectionSourcesConfig {
+  mpvIpc: MpvIpcConfig;
+  vlcHttp: VlcHttpConfig;
+  mpcWeb: MpcWebConfig;
+}
 
 export interface DetectPlayingAnimeRequest {
   players?: SupportedPlayer[];
+  sources?: DetectionSourcesConfig;
+  customPlayers?: CustomPlayerDefinition[];
+}
+
+export type ListMatchSource = 'list' | 'search' | 'alias';
+
+export interface ListEntryMatch {
+  providerId: Provider;
+  mediaId: number;
+  entryId: number | null;
+  title: string;
+  totalEpisodes: number;
+  episode: number | null;
+  confidence: number;
+  source: ListMatchSource;
+}
+
+export interface TitleAlias {
+  providerId: Provider;
+  title: string;
+  season?: number | null;
+  episodeOffset?: number;
+  mediaId: number;
+  mediaTitle?: string | null;
 }
 
 export interface AnimePlaybackDetection {
   player: SupportedPlayer;
+  customPlayerId: string | null;
+  animeTitle: string;
+  episode: number | null;
+  season: number | null;
+  kind: EpisodeKind;
+  mediaPath: string | null;
+  mediaTitle: string | null;
+  positionSeconds: number | null;
+  durationSeconds: number | null;
+  paused: boolean | null;
+  listMatch: ListEntryMatch | null;
+}
+
+export interface ParsedRelease {
   animeTitle: string;
   episode: number | null;
+  episodeEnd: number | null;
+  kind: EpisodeKind;
+  season: number | null;
+  part: number | null;
+  version: number | null;
+  episodeTitle: string | null;
+  releaseGroup: string | null;
+  resolution: string | null;
+  videoCodecs: string[];
+  audioCodecs: string[];
+  source: string | null;
+  checksum: string | null;
+  languages: string[];
+  year: number | null;
+}
+
+export type MediaSourceKind =
+  | 'playerInterface'
+  | 'commandLine'
+  | 'openFile'
+  | 'playlist'
+  | 'mpris';
+
+export interface DetectionCandidate {
+  processId: number;
+  source: MediaSourceKind;
+  detection: AnimePlaybackDetection;
+}
+
+export type SelectionPolicy =
+  | 'stickToCurrent'
+  | 'mostRecent'
+  | 'focusedWindow'
+  | 'pinned'
+  | 'playerPriority';
+
+// Custom players are ranked by `customPlayerId`; without one the entry ranks
+// every custom player.
+export interface PlayerPriorityEntry {
+  player: SupportedPlayer;
+  customPlayerId?: string;
+}
+
+export interface DetectionSelectionConfig {
+  policy: SelectionPolicy;
+  pinnedProcessId?: number | null;
+  playerPriority?: PlayerPriorityEntry[];
+}
+
+export interface DetectionStabilityConfig {
+  confirmCycles: number;
+  confirmMs: number;
+  closeGraceMs: number;
+}
+
+export type TransitionReason =
+  | 'started'
+  | 'episodeChanged'
+  | 'playerSwitched'
+  | 'playerClosed'
+  | 'observerDisabled';
+
+export interface PlaybackObserverEvent extends AnimePlaybackDetection {
+  reason: TransitionReason;
+}
+
+export interface PlaybackProgress {
+  positionSeconds: number;
+  durationSeconds: number | null;
+  percent: number | null;
+  paused: boolean;
+}
+
+export interface PlaybackStatusEvent extends AnimePlaybackDetection {
+  progress: PlaybackProgress;
 }
 
 export interface ConfigurePlaybackObserverRequest {
   enabled?: boolean;
   players?: SupportedPlayer[];
   pollIntervalMs?: number;
+  sources?: DetectionSourcesConfig;
+  customPlayers?: CustomPlayerDefinition[];
+  selection?: DetectionSelectionConfig;
+  stability?: DetectionStabilityConfig;
 }
 
 export interface PlaybackObserverSnapshot {
@@ -55,12 +247,39 @@ export interface PlaybackObserverSnapshot {
   lastObserved: AnimePlaybackDetection | null;
   observedProcessId: number | null;
   observedPlayer: SupportedPlayer | null;
+  candidates: DetectionCandidate[];
+  selection: DetectionSelectionConfig;
+  stability: DetectionStabilityConfig;
+  lastTransition: TransitionReason | null;
+  progress: PlaybackProgress | null;
   selectedPlayers: SupportedPlayer[];
+  sources: DetectionSourcesConfig;
+  customPlayers: CustomPlayerDefinition[];
   enabled: boolean;
   pollIntervalMs: number;
   lastError: string | null;
 }
 
+export interface ScrobblerConfig {
+  enabled: boolean;
+  completionPercent: number;
+  watchedMinutes: number;
+  confirmUpdates: boolean;
+}
+
+export interface Scrobble {
+  id: number;
+  animeId: number;
+  animeTitle: string;
+  episode: number;
+  update: AnimeListUpdateRequest;
+}
+
+export interface ScrobblerSnapshot {
+  config: ScrobblerConfig;
+  pendingConfirmations: Scrobble[];
+}
+
 export interface DiscordPresenceButton {
   label: string;
   url: string;
@@ -1,7 +1,9 @@
 import { createTauriStore } from '@tauri-store/zustand';
 import { create } from 'zustand';
 
+import { AnimeListService } from '@/services/backend/AnimeList';
 import { PlayerDetectionService } from '@/services/backend/PlayerDetection';
+import { ScrobblerService } from '@/services/backend/Scrobbler';
 import { ConfigurationState } from '@/types/Configuration';
 import { ConfigMenuStep } from '@/types/Navigation';
 
@@ -50,8 +52,16 @@ export const useConfigMenuStore = create<ConfigMenuStore>((set) => ({
     set(() => {
       PlayerDetectionService.configurePlaybackObserver({
         enabled: configuration.detection.playerDetectionEnabled,
-        players: configuration.detection.enabledPlayers
+        players: configuration.detection.enabledPlayers,
+        selection: configuration.detection.selection,
+        stability: configuration.detection.stability
       });
+      if (configuration.detection.scrobbler) {
+        ScrobblerService.configure(configuration.detection.scrobbler);
+      }
+      if (configuration.listUpdates) {
+        AnimeListService.configure(configuration.listUpdates);
+      }
       return { configuration };
     })
 }));
@@ -1,6 +1,8 @@
 import { createTauriStore } from '@tauri-store/zustand';
 import { create } from 'zustand';
 
+import { TitleAliasService } from '@/services/backend/TitleAliases';
+import { TitleAlias } from '@/services/backend/types';
 import { Provider } from '@/types/List';
 
 export type AliasesByAnimeId = Record<string, string[]>;
@@ -16,7 +18,9 @@ const hasProviderBuckets = (
   );
 };
 
-const toProviderAliases = (aliases: PersistedAliases): AliasesByProvider => {
+export const toProviderAliases = (
+  aliases: PersistedAliases
+): AliasesByProvider => {
   if (hasProviderBuckets(aliases)) {
     return aliases;
   }
@@ -30,69 +34,60 @@ const toProviderAliases = (aliases: PersistedAliases): AliasesByProvider => {
   };
 };
 
-const getAliasesForProvider = (
-  aliases: PersistedAliases,
+const groupAliasesByAnimeId = (
+  aliases: TitleAlias[],
   provider: Provider
 ): AliasesByAnimeId => {
-  if (hasProviderBuckets(aliases)) {
-    return aliases[provider] || {};
-  }
-
-  if (provider === Provider.MY_ANIME_LIST) {
-    return aliases as AliasesByAnimeId;
-  }
-
-  return {};
+  return aliases
+    .filter((alias) => alias.providerId === provider)
+    .reduce<AliasesByAnimeId>((aliasesByAnimeId, alias) => {
+      const key = alias.mediaId.toString();
+      aliasesByAnimeId[key] = [...(aliasesByAnimeId[key] || []), alias.title];
+      return aliasesByAnimeId;
+    }, {});
 };
 
+// The title aliases saved in the backend, which detection matches against.
+// Kept in memory only; the backend store is the one that persists them.
 type NowPlayingAliasesStore = {
-  aliasesByAnimeId: PersistedAliases;
+  aliases: TitleAlias[];
   getAliasesByProvider: (provider: Provider) => AliasesByAnimeId;
-  addAlias: (provider: Provider, animeId: number, title: string) => void;
+  loadAliases: () => Promise<void>;
+  addAlias: (alias: TitleAlias) => Promise<void>;
 };
 
 export const useNowPlayingAliasesStore = create<NowPlayingAliasesStore>(
   (set, get) => ({
-    aliasesByAnimeId: {},
+    aliases: [],
     getAliasesByProvider: (provider) =>
-      getAliasesForProvider(get().aliasesByAnimeId, provider),
-    addAlias: (provider: Provider, animeId: number, title: string) =>
-      set((state) => {
-        const trimmedTitle = title.trim();
-        if (!trimmedTitle) {
-          return {};
-        }
-
-        const aliasesByProvider = toProviderAliases(state.aliasesByAnimeId);
-        const providerAliases = aliasesByProvider[provider] || {};
-        const key = animeId.toString();
-        const existingAliases = providerAliases[key] || [];
-        const alreadyExists = existingAliases.some(
-          (alias) => alias.toLowerCase().trim() === trimmedTitle.toLowerCase()
-        );
-
-        if (alreadyExists) {
-          return {};
-        }
-
-        return {
-          aliasesByAnimeId: {
-            ...aliasesByProvider,
-            [provider]: {
-              ...providerAliases,
-              [key]: [...existingAliases, trimmedTitle]
-            }
-          }
-        };
-      })
+      groupAliasesByAnimeId(get().aliases, provider),
+    loadAliases: async () => {
+      set({ aliases: await TitleAliasService.list() });
+    },
+    addAlias: async (alias) => {
+      set({ aliases: await TitleAliasService.add(alias) });
+    }
   })
 );
 
-export const tauriHandler = createTauriStore(
+// Aliases used to be saved by the frontend alone. They are only read once to
+// move them to the backend; see `useNowPlayingAliasesMigration`.
+type LegacyNowPlayingAliasesStore = {
+  aliasesByAnimeId: PersistedAliases;
+  clearAliases: () => void;
+};
+
+export const useLegacyNowPlayingAliasesStore =
+  create<LegacyNowPlayingAliasesStore>((set) => ({
+    aliasesByAnimeId: {},
+    clearAliases: () => set({ aliasesByAnimeId: {} })
+  }));
+
+export const legacyTauriHandler = createTauriStore(
   'nowPlayingAliases',
-  useNowPlayingAliasesStore,
+  useLegacyNowPlayingAliasesStore,
   {
-    autoStart: true,
+    autoStart: false,
     saveOnChange: true,
     filterKeys: ['aliasesByAnimeId'],
     filterKeysStrategy: 'pick'
@@ -1,8 +1,17 @@
-import { SupportedPlayer } from '@/services/backend/types';
+import {
+  DetectionSelectionConfig,
+  DetectionStabilityConfig,
+  ListUpdatesConfig,
+  ScrobblerConfig,
+  SupportedPlayer
+} from '@/services/backend/types';
 
 export interface DetectionConfig {
   playerDetectionEnabled: boolean;
   enabledPlayers: SupportedPlayer[];
+  selection?: DetectionSelectionConfig;
+  stability?: DetectionStabilityConfig;
+  scrobbler?: ScrobblerConfig;
 }
 
 export interface ApplicationConfig {
@@ -24,4 +33,5 @@ export interface ConfigurationState {
   detection: DetectionConfig;
   application: ApplicationConfig;
   sharing: SharingConfig;
+  listUpdates?: ListUpdatesConfig;
 }